@@ -0,0 +1,380 @@
+//! Procedural versions of `array_refs!` and `mut_array_refs!`, which
+//! accept the same input but report mistakes in it with spanned errors.
+
+use proc_macro::{Delimiter, Group, Span, TokenStream, TokenTree};
+
+use {is_punct, Error};
+
+/// The parsed input `arr, pre, ... ; .. ; post, ...` or `arr, len, ...`.
+struct Input {
+    arr: Vec<TokenTree>,
+    /// The leading lengths, in order, including those within groups.
+    pre: Vec<Vec<TokenTree>>,
+    /// How the leading lengths are grouped.
+    shape: Vec<Shape>,
+    /// The trailing lengths, if there is a `..` remainder.
+    post: Option<Vec<Vec<TokenTree>>>,
+}
+
+/// A segment, which is one length, a length to skip, or a
+/// parenthesized group.
+enum Shape {
+    Len,
+    Skip,
+    Group(Vec<Shape>),
+}
+
+pub fn expand(input: TokenStream, mutable: bool) -> Result<TokenStream, Error> {
+    let input = parse(input)?;
+    let arr_span = input.arr[0].span();
+
+    let mut out = TokenStream::new();
+    let lens: Vec<_> = input
+        .pre
+        .iter()
+        .chain(input.post.iter().flatten())
+        .collect();
+    for (i, len) in lens.iter().enumerate() {
+        // A separate constant for each length makes a length that is
+        // not a constant an error at that length.
+        out.extend(code(&format!("const __ARRAYREF_LEN_{}: usize =", i)));
+        out.extend(len.iter().cloned());
+        out.extend(code(";"));
+    }
+    let names: Vec<String> = (0..lens.len())
+        .map(|i| format!("__ARRAYREF_LEN_{}", i))
+        .collect();
+    let (pre_names, post_names) = names.split_at(input.pre.len());
+    let sum = |names: &[String]| {
+        if names.is_empty() {
+            "0".to_string()
+        } else {
+            names.join(" + ")
+        }
+    };
+
+    let (reference, ptr, as_ptr) = if mutable {
+        ("&mut ", "*mut", "as_mut_ptr")
+    } else {
+        ("&", "*const", "as_ptr")
+    };
+    let array = |n: &String| format!("{reference}[T; {n}], ", reference = reference, n = n);
+    let take = |n: &String| {
+        format!(
+            "{{ let aref = {reference}*(p as {ptr} [T; {n}]); p = p.add({n}); aref }},",
+            reference = reference,
+            ptr = ptr,
+            n = n,
+        )
+    };
+    let take_at = |i: usize| {
+        format!(
+            "{reference}*(a.{as_ptr}().add({offset}) as {ptr} [T; {n}]),",
+            reference = reference,
+            as_ptr = as_ptr,
+            offset = sum(&names[..i]),
+            ptr = ptr,
+            n = names[i],
+        )
+    };
+    let constness = if mutable { "" } else { "const " };
+
+    let function = match input.post {
+        None => format!(
+            "{constness}unsafe fn as_arrays<T>(a: {reference}[T; {total}]) -> ({arrays}) {{
+                ({take})
+            }}",
+            constness = constness,
+            reference = reference,
+            total = sum(&names),
+            arrays = render(&input.shape, &mut 0, &|i| array(&names[i])),
+            take = render(&input.shape, &mut 0, &take_at),
+        ),
+        Some(_) => format!(
+            "{constness}unsafe fn as_arrays<T>(a: {reference}[T])
+                -> ({pre_arrays} {reference}[T], {post_arrays})
+            {{
+                const PRE_LEN: usize = {pre_len};
+                const POST_LEN: usize = {post_len};
+                assert!(
+                    a.len() >= PRE_LEN + POST_LEN,
+                    \"the input is shorter than the fixed-length segments\",
+                );
+                let var_len = a.len() - PRE_LEN - POST_LEN;
+                let (pre, rest) = a.{split_at}(PRE_LEN);
+                let (middle, post) = rest.{split_at}(var_len);
+                let mut p = pre.{as_ptr}();
+                let pre = ({pre_take});
+                p = post.{as_ptr}();
+                let post = ({post_take});
+                ({pre_items} middle, {post_items})
+            }}",
+            constness = constness,
+            reference = reference,
+            pre_arrays = concat(pre_names, array),
+            post_arrays = concat(post_names, array),
+            pre_len = sum(pre_names),
+            post_len = sum(post_names),
+            split_at = if mutable { "split_at_mut" } else { "split_at" },
+            as_ptr = as_ptr,
+            pre_take = concat(pre_names, take),
+            post_take = concat(post_names, take),
+            pre_items = (0..pre_names.len())
+                .map(|i| format!("pre.{}, ", i))
+                .collect::<String>(),
+            post_items = (0..post_names.len())
+                .map(|i| format!("post.{}, ", i))
+                .collect::<String>(),
+        ),
+    };
+    out.extend(code(&format!(
+        "#[inline]
+        #[allow(unused_assignments, unused_mut, unused_variables)]
+        #[allow(clippy::mixed_read_write_in_expression)]
+        {}",
+        function
+    )));
+
+    let arr: TokenStream = input.arr.into_iter().collect();
+    let input_binding = if input.post.is_none() {
+        // Checking the length through a trait lets the error name both
+        // lengths, where a mismatched argument to `as_arrays` would
+        // just be a type error pointing into this expansion.
+        out.extend(code(&format!(
+            "#[diagnostic::on_unimplemented(
+                message = \"sum of segment lengths ({{TOTAL}}) does not match the length \
+                           of the input `{{Self}}`\",
+                label = \"this is not {{TOTAL}} elements long\"
+            )]
+            trait SegmentsFit<const TOTAL: usize> {{
+                type Elem;
+            }}
+            impl<T, const N: usize> SegmentsFit<N> for [T; N] {{
+                type Elem = T;
+            }}
+            #[inline]
+            {constness}fn exact<A: ?Sized + SegmentsFit<L>, const L: usize>(a: {reference}A)
+                -> {reference}[A::Elem; L]
+            {{
+                unsafe {{ {reference}*(a as {ptr} A as {ptr} [A::Elem; L]) }}
+            }}",
+            constness = constness,
+            reference = reference,
+            ptr = ptr,
+        )));
+        let mut call = respan(
+            code(&format!("exact::<_, {{ {} }}>", sum(&names))),
+            arr_span,
+        );
+        let mut args = Group::new(Delimiter::Parenthesis, arr);
+        args.set_span(arr_span);
+        call.extend(Some(TokenTree::from(args)));
+        call
+    } else {
+        arr
+    };
+    out.extend(code("let input ="));
+    out.extend(input_binding);
+    out.extend(code(
+        ";
+        #[allow(unused_unsafe)]
+        unsafe { as_arrays(input) }",
+    ));
+    Ok(Some(TokenTree::from(Group::new(Delimiter::Brace, out)))
+        .into_iter()
+        .collect())
+}
+
+fn parse(input: TokenStream) -> Result<Input, Error> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let semis: Vec<usize> = (0..tokens.len())
+        .filter(|&i| is_punct(tokens.get(i), ';'))
+        .collect();
+    let (head, mut post) = match semis.len() {
+        0 => (&tokens[..], None),
+        2 if is_dot_dot(&tokens[semis[0] + 1..semis[1]]) => {
+            let post = split_lengths(&tokens[semis[1] + 1..])?;
+            (&tokens[..semis[0]], Some(post))
+        }
+        _ => {
+            return Err(Error::new(
+                tokens[semis[0]].span(),
+                "expected the lengths to be separated from the trailing lengths by `; .. ;`",
+            ))
+        }
+    };
+    let mut parts = split_lengths(head)?;
+    if parts.is_empty() || parts[0].is_empty() {
+        return Err(Error::new(
+            head.first().map_or(Span::call_site(), |t| t.span()),
+            "expected the array reference to split, followed by the segment lengths",
+        ));
+    }
+    let arr = parts.remove(0);
+    if post.is_none() {
+        // A `..` among the lengths, as in `arr, 8, .., 4`, is the same
+        // as `arr, 8; .. ; 4`.
+        let mut dot_dots = parts.iter().enumerate().filter(|(_, p)| is_dot_dot(p));
+        if let Some((i, _)) = dot_dots.next() {
+            if let Some((_, extra)) = dot_dots.next() {
+                return Err(Error::new(
+                    extra[0].span(),
+                    "expected at most one `..` remainder",
+                ));
+            }
+            post = Some(parts.split_off(i + 1));
+            parts.pop();
+        }
+    }
+    let mut pre = Vec::new();
+    let shape = if post.is_some() {
+        // Groups and skips are only supported without a remainder, so
+        // these are all plain lengths.
+        let mut lens = parts.iter().chain(post.iter().flatten());
+        if let Some(skip) = lens.find(|len| is_underscore(len.first())) {
+            return Err(Error::new(
+                skip[0].span(),
+                "`_` cannot be used with a `..` remainder",
+            ));
+        }
+        pre = parts;
+        pre.iter().map(|_| Shape::Len).collect()
+    } else {
+        parts
+            .into_iter()
+            .map(|part| parse_segment(part, &mut pre))
+            .collect::<Result<_, _>>()?
+    };
+    Ok(Input {
+        arr,
+        pre,
+        shape,
+        post,
+    })
+}
+
+/// Parses one segment, adding the lengths in it to `lens`.  As with
+/// tuples, a parenthesized list with a comma in it, such as `(4, 4)`
+/// or `(4,)`, is a group, while `(4)` is just a length.  A length
+/// after `_` is skipped.
+fn parse_segment(
+    mut tokens: Vec<TokenTree>,
+    lens: &mut Vec<Vec<TokenTree>>,
+) -> Result<Shape, Error> {
+    if is_underscore(tokens.first()) {
+        if tokens.len() == 1 {
+            return Err(Error::new(
+                tokens[0].span(),
+                "expected the length to skip after `_`",
+            ));
+        }
+        tokens.remove(0);
+        lens.push(tokens);
+        return Ok(Shape::Skip);
+    }
+    if let [TokenTree::Group(ref group)] = tokens[..] {
+        if group.delimiter() == Delimiter::Parenthesis {
+            let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+            let parts = split_lengths(&inner)?;
+            if parts.len() > 1 || is_punct(inner.last(), ',') || is_underscore(inner.first()) {
+                return parts
+                    .into_iter()
+                    .map(|part| parse_segment(part, lens))
+                    .collect::<Result<_, _>>()
+                    .map(Shape::Group);
+            }
+        }
+    }
+    lens.push(tokens);
+    Ok(Shape::Len)
+}
+
+/// Renders each length in `shape` with `len`, given the index of the
+/// length counting from `next`, and each group as a tuple of its
+/// contents.  Skipped lengths are counted but not rendered.
+fn render<F: Fn(usize) -> String>(shape: &[Shape], next: &mut usize, len: &F) -> String {
+    shape
+        .iter()
+        .map(|segment| match *segment {
+            Shape::Len => {
+                *next += 1;
+                len(*next - 1)
+            }
+            Shape::Skip => {
+                *next += 1;
+                String::new()
+            }
+            Shape::Group(ref inner) => format!("({}), ", render(inner, next, len)),
+        })
+        .collect()
+}
+
+/// Splits comma-separated lengths, allowing a trailing comma, and
+/// rejecting empty lengths between commas.
+pub fn split_lengths(tokens: &[TokenTree]) -> Result<Vec<Vec<TokenTree>>, Error> {
+    let mut parts: Vec<Vec<TokenTree>> = vec![Vec::new()];
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        // In an expression, generic arguments always follow `::`, so
+        // any other `<` is a comparison or a shift.
+        if is_punct(Some(token), '<') && (depth > 0 || (i > 0 && is_punct(tokens.get(i - 1), ':')))
+        {
+            depth += 1;
+        } else if is_punct(Some(token), '>')
+            && depth > 0
+            && !is_punct(tokens.get(i.wrapping_sub(1)), '-')
+        {
+            depth -= 1;
+        } else if depth == 0 && is_punct(Some(token), ',') {
+            if parts.last().is_some_and(Vec::is_empty) && parts.len() > 1 {
+                return Err(Error::new(
+                    token.span(),
+                    "expected a segment length before `,`",
+                ));
+            }
+            parts.push(Vec::new());
+            continue;
+        }
+        parts.last_mut().unwrap().push(token.clone());
+    }
+    if parts.last().is_some_and(Vec::is_empty) {
+        parts.pop();
+    }
+    Ok(parts)
+}
+
+fn is_underscore(token: Option<&TokenTree>) -> bool {
+    match token {
+        Some(TokenTree::Ident(ident)) => ident.to_string() == "_",
+        _ => false,
+    }
+}
+
+fn is_dot_dot(tokens: &[TokenTree]) -> bool {
+    tokens.len() == 2 && is_punct(tokens.first(), '.') && is_punct(tokens.get(1), '.')
+}
+
+pub fn code(s: &str) -> TokenStream {
+    s.parse().expect("generated code should parse")
+}
+
+fn concat<F: Fn(&String) -> String>(names: &[String], f: F) -> String {
+    names.iter().map(f).collect()
+}
+
+/// Gives `tokens` the span `span`, so that errors in them point there.
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(ref group) = token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = respanned.into();
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
+}
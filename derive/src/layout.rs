@@ -0,0 +1,166 @@
+//! The procedural macro `layout!`, which names the offset and length
+//! of each field of a record by joining the record and field names.
+
+use proc_macro::{Delimiter, Group, Ident, Span, TokenStream, TokenTree};
+
+use array_refs::{code, split_lengths};
+use {is_punct, Error};
+
+/// One record: `#[attr] vis NAME = { FIELD: len, ... }`.
+struct Record {
+    attrs: Vec<TokenTree>,
+    vis: Vec<TokenTree>,
+    name: Ident,
+    fields: Vec<Field>,
+}
+
+/// One field: `#[attr] NAME: len`.
+struct Field {
+    attrs: Vec<TokenTree>,
+    name: Ident,
+    len: Vec<TokenTree>,
+}
+
+pub fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut out = TokenStream::new();
+    for record in parse(input)? {
+        let prefix = upper(&record.name);
+        let mut offset = "0".to_string();
+        for field in &record.fields {
+            let name = format!("{}_{}", prefix, upper(&field.name));
+            let macro_name = name.to_lowercase();
+            let attrs: TokenStream = field.attrs.iter().cloned().collect();
+            let vis: TokenStream = record.vis.iter().cloned().collect();
+
+            out.extend(attrs.clone());
+            out.extend(vis.clone());
+            out.extend(code(&format!("const {}_OFFSET: usize = {};", name, offset)));
+            out.extend(attrs.clone());
+            out.extend(vis);
+            out.extend(code(&format!("const {}_LEN: usize = ", name)));
+            out.extend(Some(TokenTree::from(Group::new(
+                Delimiter::Parenthesis,
+                field.len.iter().cloned().collect(),
+            ))));
+            out.extend(code(";"));
+            for (suffix, array_ref) in [("", "array_ref"), ("_mut", "array_mut_ref")] {
+                out.extend(attrs.clone());
+                out.extend(code(&format!(
+                    "#[allow(unused_macros)]
+                    macro_rules! {macro_name}{suffix} {{
+                        ($buf:expr) => {{
+                            ::arrayref::{array_ref}!($buf, {name}_OFFSET, {name}_LEN)
+                        }};
+                    }}",
+                    macro_name = macro_name,
+                    suffix = suffix,
+                    array_ref = array_ref,
+                    name = name,
+                )));
+            }
+            offset = format!("{name}_OFFSET + {name}_LEN", name = name);
+        }
+        out.extend(record.attrs);
+        out.extend(record.vis);
+        out.extend(code(&format!("const {}_SIZE: usize = {};", prefix, offset)));
+    }
+    Ok(out)
+}
+
+fn parse(input: TokenStream) -> Result<Vec<Record>, Error> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut records = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let attrs = take_attributes(&tokens, &mut i);
+        let vis_start = i;
+        if let Some(TokenTree::Ident(ident)) = tokens.get(i) {
+            if ident.to_string() == "pub" {
+                i += 1;
+                if let Some(TokenTree::Group(group)) = tokens.get(i) {
+                    if group.delimiter() == Delimiter::Parenthesis {
+                        i += 1;
+                    }
+                }
+            }
+        }
+        let vis = tokens[vis_start..i].to_vec();
+        let name = expect_ident(&tokens, i, "expected the name of a record")?;
+        i += 1;
+        if !is_punct(tokens.get(i), '=') {
+            return Err(error_at(&tokens, i, "expected `=` after the record name"));
+        }
+        i += 1;
+        let body = match tokens.get(i) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group,
+            _ => {
+                return Err(error_at(
+                    &tokens,
+                    i,
+                    "expected the fields of the record in braces",
+                ))
+            }
+        };
+        i += 1;
+        let body: Vec<TokenTree> = body.stream().into_iter().collect();
+        let fields = split_lengths(&body)?
+            .iter()
+            .map(|part| parse_field(part))
+            .collect::<Result<_, _>>()?;
+        records.push(Record {
+            attrs,
+            vis,
+            name,
+            fields,
+        });
+    }
+    Ok(records)
+}
+
+fn parse_field(tokens: &[TokenTree]) -> Result<Field, Error> {
+    let mut i = 0;
+    let attrs = take_attributes(tokens, &mut i);
+    let name = expect_ident(tokens, i, "expected the name of a field")?;
+    if !is_punct(tokens.get(i + 1), ':') || tokens.len() == i + 2 {
+        return Err(Error::new(
+            name.span(),
+            "expected the field name to be followed by `:` and its length",
+        ));
+    }
+    Ok(Field {
+        attrs,
+        name,
+        len: tokens[i + 2..].to_vec(),
+    })
+}
+
+/// Takes any `#[...]` attributes starting at `*i`.
+fn take_attributes(tokens: &[TokenTree], i: &mut usize) -> Vec<TokenTree> {
+    let start = *i;
+    while is_punct(tokens.get(*i), '#') {
+        *i += 2;
+    }
+    *i = (*i).min(tokens.len());
+    tokens[start..*i].to_vec()
+}
+
+fn expect_ident(tokens: &[TokenTree], i: usize, message: &str) -> Result<Ident, Error> {
+    match tokens.get(i) {
+        Some(TokenTree::Ident(ident)) => Ok(ident.clone()),
+        _ => Err(error_at(tokens, i, message)),
+    }
+}
+
+fn error_at(tokens: &[TokenTree], i: usize, message: &str) -> Error {
+    let span = tokens
+        .get(i)
+        .or_else(|| tokens.last())
+        .map_or(Span::call_site(), TokenTree::span);
+    Error::new(span, message)
+}
+
+/// The name of `ident` in upper case, without any `r#`.
+fn upper(ident: &Ident) -> String {
+    let name = ident.to_string();
+    name.trim_start_matches("r#").to_uppercase()
+}
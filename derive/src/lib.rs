@@ -0,0 +1,409 @@
+//! The procedural macros of arrayref: the `ArrayRefs` derive, the
+//! procedural versions of `array_refs!` and `mut_array_refs!`, and
+//! `layout!`.  This crate is not meant to be used directly: enable the
+//! `derive`, `proc-macros` or `layout` feature of arrayref, which
+//! re-exports these along with the rest of the crate.
+
+extern crate proc_macro;
+
+mod array_refs;
+mod layout;
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Derives `from_slice` (or, for a struct of mutable references,
+/// `from_mut_slice`) for a struct whose fields are array references,
+/// optionally followed by a final slice field for the remainder.  See
+/// the documentation of `arrayref::ArrayRefs`.
+#[proc_macro_derive(ArrayRefs)]
+pub fn derive_array_refs(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+/// `array_refs!`, with errors in its input reported at the mistake.
+/// See the documentation of `arrayref::array_refs`.
+#[proc_macro]
+pub fn array_refs(input: TokenStream) -> TokenStream {
+    match array_refs::expand(input, false) {
+        Ok(output) => output,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+/// `mut_array_refs!`, with errors in its input reported at the
+/// mistake.  See the documentation of `arrayref::mut_array_refs`.
+#[proc_macro]
+pub fn mut_array_refs(input: TokenStream) -> TokenStream {
+    match array_refs::expand(input, true) {
+        Ok(output) => output,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+/// Defines the offset and length constants and the accessor macros
+/// of the fields of a record.  See the documentation of
+/// `arrayref::layout`.
+#[proc_macro]
+pub fn layout(input: TokenStream) -> TokenStream {
+    match layout::expand(input) {
+        Ok(output) => output,
+        Err(error) => error.into_compile_error(),
+    }
+}
+
+/// A compile error reported at a particular span of the input.
+struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    fn new(span: Span, message: &str) -> Self {
+        Error {
+            span,
+            message: message.to_string(),
+        }
+    }
+
+    /// Expands to `compile_error! { "..." }` spanned at the error,
+    /// which works both where an item and where an expression is
+    /// expected.
+    fn into_compile_error(self) -> TokenStream {
+        let mut message = Literal::string(&self.message);
+        message.set_span(self.span);
+        let mut bang = Punct::new('!', Spacing::Alone);
+        bang.set_span(self.span);
+        let mut args = Group::new(Delimiter::Brace, TokenTree::from(message).into());
+        args.set_span(self.span);
+        vec![
+            TokenTree::from(Ident::new("compile_error", self.span)),
+            bang.into(),
+            args.into(),
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// One field of the struct being derived.
+struct Field {
+    name: Ident,
+    span: Span,
+    mutable: bool,
+    lifetime: Option<String>,
+    element: String,
+    /// The length of an array field, or `None` for a slice.
+    len: Option<String>,
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut i = skip_attributes_and_visibility(&tokens, 0);
+    match tokens.get(i) {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => i += 1,
+        Some(token) => {
+            return Err(Error::new(
+                token.span(),
+                "ArrayRefs can only be derived for structs",
+            ))
+        }
+        None => return Err(Error::new(Span::call_site(), "expected a struct")),
+    }
+    let name = match tokens.get(i) {
+        Some(TokenTree::Ident(ident)) => ident.clone(),
+        _ => return Err(Error::new(Span::call_site(), "expected a struct name")),
+    };
+    i += 1;
+
+    let mut impl_generics = Vec::new();
+    let mut type_generics = Vec::new();
+    if is_punct(tokens.get(i), '<') {
+        let end = matching_angle(&tokens, i)
+            .ok_or_else(|| Error::new(name.span(), "unterminated generic parameters"))?;
+        for param in split_top_level(&tokens[i + 1..end], ',') {
+            if param.is_empty() {
+                continue;
+            }
+            let declared = match param.iter().position(|t| is_punct(Some(t), '=')) {
+                Some(default) => &param[..default],
+                None => param,
+            };
+            impl_generics.push(stringify(declared));
+            type_generics.push(generic_argument(param));
+        }
+        i = end + 1;
+    }
+
+    let mut where_clause = Vec::new();
+    let body = loop {
+        match tokens.get(i) {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+                break group.stream();
+            }
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                return Err(Error::new(
+                    group.span(),
+                    "ArrayRefs needs a struct with named fields",
+                ))
+            }
+            Some(token) => where_clause.push(token.clone()),
+            None => {
+                return Err(Error::new(
+                    name.span(),
+                    "ArrayRefs needs a struct with named fields",
+                ))
+            }
+        }
+        i += 1;
+    };
+
+    let body: Vec<TokenTree> = body.into_iter().collect();
+    let mut fields = Vec::new();
+    for field in split_top_level(&body, ',') {
+        if !field.is_empty() {
+            fields.push(parse_field(field)?);
+        }
+    }
+    let first = match fields.first() {
+        Some(first) => first,
+        None => {
+            return Err(Error::new(
+                name.span(),
+                "ArrayRefs needs at least one field",
+            ))
+        }
+    };
+    for (n, field) in fields.iter().enumerate() {
+        if field.mutable != first.mutable {
+            return Err(Error::new(
+                field.span,
+                "the fields must be either all shared references or all mutable references",
+            ));
+        }
+        if field.len.is_none() && n + 1 != fields.len() {
+            return Err(Error::new(field.span, "only the last field can be a slice"));
+        }
+    }
+    let lifetime = match first.lifetime {
+        Some(ref lifetime) => lifetime.clone(),
+        None => {
+            return Err(Error::new(
+                first.span,
+                "the fields must borrow with a named lifetime, as in `&'a [u8; 4]`",
+            ))
+        }
+    };
+
+    let (constructor, reference, cursor, take, rest) = if first.mutable {
+        (
+            "from_mut_slice",
+            "mut ",
+            "ArrayCursorMut",
+            "take_mut",
+            "into_rest",
+        )
+    } else {
+        ("from_slice", "", "ArrayCursor", "take", "rest")
+    };
+    let mut len = String::from("0");
+    let mut initializers = String::new();
+    for field in &fields {
+        match field.len {
+            Some(ref n) => {
+                len += &format!(" + ({})", n);
+                initializers += &format!("{}: cursor.{}(), ", field.name, take);
+            }
+            None => initializers += &format!("{}: cursor.{}(), ", field.name, rest),
+        }
+    }
+    let generics = |params: &[String]| {
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        }
+    };
+    let output = format!(
+        "impl{impl_generics} {name}{type_generics} {where_clause} {{
+            /// Splits `slice` into the fields of this struct, in order,
+            /// after checking once that it is long enough for them all.
+            #[inline]
+            pub fn {constructor}(slice: &{lifetime} {reference}[{element}])
+                -> ::arrayref::__private::Result<Self, ::arrayref::LengthError>
+            {{
+                let len: usize = {len};
+                if slice.len() < len {{
+                    return ::arrayref::__private::Err(
+                        ::arrayref::__private::length_error(len, slice.len()),
+                    );
+                }}
+                #[allow(unused_mut)]
+                let mut cursor = ::arrayref::{cursor}::new(slice);
+                ::arrayref::__private::Ok({name} {{ {initializers} }})
+            }}
+        }}",
+        impl_generics = generics(&impl_generics),
+        name = name,
+        type_generics = generics(&type_generics),
+        where_clause = stringify(&where_clause),
+        constructor = constructor,
+        lifetime = lifetime,
+        reference = reference,
+        element = first.element,
+        len = len,
+        cursor = cursor,
+        initializers = initializers,
+    );
+    output
+        .parse()
+        .map_err(|_| Error::new(name.span(), "ArrayRefs generated invalid code"))
+}
+
+/// Parses `name: &'a [T; N]`, `name: &'a mut [T]` and the like.
+fn parse_field(tokens: &[TokenTree]) -> Result<Field, Error> {
+    let i = skip_attributes_and_visibility(tokens, 0);
+    let name = match tokens.get(i) {
+        Some(TokenTree::Ident(ident)) => ident.clone(),
+        Some(token) => return Err(Error::new(token.span(), "expected a field name")),
+        None => return Err(Error::new(Span::call_site(), "expected a field name")),
+    };
+    let ty = &tokens[(i + 2).min(tokens.len())..];
+    let span = ty.first().map_or(name.span(), |t| t.span());
+    let error = || {
+        Error::new(
+            span,
+            &format!(
+                "field `{}` must be an array reference, as in `&'a [u8; 4]`, \
+                 or, if it is the last field, a slice, as in `&'a [u8]`",
+                name
+            ),
+        )
+    };
+
+    let mut j = 0;
+    if !is_punct(ty.get(j), '&') {
+        return Err(error());
+    }
+    j += 1;
+    let mut lifetime = None;
+    if is_punct(ty.get(j), '\'') {
+        lifetime = Some(stringify(&ty[j..j + 2]));
+        j += 2;
+    }
+    let mut mutable = false;
+    if let Some(TokenTree::Ident(ident)) = ty.get(j) {
+        if ident.to_string() == "mut" {
+            mutable = true;
+            j += 1;
+        }
+    }
+    let inner: Vec<TokenTree> = match ty.get(j) {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+            group.stream().into_iter().collect()
+        }
+        _ => return Err(error()),
+    };
+    if j + 1 != ty.len() {
+        return Err(error());
+    }
+    let mut parts = split_top_level(&inner, ';');
+    let element = stringify(parts.remove(0));
+    let len = match parts.len() {
+        0 => None,
+        1 => Some(stringify(parts[0])),
+        _ => return Err(error()),
+    };
+    Ok(Field {
+        name,
+        span,
+        mutable,
+        lifetime,
+        element,
+        len,
+    })
+}
+
+/// Skips any `#[...]` attributes followed by a `pub` or `pub(...)`.
+fn skip_attributes_and_visibility(tokens: &[TokenTree], mut i: usize) -> usize {
+    while is_punct(tokens.get(i), '#') {
+        i += 2;
+    }
+    if let Some(TokenTree::Ident(ident)) = tokens.get(i) {
+        if ident.to_string() == "pub" {
+            i += 1;
+            if let Some(TokenTree::Group(group)) = tokens.get(i) {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    i += 1;
+                }
+            }
+        }
+    }
+    i
+}
+
+/// The argument naming a generic parameter: `'a` for `'a: 'b`, `T` for
+/// `T: Copy` and `N` for `const N: usize`.
+fn generic_argument(param: &[TokenTree]) -> String {
+    if is_punct(param.first(), '\'') {
+        return stringify(&param[..2]);
+    }
+    let start = match param.first() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "const" => 1,
+        _ => 0,
+    };
+    stringify(&param[start..start + 1])
+}
+
+/// The index of the `>` closing the `<` at `open`.
+fn matching_angle(tokens: &[TokenTree], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if is_punct(Some(token), '<') {
+            depth += 1;
+        } else if is_punct(Some(token), '>') && !follows_dash(tokens, i) {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+/// Splits `tokens` at each `separator` that is not inside angle brackets.
+fn split_top_level(tokens: &[TokenTree], separator: char) -> Vec<&[TokenTree]> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        if is_punct(Some(token), '<') {
+            depth += 1;
+        } else if is_punct(Some(token), '>') && !follows_dash(tokens, i) {
+            depth = depth.saturating_sub(1);
+        } else if depth == 0 && is_punct(Some(token), separator) {
+            parts.push(&tokens[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+/// Whether the `>` at `i` is part of `->`.
+fn follows_dash(tokens: &[TokenTree], i: usize) -> bool {
+    i > 0 && is_punct(tokens.get(i - 1), '-')
+}
+
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+    match token {
+        Some(TokenTree::Punct(punct)) => punct.as_char() == c,
+        _ => false,
+    }
+}
+
+fn stringify(tokens: &[TokenTree]) -> String {
+    tokens.iter().cloned().collect::<TokenStream>().to_string()
+}
@@ -0,0 +1,130 @@
+//! Reading fixed-size arrays from a `futures_io::AsyncRead`, available
+//! with the `futures-io` feature.
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_io::{AsyncRead, Error, ErrorKind, Result};
+
+/// Extends every `futures_io::AsyncRead` with a method for reading a
+/// fixed-size array, the async counterpart of `ReadArrayExt`.
+pub trait AsyncReadArrayExt: AsyncRead {
+    /// You can use `read_exact_array` to read exactly `N` bytes into a
+    /// new array, awaiting as many reads as that takes.  It fails with
+    /// `ErrorKind::UnexpectedEof` if the reader ends first.
+    ///
+    /// ```edition2018
+    /// use arrayref::AsyncReadArrayExt;
+    ///
+    /// async fn read_header(mut stream: &[u8]) -> std::io::Result<(u16, u32)> {
+    ///     let kind = u16::from_be_bytes(stream.read_exact_array().await?);
+    ///     let len = u32::from_be_bytes(stream.read_exact_array().await?);
+    ///     Ok((kind, len))
+    /// }
+    /// # fn main() {
+    /// # use std::future::Future;
+    /// # use std::task::{Context, Poll, Waker};
+    /// # let mut fut = Box::pin(read_header(&[0, 1, 0, 0, 0, 42]));
+    /// # let poll = fut.as_mut().poll(&mut Context::from_waker(Waker::noop()));
+    /// # assert!(matches!(poll, Poll::Ready(Ok((1, 42)))));
+    /// # }
+    /// ```
+    fn read_exact_array<const N: usize>(&mut self) -> ReadExactArray<'_, Self, N>
+    where
+        Self: Unpin,
+    {
+        ReadExactArray {
+            reader: self,
+            array: [0; N],
+            filled: 0,
+        }
+    }
+}
+
+impl<R: AsyncRead + ?Sized> AsyncReadArrayExt for R {}
+
+/// The future returned by `AsyncReadArrayExt::read_exact_array`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct ReadExactArray<'a, R: ?Sized, const N: usize> {
+    reader: &'a mut R,
+    array: [u8; N],
+    filled: usize,
+}
+
+impl<R: AsyncRead + Unpin + ?Sized, const N: usize> Future for ReadExactArray<'_, R, N> {
+    type Output = Result<[u8; N]>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        while this.filled < N {
+            let buf = &mut this.array[this.filled..];
+            match Pin::new(&mut *this.reader).poll_read(cx, buf) {
+                Poll::Ready(Ok(0)) => {
+                    return Poll::Ready(Err(Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "failed to fill whole array",
+                    )))
+                }
+                Poll::Ready(Ok(n)) => this.filled += n,
+                Poll::Ready(Err(e)) if e.kind() == ErrorKind::Interrupted => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(this.array))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::task::Waker;
+
+    /// Hands out one byte per poll, with a `Pending` and an
+    /// `Interrupted` before each.
+    struct Trickle<'a>(&'a [u8], u8);
+
+    impl AsyncRead for Trickle<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<Result<usize>> {
+            self.1 = (self.1 + 1) % 3;
+            match self.1 {
+                1 => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+                2 => Poll::Ready(Err(Error::from(ErrorKind::Interrupted))),
+                _ if self.0.is_empty() || buf.is_empty() => Poll::Ready(Ok(0)),
+                _ => {
+                    buf[0] = self.0[0];
+                    self.0 = &self.0[1..];
+                    Poll::Ready(Ok(1))
+                }
+            }
+        }
+    }
+
+    fn block_on<F: Future + Unpin>(mut fut: F) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+        loop {
+            if let Poll::Ready(out) = Pin::new(&mut fut).poll(&mut cx) {
+                return out;
+            }
+        }
+    }
+
+    #[test]
+    fn reads_arrays_across_partial_reads() {
+        let mut reader = Trickle(&[1, 2, 3, 4, 5], 0);
+        assert_eq!(block_on(reader.read_exact_array()).unwrap(), [1, 2, 3]);
+        assert_eq!(block_on(reader.read_exact_array::<0>()).unwrap(), []);
+        let err = block_on(reader.read_exact_array::<3>()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let mut slice = &[6u8, 7][..];
+        assert_eq!(block_on(slice.read_exact_array()).unwrap(), [6, 7]);
+    }
+}
@@ -0,0 +1,262 @@
+//! Bit-level access to fixed-size byte arrays.
+
+use core::ops::Range;
+
+/// The order in which a `BitView` numbers the bits of each byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// Bit 0 is the most significant bit of the first byte, as in
+    /// network protocol diagrams.  Multi-bit fields read with
+    /// `get_bits` have their first bit as their most significant.
+    Msb0,
+    /// Bit 0 is the least significant bit of the first byte, as in
+    /// most hardware register layouts.  Multi-bit fields read with
+    /// `get_bits` have their first bit as their least significant.
+    Lsb0,
+}
+
+impl BitOrder {
+    #[inline]
+    const fn locate(self, bit: usize) -> (usize, u8) {
+        let shift = match self {
+            BitOrder::Msb0 => 7 - bit % 8,
+            BitOrder::Lsb0 => bit % 8,
+        };
+        (bit / 8, 1 << shift)
+    }
+}
+
+const fn get_bit(bytes: &[u8], order: BitOrder, bit: usize) -> bool {
+    let (byte, mask) = order.locate(bit);
+    bytes[byte] & mask != 0
+}
+
+const fn get_bits(bytes: &[u8], order: BitOrder, range: Range<usize>) -> u64 {
+    assert!(range.start <= range.end, "bit range starts after it ends");
+    assert!(
+        range.end - range.start <= 64,
+        "bit range is wider than 64 bits"
+    );
+    let mut value = 0;
+    let mut bit = range.start;
+    while bit < range.end {
+        let b = get_bit(bytes, order, bit) as u64;
+        match order {
+            BitOrder::Msb0 => value = value << 1 | b,
+            BitOrder::Lsb0 => value |= b << (bit - range.start),
+        }
+        bit += 1;
+    }
+    value
+}
+
+/// A `BitView` reads individual bits and bit-fields out of a byte
+/// array, such as a flags byte or a packed header extracted with
+/// `array_ref!`.
+///
+/// **Panics** (in each accessor) if a bit index is out of bounds.
+/// The accessors are `const fn`s, so flags in a `static` blob can be
+/// decoded at compile time.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// use arrayref::{BitOrder, BitView};
+///
+/// # fn main() {
+/// // The first two bytes of an IPv4 header.
+/// let header = [0x45, 0xb8, 0x00, 0x54];
+/// let bits = BitView::new(array_ref!(header, 0, 2), BitOrder::Msb0);
+/// assert_eq!(bits.get_bits(0..4), 4); // version
+/// assert_eq!(bits.get_bits(4..8), 5); // header length
+/// assert_eq!(bits.get_bits(8..14), 46); // DSCP
+/// assert!(!bits.get_bit(15));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitView<'a, const N: usize> {
+    bytes: &'a [u8; N],
+    order: BitOrder,
+}
+
+struct AssertBitsFit<const N: usize>;
+
+impl<const N: usize> AssertBitsFit<N> {
+    const OK: () = assert!(
+        N <= usize::MAX / 8,
+        "the number of bits must fit in a usize"
+    );
+}
+
+impl<'a, const N: usize> BitView<'a, N> {
+    /// Views the bits of `bytes`, numbered in the given order.
+    pub const fn new(bytes: &'a [u8; N], order: BitOrder) -> Self {
+        let () = AssertBitsFit::<N>::OK;
+        BitView { bytes, order }
+    }
+
+    /// The number of bits in the view, `8 * N`.
+    pub const fn len(&self) -> usize {
+        8 * N
+    }
+
+    /// Whether the view has no bits at all.
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// The bit order in use.
+    pub const fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// The underlying bytes.
+    pub const fn bytes(&self) -> &'a [u8; N] {
+        self.bytes
+    }
+
+    /// Whether bit `bit` is set.
+    pub const fn get_bit(&self, bit: usize) -> bool {
+        get_bit(self.bytes, self.order, bit)
+    }
+
+    /// The bits in `range` as an integer.  **Panics** if the range is
+    /// wider than 64 bits.
+    pub const fn get_bits(&self, range: Range<usize>) -> u64 {
+        get_bits(self.bytes, self.order, range)
+    }
+}
+
+/// A `BitViewMut` is a `BitView` that can also set bits.
+///
+/// ```
+/// use arrayref::{BitOrder, BitViewMut};
+///
+/// let mut status = [0u8; 2];
+/// let mut bits = BitViewMut::new(&mut status, BitOrder::Lsb0);
+/// bits.set_bit(0, true);
+/// bits.set_bits(4..12, 0xab);
+/// assert_eq!(status, [0xb1, 0x0a]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct BitViewMut<'a, const N: usize> {
+    bytes: &'a mut [u8; N],
+    order: BitOrder,
+}
+
+impl<'a, const N: usize> BitViewMut<'a, N> {
+    /// Views the bits of `bytes`, numbered in the given order.
+    pub fn new(bytes: &'a mut [u8; N], order: BitOrder) -> Self {
+        let () = AssertBitsFit::<N>::OK;
+        BitViewMut { bytes, order }
+    }
+
+    /// The number of bits in the view, `8 * N`.
+    pub fn len(&self) -> usize {
+        8 * N
+    }
+
+    /// Whether the view has no bits at all.
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// The bit order in use.
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// A read-only view of the same bits.
+    pub fn as_view(&self) -> BitView<'_, N> {
+        BitView::new(self.bytes, self.order)
+    }
+
+    /// Whether bit `bit` is set.
+    pub fn get_bit(&self, bit: usize) -> bool {
+        get_bit(self.bytes, self.order, bit)
+    }
+
+    /// The bits in `range` as an integer.  **Panics** if the range is
+    /// wider than 64 bits.
+    pub fn get_bits(&self, range: Range<usize>) -> u64 {
+        get_bits(self.bytes, self.order, range)
+    }
+
+    /// Sets or clears bit `bit`.
+    pub fn set_bit(&mut self, bit: usize, value: bool) {
+        let (byte, mask) = self.order.locate(bit);
+        if value {
+            self.bytes[byte] |= mask;
+        } else {
+            self.bytes[byte] &= !mask;
+        }
+    }
+
+    /// Stores `value` in the bits in `range`.  **Panics** if `value`
+    /// does not fit in the range, or the range is wider than 64 bits.
+    pub fn set_bits(&mut self, range: Range<usize>, value: u64) {
+        assert!(range.start <= range.end, "bit range starts after it ends");
+        let width = range.end - range.start;
+        assert!(width <= 64, "bit range is wider than 64 bits");
+        assert!(
+            width == 64 || value >> width == 0,
+            "value does not fit in {} bits",
+            width
+        );
+        for (i, bit) in range.enumerate() {
+            let shift = match self.order {
+                BitOrder::Msb0 => width - 1 - i,
+                BitOrder::Lsb0 => i,
+            };
+            self.set_bit(bit, value >> shift & 1 == 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate quickcheck;
+
+    use super::*;
+
+    #[test]
+    fn set_then_get_round_trips() {
+        fn f(start: u8, width: u8, value: u64, msb0: bool) -> bool {
+            let order = if msb0 { BitOrder::Msb0 } else { BitOrder::Lsb0 };
+            let start = start as usize % 64;
+            let width = width as usize % 65;
+            let value = if width == 64 {
+                value
+            } else {
+                value & ((1 << width) - 1)
+            };
+            let mut bytes = [0x5au8; 16];
+            let before = bytes;
+            let mut bits = BitViewMut::new(&mut bytes, order);
+            bits.set_bits(start..start + width, value);
+            let outside_unchanged = (0..128)
+                .filter(|b| !(start..start + width).contains(b))
+                .all(|b| bits.get_bit(b) == BitView::new(&before, order).get_bit(b));
+            bits.as_view().get_bits(start..start + width) == value && outside_unchanged
+        }
+        quickcheck::quickcheck(f as fn(u8, u8, u64, bool) -> bool);
+    }
+
+    #[test]
+    fn orders_number_bits_differently() {
+        let bytes = [0x80, 0x01];
+        let msb = BitView::new(&bytes, BitOrder::Msb0);
+        let lsb = BitView::new(&bytes, BitOrder::Lsb0);
+        assert!(msb.get_bit(0) && msb.get_bit(15));
+        assert!(lsb.get_bit(7) && lsb.get_bit(8));
+        assert_eq!(msb.get_bits(0..16), 0x8001);
+        assert_eq!(lsb.get_bits(0..16), 0x0180);
+        assert_eq!(msb.len(), 16);
+    }
+
+    #[test]
+    #[should_panic(expected = "value does not fit in 3 bits")]
+    fn set_bits_checks_value() {
+        BitViewMut::new(&mut [0u8], BitOrder::Msb0).set_bits(0..3, 8);
+    }
+}
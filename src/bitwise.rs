@@ -0,0 +1,86 @@
+//! Element-wise bitwise operations on fixed-size arrays.
+
+use core::convert::TryFrom;
+use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign};
+
+use bounds::sub_slice_mut;
+
+macro_rules! bitwise_assign {
+    ($name:ident, $at:ident, $bound:ident, $op:tt, $what:expr, $example:expr) => {
+        #[doc = concat!("Combines each element of `src` into `dst` with `", $what, "`.")]
+        ///
+        /// The loop has a constant trip count, so for small `N` it is
+        /// fully unrolled, and for larger `N` it is vectorized.
+        ///
+        /// ```
+        #[doc = concat!("use arrayref::", stringify!($name), ";")]
+        ///
+        #[doc = $example]
+        /// ```
+        #[inline]
+        pub fn $name<T: Copy + $bound, const N: usize>(dst: &mut [T; N], src: &[T; N]) {
+            for (d, &s) in dst.iter_mut().zip(src) {
+                *d $op s;
+            }
+        }
+
+        #[doc = concat!("Combines `src` with `", $what, "` into the `N` elements of `buf` starting")]
+        #[doc = concat!("at `offset`, as `", stringify!($name), "(array_mut_ref!(buf, offset, N), src)`")]
+        /// would.
+        ///
+        /// **Panics** if the window is out of bounds.
+        #[inline]
+        pub fn $at<T: Copy + $bound, const N: usize>(buf: &mut [T], offset: usize, src: &[T; N]) {
+            let dst = <&mut [T; N]>::try_from(sub_slice_mut(buf, offset, N)).unwrap();
+            $name(dst, src);
+        }
+    };
+}
+
+bitwise_assign!(
+    xor_assign,
+    xor_assign_at,
+    BitXorAssign,
+    ^=,
+    "^",
+    "let mut block = *b\"attack at dawn!!\";\nlet keystream = [0x5a; 16];\nxor_assign(&mut block, &keystream);\nxor_assign(&mut block, &keystream);\nassert_eq!(&block, b\"attack at dawn!!\");"
+);
+bitwise_assign!(
+    and_assign,
+    and_assign_at,
+    BitAndAssign,
+    &=,
+    "&",
+    "let mut flags = [0b1111u8, 0b1010];\nand_assign(&mut flags, &[0b0110, 0b0011]);\nassert_eq!(flags, [0b0110, 0b0010]);"
+);
+bitwise_assign!(
+    or_assign,
+    or_assign_at,
+    BitOrAssign,
+    |=,
+    "|",
+    "let mut flags = [0b0001u8, 0b1000];\nor_assign(&mut flags, &[0b0100, 0b1000]);\nassert_eq!(flags, [0b0101, 0b1000]);"
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn window_forms_match_array_forms() {
+        let mut buf = [0xffu8; 8];
+        xor_assign_at(&mut buf, 2, &[0x0f; 4]);
+        and_assign_at(&mut buf, 0, &[0x3c; 3]);
+        or_assign_at(&mut buf, 5, &[0x00, 0x01, 0x02]);
+        assert_eq!(buf, [0x3c, 0x3c, 0x30, 0xf0, 0xf0, 0xf0, 0xff, 0xff]);
+        let mut words = [1u32 << 31, 3];
+        xor_assign(&mut words, &[1 << 31, 1]);
+        assert_eq!(words, [0, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn window_is_bounds_checked() {
+        xor_assign_at(&mut [0u8; 4], 2, &[0; 3]);
+    }
+}
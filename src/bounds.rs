@@ -0,0 +1,223 @@
+//! Bounds checking for `array_ref!` and its relatives, with an
+//! optional user-installed failure handler.
+
+use error::BoundsFailure;
+
+#[cfg(feature = "bounds-hook")]
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+#[cfg(feature = "bounds-hook")]
+static HANDLER: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Installs `handler` to be called, instead of panicking, when
+/// `array_ref!`, `array_mut_ref!`, `array_ref_end!` or
+/// `array_mut_ref_end!` (or a macro built on them, such as
+/// `swap_between!`) is given a window that is out of bounds.  The
+/// handler must not return: it might log the failure and reset the
+/// device, light an error LED, or spin until a watchdog fires.
+///
+/// Only available with the `bounds-hook` feature.  The macros that
+/// check lengths at compile time never fail at runtime, and so never
+/// call the handler.
+///
+/// ```
+/// fn reset(failure: &arrayref::BoundsFailure) -> ! {
+///     // A real handler would record `failure` and reset the device.
+///     panic!("{}", failure)
+/// }
+///
+/// arrayref::set_bounds_failure_handler(reset);
+/// ```
+#[cfg(feature = "bounds-hook")]
+pub fn set_bounds_failure_handler(handler: fn(&BoundsFailure) -> !) {
+    HANDLER.store(handler as *mut (), Ordering::Release);
+}
+
+#[cold]
+#[inline(never)]
+fn fail(failure: BoundsFailure) -> ! {
+    #[cfg(feature = "bounds-hook")]
+    {
+        let handler = HANDLER.load(Ordering::Acquire);
+        if !handler.is_null() {
+            // Only `set_bounds_failure_handler` stores to `HANDLER`,
+            // and it only stores function pointers of this type.
+            let handler: fn(&BoundsFailure) -> ! = unsafe { core::mem::transmute(handler) };
+            handler(&failure);
+        }
+    }
+    panic!("{}", failure)
+}
+
+/// Checks that `len` elements at `offset` fit in `available`,
+/// reporting failure through `fail`.
+#[inline]
+pub(crate) fn check(offset: usize, len: usize, available: usize) {
+    if offset > available || len > available - offset {
+        fail(BoundsFailure::new(offset, len, available));
+    }
+}
+
+/// `&slice[offset..][..len]`, reporting failure through `fail`.
+#[doc(hidden)]
+#[inline]
+pub fn sub_slice<T>(slice: &[T], offset: usize, len: usize) -> &[T] {
+    if offset > slice.len() || len > slice.len() - offset {
+        fail(BoundsFailure::new(offset, len, slice.len()));
+    }
+    &slice[offset..][..len]
+}
+
+/// `&mut slice[offset..][..len]`, reporting failure through `fail`.
+#[doc(hidden)]
+#[inline]
+pub fn sub_slice_mut<T>(slice: &mut [T], offset: usize, len: usize) -> &mut [T] {
+    if offset > slice.len() || len > slice.len() - offset {
+        fail(BoundsFailure::new(offset, len, slice.len()));
+    }
+    &mut slice[offset..][..len]
+}
+
+/// The `len` elements of `slice` that end `offset` elements before
+/// its end, reporting failure through `fail`.
+#[doc(hidden)]
+#[inline]
+pub fn sub_slice_end<T>(slice: &[T], offset: usize, len: usize) -> &[T] {
+    if offset > slice.len() || len > slice.len() - offset {
+        fail(BoundsFailure::from_end(offset, len, slice.len()));
+    }
+    let end = slice.len() - offset;
+    &slice[end - len..end]
+}
+
+/// The mutable version of `sub_slice_end`.
+#[doc(hidden)]
+#[inline]
+pub fn sub_slice_end_mut<T>(slice: &mut [T], offset: usize, len: usize) -> &mut [T] {
+    if offset > slice.len() || len > slice.len() - offset {
+        fail(BoundsFailure::from_end(offset, len, slice.len()));
+    }
+    let end = slice.len() - offset;
+    &mut slice[end - len..end]
+}
+
+/// `slice.split_at(len)`, reporting failure through `fail`.
+#[doc(hidden)]
+#[inline]
+pub fn split_head<T>(slice: &[T], len: usize) -> (&[T], &[T]) {
+    if len > slice.len() {
+        fail(BoundsFailure::new(0, len, slice.len()));
+    }
+    slice.split_at(len)
+}
+
+/// `slice.split_at_mut(len)`, reporting failure through `fail`.
+#[doc(hidden)]
+#[inline]
+pub fn split_head_mut<T>(slice: &mut [T], len: usize) -> (&mut [T], &mut [T]) {
+    if len > slice.len() {
+        fail(BoundsFailure::new(0, len, slice.len()));
+    }
+    slice.split_at_mut(len)
+}
+
+/// `slice.split_at(slice.len() - len)`, reporting failure through
+/// `fail`.
+#[doc(hidden)]
+#[inline]
+pub fn split_tail<T>(slice: &[T], len: usize) -> (&[T], &[T]) {
+    if len > slice.len() {
+        fail(BoundsFailure::from_end(0, len, slice.len()));
+    }
+    slice.split_at(slice.len() - len)
+}
+
+/// `slice.split_at_mut(slice.len() - len)`, reporting failure through
+/// `fail`.
+#[doc(hidden)]
+#[inline]
+pub fn split_tail_mut<T>(slice: &mut [T], len: usize) -> (&mut [T], &mut [T]) {
+    if len > slice.len() {
+        fail(BoundsFailure::from_end(0, len, slice.len()));
+    }
+    let mid = slice.len() - len;
+    slice.split_at_mut(mid)
+}
+
+/// Checks that each `(offset, len)` region fits in `available`
+/// elements, reporting failure through `fail`, and panics if any two
+/// of them overlap, for `disjoint_mut_refs!`.  Empty regions overlap
+/// nothing.
+#[doc(hidden)]
+#[inline]
+pub fn check_disjoint(regions: &[(usize, usize)], available: usize) {
+    for (i, &(offset, len)) in regions.iter().enumerate() {
+        check(offset, len, available);
+        for &(other_offset, other_len) in &regions[..i] {
+            assert!(
+                len == 0
+                    || other_len == 0
+                    || offset + len <= other_offset
+                    || other_offset + other_len <= offset,
+                "region {}..{} overlaps region {}..{}",
+                other_offset,
+                other_offset + other_len,
+                offset,
+                offset + len,
+            );
+        }
+    }
+}
+
+/// `slice.get(offset..)?.get(..len)`, for the fallible macros.
+#[doc(hidden)]
+#[inline]
+pub fn try_sub_slice<T>(slice: &[T], offset: usize, len: usize) -> Option<&[T]> {
+    if offset > slice.len() || len > slice.len() - offset {
+        return None;
+    }
+    Some(&slice[offset..][..len])
+}
+
+/// The mutable version of `try_sub_slice`.
+#[doc(hidden)]
+#[inline]
+pub fn try_sub_slice_mut<T>(slice: &mut [T], offset: usize, len: usize) -> Option<&mut [T]> {
+    if offset > slice.len() || len > slice.len() - offset {
+        return None;
+    }
+    Some(&mut slice[offset..][..len])
+}
+
+#[cfg(all(test, feature = "bounds-hook"))]
+mod test {
+    use super::*;
+    use std::panic;
+    use std::string::ToString;
+
+    fn handler(failure: &BoundsFailure) -> ! {
+        panic!("handled: {}", failure.offset())
+    }
+
+    #[test]
+    fn handler_is_called() {
+        set_bounds_failure_handler(handler);
+        let err = panic::catch_unwind(|| {
+            let data = [0u8; 4];
+            let _ = array_ref!(data, 3, 2);
+        })
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<std::string::String>().unwrap(),
+            "handled: 3"
+        );
+        assert_eq!(
+            BoundsFailure::new(3, 2, 4).to_string(),
+            "range of 2 elements at offset 3 is out of bounds for length 4"
+        );
+        assert_eq!(
+            BoundsFailure::from_end(3, 2, 4).to_string(),
+            "range of 2 elements at offset 3 from the end is out of bounds for length 4"
+        );
+    }
+}
@@ -0,0 +1,452 @@
+//! Building a fixed-size array piece by piece.
+
+use core::mem::{self, MaybeUninit};
+use core::{fmt, ptr, slice};
+
+use error::{CollectError, LengthError};
+
+/// An `ArrayBuilder` collects elements into inline storage until
+/// there are exactly `N` of them, at which point `finish` turns them
+/// into a `[T; N]`.  Elements can be pushed one at a time, as arrays,
+/// or cloned from slices, and pushing more than `N` elements in total
+/// is an error rather than a panic.  No allocation is involved.
+///
+/// ```
+/// use arrayref::ArrayBuilder;
+///
+/// let mut nonce = ArrayBuilder::<u8, 12>::new();
+/// nonce.push_array([0xff; 4]).unwrap();
+/// nonce.push_slice(&[1, 2, 3, 4, 5, 6, 7]).unwrap();
+/// assert_eq!(nonce.remaining(), 1);
+/// assert!(nonce.push_slice(&[8, 9]).is_err());
+/// nonce.push(8).unwrap();
+/// assert_eq!(nonce.finish().unwrap(), [255,255,255,255, 1,2,3,4,5,6,7,8]);
+///
+/// let short = ArrayBuilder::<u8, 4>::new();
+/// assert_eq!(short.finish().unwrap_err().missing(), 4);
+/// ```
+pub struct ArrayBuilder<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayBuilder<T, N> {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        ArrayBuilder {
+            // an array of `MaybeUninit` needs no initialization
+            buf: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+            len: 0,
+        }
+    }
+
+    /// The number of elements pushed so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether nothing has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// How many more elements are needed to fill the array.
+    pub fn remaining(&self) -> usize {
+        N - self.len
+    }
+
+    /// Whether all `N` elements have been pushed.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// The elements pushed so far.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len) }
+    }
+
+    fn check_room(&self, count: usize) -> Result<(), LengthError> {
+        if count > self.remaining() {
+            Err(LengthError::new(count, self.remaining()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Pushes a single element, or fails (dropping it) if the array
+    /// is already full.
+    pub fn push(&mut self, item: T) -> Result<(), LengthError> {
+        self.check_room(1)?;
+        self.buf[self.len] = MaybeUninit::new(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Pushes all the elements of an array, or fails (dropping them)
+    /// if there is not room for all of them.
+    pub fn push_array<const K: usize>(&mut self, items: [T; K]) -> Result<(), LengthError> {
+        self.check_room(K)?;
+        let items = mem::ManuallyDrop::new(items);
+        unsafe {
+            ptr::copy_nonoverlapping(
+                items.as_ptr(),
+                self.buf.as_mut_ptr().add(self.len) as *mut T,
+                K,
+            );
+        }
+        self.len += K;
+        Ok(())
+    }
+
+    /// Pushes clones of all the elements of a slice, or fails without
+    /// pushing any of them if there is not room for all of them.
+    pub fn push_slice(&mut self, items: &[T]) -> Result<(), LengthError>
+    where
+        T: Clone,
+    {
+        self.check_room(items.len())?;
+        for item in items {
+            self.buf[self.len] = MaybeUninit::new(item.clone());
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    /// Turns the builder into the finished array, or fails (dropping
+    /// the elements pushed so far) if fewer than `N` elements have
+    /// been pushed.
+    pub fn finish(self) -> Result<[T; N], LengthError> {
+        if !self.is_full() {
+            return Err(LengthError::new(N, self.len));
+        }
+        let this = mem::ManuallyDrop::new(self);
+        // every element is initialized, and `[MaybeUninit<T>; N]` has
+        // the same layout as `[T; N]`.
+        Ok(unsafe { ptr::read(this.buf.as_ptr() as *const [T; N]) })
+    }
+}
+
+impl<T, const N: usize> Default for ArrayBuilder<T, N> {
+    fn default() -> Self {
+        ArrayBuilder::new()
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayBuilder<T, N> {
+    fn drop(&mut self) {
+        let len = mem::replace(&mut self.len, 0);
+        unsafe {
+            let pushed = slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut T, len);
+            ptr::drop_in_place(pushed)
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayBuilder<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArrayBuilder")
+            .field("pushed", &self.as_slice())
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
+
+/// An `ArrayFill` fills a borrowed, uninitialized `[MaybeUninit<T>; N]`
+/// in place, the way `ArrayBuilder` fills its own storage, so that a
+/// packet can be assembled straight into a stack or static buffer.
+/// Once all `N` elements have been pushed, `finish` returns the buffer
+/// as a `&mut [T; N]`.  The elements then belong to the buffer, which
+/// as a `MaybeUninit` never drops them.  An `ArrayFill` dropped before
+/// it is finished drops the elements pushed so far.
+///
+/// ```
+/// use arrayref::ArrayFill;
+/// use std::mem::MaybeUninit;
+///
+/// let mut buf = [MaybeUninit::<u8>::uninit(); 6];
+/// let mut fill = ArrayFill::new(&mut buf);
+/// fill.push_array([0xca, 0xfe]).unwrap();
+/// fill.push_slice(b"ok!").unwrap();
+/// assert!(fill.push_array([0; 2]).is_err());
+/// fill.push(b'\n').unwrap();
+/// let packet: &mut [u8; 6] = fill.finish().unwrap();
+/// assert_eq!(packet, b"\xca\xfeok!\n");
+/// ```
+pub struct ArrayFill<'a, T, const N: usize> {
+    buf: &'a mut [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<'a, T, const N: usize> ArrayFill<'a, T, N> {
+    /// Starts filling `buf` from the beginning.  Anything already in
+    /// it is overwritten without being dropped.
+    pub fn new(buf: &'a mut [MaybeUninit<T>; N]) -> Self {
+        ArrayFill { buf, len: 0 }
+    }
+
+    /// The number of elements pushed so far.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether nothing has been pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// How many more elements are needed to fill the buffer.
+    pub fn remaining(&self) -> usize {
+        N - self.len
+    }
+
+    /// Whether all `N` elements have been pushed.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// The elements pushed so far.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len) }
+    }
+
+    fn check_room(&self, count: usize) -> Result<(), LengthError> {
+        if count > self.remaining() {
+            Err(LengthError::new(count, self.remaining()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Pushes a single element, or fails (dropping it) if the buffer
+    /// is already full.
+    pub fn push(&mut self, item: T) -> Result<(), LengthError> {
+        self.check_room(1)?;
+        self.buf[self.len] = MaybeUninit::new(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Pushes all the elements of an array, or fails (dropping them)
+    /// if there is not room for all of them.
+    pub fn push_array<const K: usize>(&mut self, items: [T; K]) -> Result<(), LengthError> {
+        self.check_room(K)?;
+        let items = mem::ManuallyDrop::new(items);
+        unsafe {
+            ptr::copy_nonoverlapping(
+                items.as_ptr(),
+                self.buf.as_mut_ptr().add(self.len) as *mut T,
+                K,
+            );
+        }
+        self.len += K;
+        Ok(())
+    }
+
+    /// Pushes clones of all the elements of a slice, or fails without
+    /// pushing any of them if there is not room for all of them.
+    pub fn push_slice(&mut self, items: &[T]) -> Result<(), LengthError>
+    where
+        T: Clone,
+    {
+        self.check_room(items.len())?;
+        for item in items {
+            self.buf[self.len] = MaybeUninit::new(item.clone());
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    /// Returns the filled buffer, or fails (dropping the elements
+    /// pushed so far) if fewer than `N` elements have been pushed.
+    pub fn finish(self) -> Result<&'a mut [T; N], LengthError> {
+        if !self.is_full() {
+            return Err(LengthError::new(N, self.len));
+        }
+        let this = mem::ManuallyDrop::new(self);
+        // `this` is never dropped, so the borrow can be moved out of
+        // it.  Every element is initialized, and `[MaybeUninit<T>; N]`
+        // has the same layout as `[T; N]`.
+        unsafe {
+            let buf: &'a mut [MaybeUninit<T>; N] = ptr::read(&this.buf);
+            Ok(&mut *(buf.as_mut_ptr() as *mut [T; N]))
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayFill<'_, T, N> {
+    fn drop(&mut self) {
+        let len = mem::replace(&mut self.len, 0);
+        unsafe {
+            let pushed = slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut T, len);
+            ptr::drop_in_place(pushed)
+        }
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayFill<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArrayFill")
+            .field("pushed", &self.as_slice())
+            .field("remaining", &self.remaining())
+            .finish()
+    }
+}
+
+/// You can use `try_array_from_fn` to create a `[T; N]` from a
+/// fallible `f`, calling it for each index in order.  The first error
+/// is returned as soon as it occurs, after dropping the elements
+/// created so far.
+///
+/// ```
+/// use arrayref::try_array_from_fn;
+///
+/// let fields = ["12", "7", "40"];
+/// let parsed: Result<[u8; 3], _> = try_array_from_fn(|i| fields[i].parse());
+/// assert_eq!(parsed, Ok([12, 7, 40]));
+/// let bad: Result<[u8; 3], _> = try_array_from_fn(|i| ["1", "x", "3"][i].parse());
+/// assert!(bad.is_err());
+/// ```
+pub fn try_array_from_fn<T, E, const N: usize>(
+    mut f: impl FnMut(usize) -> Result<T, E>,
+) -> Result<[T; N], E> {
+    let mut builder = ArrayBuilder::new();
+    for i in 0..N {
+        builder.push(f(i)?).unwrap();
+    }
+    Ok(builder.finish().unwrap())
+}
+
+/// You can use `collect_array` to collect the items of an iterator
+/// into a `[T; N]`, failing with a `CollectError` that says how many
+/// items were missing if the iterator is too short, or that it was
+/// too long if any items are left over.
+///
+/// ```
+/// use arrayref::{collect_array, CollectError};
+///
+/// let line = "10.0.0.1";
+/// let octets: Result<[u8; 4], _> = collect_array(line.split('.').map(|s| s.parse().unwrap()));
+/// assert_eq!(octets, Ok([10, 0, 0, 1]));
+/// assert_eq!(collect_array::<_, 4>("1.2".split('.')), Err(CollectError::TooShort { missing: 2 }));
+/// assert_eq!(collect_array::<_, 1>("1.2".split('.')), Err(CollectError::TooLong));
+/// ```
+pub fn collect_array<T, const N: usize>(
+    items: impl IntoIterator<Item = T>,
+) -> Result<[T; N], CollectError> {
+    let mut items = items.into_iter();
+    let mut builder = ArrayBuilder::new();
+    for i in 0..N {
+        match items.next() {
+            Some(item) => builder.push(item).unwrap(),
+            None => return Err(CollectError::TooShort { missing: N - i }),
+        }
+    }
+    if items.next().is_some() {
+        return Err(CollectError::TooLong);
+    }
+    Ok(builder.finish().unwrap())
+}
+
+#[cfg(test)]
+mod test {
+    extern crate quickcheck;
+
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn overflow_is_an_error() {
+        let mut b = ArrayBuilder::<u16, 3>::new();
+        b.push_array([1, 2]).unwrap();
+        let err = b.push_array([3, 4]).unwrap_err();
+        assert_eq!((err.required(), err.available()), (2, 1));
+        assert_eq!(b.as_slice(), [1, 2]);
+        b.push(3).unwrap();
+        assert!(b.push(4).is_err());
+        assert_eq!(b.finish(), Ok([1, 2, 3]));
+    }
+
+    #[test]
+    fn drops_partial_contents() {
+        let counter = Rc::new(());
+        {
+            let mut b = ArrayBuilder::<Rc<()>, 4>::new();
+            b.push_slice(&[counter.clone(), counter.clone()]).unwrap();
+            b.push(counter.clone()).unwrap();
+            assert_eq!(Rc::strong_count(&counter), 4);
+            assert!(b.finish().is_err());
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+        let mut b = ArrayBuilder::<Rc<()>, 2>::new();
+        b.push_array([counter.clone(), counter.clone()]).unwrap();
+        let done = b.finish().unwrap();
+        assert_eq!(Rc::strong_count(&counter), 3);
+        drop(done);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn array_fill_fills_in_place() {
+        let counter = Rc::new(());
+        let mut buf: [MaybeUninit<Rc<()>>; 3] = [const { MaybeUninit::uninit() }; 3];
+        {
+            let mut fill = ArrayFill::new(&mut buf);
+            fill.push_slice(&[counter.clone(), counter.clone()])
+                .unwrap();
+            assert_eq!(
+                fill.push_array([counter.clone(), counter.clone()])
+                    .unwrap_err()
+                    .available(),
+                1
+            );
+            assert_eq!(Rc::strong_count(&counter), 3);
+            assert!(fill.finish().is_err());
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+        let mut fill = ArrayFill::new(&mut buf);
+        fill.push(counter.clone()).unwrap();
+        fill.push_array([counter.clone(), counter.clone()]).unwrap();
+        assert!(fill.push(counter.clone()).is_err());
+        let done = fill.finish().unwrap();
+        assert_eq!(Rc::strong_count(&counter), 4);
+        for rc in done.iter_mut() {
+            *rc = Rc::new(());
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+        for slot in buf.iter_mut() {
+            unsafe { slot.assume_init_drop() };
+        }
+    }
+
+    #[test]
+    fn try_array_from_fn_drops_on_error() {
+        let counter = Rc::new(());
+        let mut calls = 0;
+        let result: Result<[Rc<()>; 5], usize> = try_array_from_fn(|i| {
+            calls += 1;
+            if i == 3 {
+                assert_eq!(Rc::strong_count(&counter), 4);
+                Err(i)
+            } else {
+                Ok(counter.clone())
+            }
+        });
+        assert_eq!(result.unwrap_err(), 3);
+        assert_eq!(calls, 4);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn collect_array_checks_count() {
+        fn f(data: std::vec::Vec<u8>) -> bool {
+            let result = collect_array::<_, 5>(data.iter().cloned());
+            match data.len() {
+                5 => result == Ok(*array_ref!(data, 0, 5)),
+                n if n < 5 => result == Err(CollectError::TooShort { missing: 5 - n }),
+                _ => result == Err(CollectError::TooLong),
+            }
+        }
+        quickcheck::quickcheck(f as fn(std::vec::Vec<u8>) -> bool);
+        assert_eq!(collect_array::<u8, 0>(None), Ok([]));
+    }
+}
@@ -0,0 +1,457 @@
+//! Reading and writing numbers in byte slices in either byte order.
+
+use cursor::{ArrayCursor, ArrayCursorMut, Checked, Checking, TrustedInput};
+use endian::{self, ByteOrder};
+
+/// A `ByteReader` is an `ArrayCursor` over bytes that decodes integers
+/// and floats as it goes, in either byte order.  Besides the `_le` and
+/// `_be` methods, `read_u32::<E>()` and friends take the byte order as
+/// a `ByteOrder` type parameter, for codecs generic over it.
+///
+/// Like the cursor's, its methods panic if there are not enough bytes
+/// left, leaving the reader where it was; check `remaining` first when
+/// the input is untrusted.  A reader made with the unsafe
+/// `new_trusted`, for input whose length has already been checked,
+/// skips those checks instead, as its cursor does.
+///
+/// ```
+/// use arrayref::ByteReader;
+///
+/// let header = [0x89, b'P', b'N', b'G', 0, 0, 0, 13, 0x00, 0x10, 0x40, 0x49, 0x0f, 0xdb];
+/// let mut reader = ByteReader::new(&header);
+/// assert_eq!(reader.bytes::<4>(), b"\x89PNG");
+/// assert_eq!(reader.u32_be(), 13);
+/// assert_eq!(reader.u16_le(), 0x1000);
+/// assert_eq!(reader.f32_be(), core::f32::consts::PI);
+/// assert_eq!(reader.remaining(), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ByteReader<'a, C = Checked> {
+    cursor: ArrayCursor<'a, u8, C>,
+}
+
+macro_rules! read_methods {
+    ($( $t:ident: $le:ident, $be:ident; )*) => {
+        $(
+            #[doc = concat!("Reads a little-endian `", stringify!($t), "`.")]
+            #[inline]
+            pub fn $le(&mut self) -> $t {
+                $t::from_le_bytes(*self.cursor.take())
+            }
+
+            #[doc = concat!("Reads a big-endian `", stringify!($t), "`.")]
+            #[inline]
+            pub fn $be(&mut self) -> $t {
+                $t::from_be_bytes(*self.cursor.take())
+            }
+        )*
+    };
+}
+
+macro_rules! read_order_methods {
+    ($( $name:ident: $t:ident; )*) => {
+        $(
+            #[doc = concat!("Reads a `", stringify!($t), "` in byte order `E`, as `",
+                            stringify!($name), "::<E>` does.")]
+            #[inline]
+            pub fn $name<E: ByteOrder>(&mut self) -> $t {
+                endian::$name::<E>(self.cursor.take())
+            }
+        )*
+    };
+}
+
+impl<'a> ByteReader<'a> {
+    /// A reader at the start of `bytes`.
+    pub const fn new(bytes: &'a [u8]) -> Self {
+        ByteReader {
+            cursor: ArrayCursor::new(bytes),
+        }
+    }
+}
+
+impl<'a> ByteReader<'a, TrustedInput> {
+    /// A reader at the start of `bytes` whose reads do not check that
+    /// there are enough bytes left.
+    ///
+    /// # Safety
+    ///
+    /// As for `ArrayCursor::new_trusted`, every read and skip made
+    /// through the reader must fit in what is left of `bytes`.
+    pub const unsafe fn new_trusted(bytes: &'a [u8]) -> Self {
+        ByteReader {
+            cursor: ArrayCursor::new_trusted(bytes),
+        }
+    }
+}
+
+impl<'a, C: Checking> ByteReader<'a, C> {
+    /// Reads the next `N` bytes as an array reference.
+    #[inline]
+    pub fn bytes<const N: usize>(&mut self) -> &'a [u8; N] {
+        self.cursor.take()
+    }
+
+    /// Reads the next `len` bytes as a slice.
+    #[inline]
+    pub fn slice(&mut self, len: usize) -> &'a [u8] {
+        self.cursor.take_slice(len)
+    }
+
+    /// Skips over the next `len` bytes.
+    #[inline]
+    pub fn skip(&mut self, len: usize) {
+        self.cursor.skip(len)
+    }
+
+    /// Reads a `u8`.
+    #[inline]
+    pub fn u8(&mut self) -> u8 {
+        self.cursor.take::<1>()[0]
+    }
+
+    /// Reads an `i8`.
+    #[inline]
+    pub fn i8(&mut self) -> i8 {
+        self.u8() as i8
+    }
+
+    read_methods! {
+        u16: u16_le, u16_be;
+        u32: u32_le, u32_be;
+        u64: u64_le, u64_be;
+        u128: u128_le, u128_be;
+        i16: i16_le, i16_be;
+        i32: i32_le, i32_be;
+        i64: i64_le, i64_be;
+        i128: i128_le, i128_be;
+        f32: f32_le, f32_be;
+        f64: f64_le, f64_be;
+    }
+
+    read_order_methods! {
+        read_u16: u16;
+        read_u24: u32;
+        read_u32: u32;
+        read_u40: u64;
+        read_u48: u64;
+        read_u56: u64;
+        read_u64: u64;
+        read_u128: u128;
+    }
+
+    /// The number of bytes read or skipped so far.
+    pub const fn position(&self) -> usize {
+        self.cursor.position()
+    }
+
+    /// The number of bytes left to read.
+    pub const fn remaining(&self) -> usize {
+        self.cursor.remaining()
+    }
+
+    /// The bytes left to read, without reading them.
+    pub fn rest(&self) -> &'a [u8] {
+        self.cursor.rest()
+    }
+
+    /// Gives up the byte-level view, returning the underlying cursor.
+    pub fn into_cursor(self) -> ArrayCursor<'a, u8, C> {
+        self.cursor
+    }
+}
+
+impl<'a, C> From<ArrayCursor<'a, u8, C>> for ByteReader<'a, C> {
+    fn from(cursor: ArrayCursor<'a, u8, C>) -> Self {
+        ByteReader { cursor }
+    }
+}
+
+/// A `ByteWriter` is an `ArrayCursorMut` over bytes that encodes
+/// integers and floats as it goes, in either byte order, for
+/// serializing into a fixed-size buffer.  As with `ByteReader`,
+/// `write_u32::<E>()` and friends take the byte order as a type
+/// parameter.
+///
+/// Like the cursor's, its methods panic if there is not enough room
+/// left, leaving the writer where it was; check `remaining` first if
+/// the buffer might be too small.  As with `ByteReader`, a writer made
+/// with the unsafe `new_trusted` skips those checks.
+///
+/// ```
+/// use arrayref::ByteWriter;
+///
+/// let mut block = [0u8; 16];
+/// let mut writer = ByteWriter::new(&mut block);
+/// writer.put_bytes(b"FWUP");
+/// writer.put_u16_be(2);
+/// writer.put_u32_le(0xdead_beef);
+/// writer.put_f32_le(1.5);
+/// assert_eq!((writer.written(), writer.remaining()), (14, 2));
+/// assert_eq!(block[..10], *b"FWUP\x00\x02\xef\xbe\xad\xde");
+/// ```
+#[derive(Debug)]
+pub struct ByteWriter<'a, C = Checked> {
+    cursor: ArrayCursorMut<'a, u8, C>,
+}
+
+macro_rules! write_methods {
+    ($( $t:ident: $le:ident, $be:ident; )*) => {
+        $(
+            #[doc = concat!("Writes a little-endian `", stringify!($t), "`.")]
+            #[inline]
+            pub fn $le(&mut self, n: $t) {
+                *self.cursor.take_mut() = n.to_le_bytes();
+            }
+
+            #[doc = concat!("Writes a big-endian `", stringify!($t), "`.")]
+            #[inline]
+            pub fn $be(&mut self, n: $t) {
+                *self.cursor.take_mut() = n.to_be_bytes();
+            }
+        )*
+    };
+}
+
+macro_rules! write_order_methods {
+    ($( $name:ident: $t:ident; )*) => {
+        $(
+            #[doc = concat!("Writes a `", stringify!($t), "` in byte order `E`, as `",
+                            stringify!($name), "::<E>` does.")]
+            #[inline]
+            pub fn $name<E: ByteOrder>(&mut self, n: $t) {
+                endian::$name::<E>(self.cursor.take_mut(), n)
+            }
+        )*
+    };
+}
+
+impl<'a> ByteWriter<'a> {
+    /// A writer at the start of `bytes`.
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        ByteWriter {
+            cursor: ArrayCursorMut::new(bytes),
+        }
+    }
+}
+
+impl<'a> ByteWriter<'a, TrustedInput> {
+    /// A writer at the start of `bytes` whose writes do not check that
+    /// there is enough room left.
+    ///
+    /// # Safety
+    ///
+    /// As for `ArrayCursorMut::new_trusted`, every write, reservation
+    /// and skip made through the writer must fit in what is left of
+    /// `bytes`.
+    pub unsafe fn new_trusted(bytes: &'a mut [u8]) -> Self {
+        ByteWriter {
+            cursor: ArrayCursorMut::new_trusted(bytes),
+        }
+    }
+}
+
+impl<'a, C: Checking> ByteWriter<'a, C> {
+    /// Writes the `N` bytes of `bytes`.
+    #[inline]
+    pub fn put_bytes<const N: usize>(&mut self, bytes: &[u8; N]) {
+        *self.cursor.take_mut() = *bytes;
+    }
+
+    /// Writes the bytes of `bytes`.
+    #[inline]
+    pub fn put_slice(&mut self, bytes: &[u8]) {
+        self.cursor
+            .take_slice_mut(bytes.len())
+            .copy_from_slice(bytes);
+    }
+
+    /// Takes the next `N` bytes to be filled in later, such as a length
+    /// or checksum field.
+    #[inline]
+    pub fn reserve<const N: usize>(&mut self) -> &'a mut [u8; N] {
+        self.cursor.take_mut()
+    }
+
+    /// Skips over the next `len` bytes, leaving them as they are.
+    #[inline]
+    pub fn skip(&mut self, len: usize) {
+        self.cursor.skip(len)
+    }
+
+    /// Writes a `u8`.
+    #[inline]
+    pub fn put_u8(&mut self, n: u8) {
+        *self.cursor.take_mut() = [n];
+    }
+
+    /// Writes an `i8`.
+    #[inline]
+    pub fn put_i8(&mut self, n: i8) {
+        self.put_u8(n as u8)
+    }
+
+    write_methods! {
+        u16: put_u16_le, put_u16_be;
+        u32: put_u32_le, put_u32_be;
+        u64: put_u64_le, put_u64_be;
+        u128: put_u128_le, put_u128_be;
+        i16: put_i16_le, put_i16_be;
+        i32: put_i32_le, put_i32_be;
+        i64: put_i64_le, put_i64_be;
+        i128: put_i128_le, put_i128_be;
+        f32: put_f32_le, put_f32_be;
+        f64: put_f64_le, put_f64_be;
+    }
+
+    write_order_methods! {
+        write_u16: u16;
+        write_u24: u32;
+        write_u32: u32;
+        write_u40: u64;
+        write_u48: u64;
+        write_u56: u64;
+        write_u64: u64;
+        write_u128: u128;
+    }
+
+    /// The number of bytes written or skipped so far.
+    pub fn written(&self) -> usize {
+        self.cursor.written()
+    }
+
+    /// The number of bytes of room left.
+    pub fn remaining(&self) -> usize {
+        self.cursor.remaining()
+    }
+
+    /// Gives up the writer, returning the bytes not yet written.
+    pub fn into_rest(self) -> &'a mut [u8] {
+        self.cursor.into_rest()
+    }
+
+    /// Gives up the byte-level view, returning the underlying cursor.
+    pub fn into_cursor(self) -> ArrayCursorMut<'a, u8, C> {
+        self.cursor
+    }
+}
+
+impl<'a, C> From<ArrayCursorMut<'a, u8, C>> for ByteWriter<'a, C> {
+    fn from(cursor: ArrayCursorMut<'a, u8, C>) -> Self {
+        ByteWriter { cursor }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate quickcheck;
+
+    use super::*;
+
+    #[test]
+    fn reads_match_from_bytes() {
+        fn f(a: u16, b: i64, c: u128, d: u32) -> bool {
+            let mut buf = [0u8; 2 + 8 + 16 + 4 + 1];
+            buf[..2].copy_from_slice(&a.to_le_bytes());
+            buf[2..10].copy_from_slice(&b.to_be_bytes());
+            buf[10..26].copy_from_slice(&c.to_le_bytes());
+            buf[26..30].copy_from_slice(&d.to_be_bytes());
+            buf[30] = 0xff;
+            let mut r = ByteReader::new(&buf);
+            (r.u16_le(), r.i64_be(), r.u128_le()) == (a, b, c)
+                && r.f32_be().to_bits() == d
+                && r.i8() == -1
+        }
+        quickcheck::quickcheck(f as fn(u16, i64, u128, u32) -> bool);
+    }
+
+    #[test]
+    fn writes_round_trip_through_reader() {
+        fn f(a: u16, b: i64, c: u128, d: u32) -> bool {
+            let mut buf = [0u8; 1 + 2 + 8 + 16 + 8 + 3];
+            let rest = {
+                let mut w = ByteWriter::new(&mut buf);
+                let len = w.reserve::<1>();
+                w.put_u16_be(a);
+                w.put_i64_le(b);
+                w.put_u128_be(c);
+                w.put_f64_le(f64::from(f32::from_bits(d)));
+                w.put_slice(&[1, 2]);
+                *len = [w.written() as u8];
+                w.into_rest().len()
+            };
+            let mut r = ByteReader::new(&buf);
+            r.u8() == 37
+                && (r.u16_be(), r.i64_le(), r.u128_be()) == (a, b, c)
+                && r.f64_le().to_bits() == f64::from(f32::from_bits(d)).to_bits()
+                && r.rest() == [1, 2, 0]
+                && rest == 1
+        }
+        quickcheck::quickcheck(f as fn(u16, i64, u128, u32) -> bool);
+    }
+
+    #[test]
+    fn byte_order_methods_round_trip() {
+        use endian::{BigEndian, LittleEndian};
+
+        fn encode<E: ByteOrder>(buf: &mut [u8; 24], a: u16, b: u32, c: u64) {
+            let mut w = ByteWriter::new(buf);
+            w.write_u16::<E>(a);
+            w.write_u24::<E>(b & 0xff_ffff);
+            w.write_u32::<E>(b);
+            w.write_u56::<E>(c >> 8);
+            w.write_u64::<E>(c);
+        }
+        fn decode<E: ByteOrder>(buf: &[u8; 24]) -> (u16, u32, u32, u64, u64) {
+            let mut r = ByteReader::new(buf);
+            (
+                r.read_u16::<E>(),
+                r.read_u24::<E>(),
+                r.read_u32::<E>(),
+                r.read_u56::<E>(),
+                r.read_u64::<E>(),
+            )
+        }
+        fn f(a: u16, b: u32, c: u64) -> bool {
+            let expected = (a, b & 0xff_ffff, b, c >> 8, c);
+            let mut be = [0u8; 24];
+            let mut le = [0u8; 24];
+            encode::<BigEndian>(&mut be, a, b, c);
+            encode::<LittleEndian>(&mut le, a, b, c);
+            be[..2] == a.to_be_bytes()
+                && le[..2] == a.to_le_bytes()
+                && decode::<BigEndian>(&be) == expected
+                && decode::<LittleEndian>(&le) == expected
+        }
+        quickcheck::quickcheck(f as fn(u16, u32, u64) -> bool);
+    }
+
+    #[test]
+    fn trusted_writes_round_trip_through_trusted_reader() {
+        fn f(a: u16, b: i64, c: u32) -> bool {
+            let mut buf = [0u8; 2 + 8 + 4 + 2];
+            let rest = {
+                let mut w = unsafe { ByteWriter::new_trusted(&mut buf) };
+                w.put_u16_le(a);
+                w.put_i64_be(b);
+                w.write_u32::<endian::BigEndian>(c);
+                w.into_rest().len()
+            };
+            let mut r = unsafe { ByteReader::new_trusted(&buf) };
+            (r.u16_le(), r.i64_be(), r.u32_be()) == (a, b, c) && r.rest() == [0, 0] && rest == 2
+        }
+        quickcheck::quickcheck(f as fn(u16, i64, u32) -> bool);
+    }
+
+    #[test]
+    #[should_panic]
+    fn short_writes_panic() {
+        ByteWriter::new(&mut [0; 3]).put_u32_be(1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn short_reads_panic() {
+        ByteReader::new(&[0; 3]).u32_le();
+    }
+}
@@ -0,0 +1,40 @@
+//! Array views of `Cell`s.
+
+use core::cell::Cell;
+
+/// You can use `cell_array` to view a `Cell` holding an array as an
+/// array of `Cell`s, so that single elements can be read and written
+/// through a shared reference.  `Cell<T>` has the same layout as `T`,
+/// so this is only a change of type.  To take a fixed-size window of
+/// a `Cell` slice, use `cell_array_ref!`.
+///
+/// ```
+/// use arrayref::cell_array;
+/// use std::cell::Cell;
+///
+/// let mut counts = [0u32; 4];
+/// let cells = cell_array(Cell::from_mut(&mut counts));
+/// cells[1].set(cells[1].get() + 1);
+/// cells[3].set(7);
+/// assert_eq!(counts, [0, 1, 0, 7]);
+/// ```
+#[inline]
+pub fn cell_array<T, const N: usize>(cell: &Cell<[T; N]>) -> &[Cell<T>; N] {
+    unsafe { &*(cell as *const Cell<[T; N]> as *const [Cell<T>; N]) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cell_array_aliases_the_array() {
+        let cell = Cell::new([1u8, 2, 3]);
+        let cells = cell_array(&cell);
+        cells[0].set(9);
+        cells[2].swap(&cells[1]);
+        assert_eq!(cell.get(), [9, 3, 2]);
+        let empty = Cell::new([0u8; 0]);
+        assert_eq!(cell_array(&empty).len(), 0);
+    }
+}
@@ -0,0 +1,172 @@
+//! A view of two array references as if they were one.
+
+use core::iter::Chain;
+use core::ops::Index;
+use core::slice;
+
+/// A `ChainedArrayRef` presents two separate array references of
+/// lengths `A` and `B` as a single sequence of `A + B` elements that
+/// can be indexed and iterated over without first copying them into
+/// one buffer.  This is handy when a fixed-size field is split across
+/// the end of a ring buffer or the boundary between two I/O buffers.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// use arrayref::ChainedArrayRef;
+///
+/// # fn main() {
+/// let end_of_ring = [0xde, 0xad];
+/// let start_of_ring = [0xbe, 0xef, 0x00];
+/// let magic = ChainedArrayRef::new(&end_of_ring, array_ref!(start_of_ring, 0, 2));
+/// assert_eq!(magic.len(), 4);
+/// assert_eq!(magic[2], 0xbe);
+/// let joined: [u8; 4] = magic.to_array();
+/// assert_eq!(u32::from_be_bytes(joined), 0xdeadbeef);
+/// # }
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct ChainedArrayRef<'a, T: 'a, const A: usize, const B: usize> {
+    first: &'a [T; A],
+    second: &'a [T; B],
+}
+
+struct AssertSum<const A: usize, const B: usize, const C: usize>;
+
+impl<const A: usize, const B: usize, const C: usize> AssertSum<A, B, C> {
+    const OK: () = assert!(
+        A.checked_add(B).is_some() && A + B == C,
+        "destination length must equal the total length of the chained arrays"
+    );
+}
+
+struct AssertLenFits<const A: usize, const B: usize>;
+
+impl<const A: usize, const B: usize> AssertLenFits<A, B> {
+    const OK: () = assert!(
+        A.checked_add(B).is_some(),
+        "the total length of the chained arrays must fit in a usize"
+    );
+}
+
+impl<'a, T, const A: usize, const B: usize> ChainedArrayRef<'a, T, A, B> {
+    /// Chains `first` and `second` together.
+    pub fn new(first: &'a [T; A], second: &'a [T; B]) -> Self {
+        let () = AssertLenFits::<A, B>::OK;
+        ChainedArrayRef { first, second }
+    }
+
+    /// The first of the chained arrays.
+    pub fn first(&self) -> &'a [T; A] {
+        self.first
+    }
+
+    /// The second of the chained arrays.
+    pub fn second(&self) -> &'a [T; B] {
+        self.second
+    }
+
+    /// The total length, `A + B`.
+    pub fn len(&self) -> usize {
+        A + B
+    }
+
+    /// Whether both arrays are empty.
+    pub fn is_empty(&self) -> bool {
+        A + B == 0
+    }
+
+    /// The element at `index` in the combined sequence, or `None` if
+    /// it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        if index < A {
+            self.first.get(index)
+        } else {
+            self.second.get(index - A)
+        }
+    }
+
+    /// An iterator over all `A + B` elements in order.
+    pub fn iter(&self) -> Chain<slice::Iter<'a, T>, slice::Iter<'a, T>> {
+        self.first.iter().chain(self.second.iter())
+    }
+
+    /// Copies the chained elements into `dst`.  The length `C` of the
+    /// destination is checked to equal `A + B` at compile time.
+    pub fn copy_to<const C: usize>(&self, dst: &mut [T; C])
+    where
+        T: Copy,
+    {
+        let () = AssertSum::<A, B, C>::OK;
+        let (head, tail) = dst.split_at_mut(A);
+        head.copy_from_slice(self.first);
+        tail.copy_from_slice(self.second);
+    }
+
+    /// Copies the chained elements into a new array, whose length `C`
+    /// is checked to equal `A + B` at compile time.
+    pub fn to_array<const C: usize>(&self) -> [T; C]
+    where
+        T: Copy,
+    {
+        let () = AssertSum::<A, B, C>::OK;
+        let mut items = self.iter();
+        core::array::from_fn(|_| *items.next().unwrap())
+    }
+}
+
+impl<'a, T, const A: usize, const B: usize> Clone for ChainedArrayRef<'a, T, A, B> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, const A: usize, const B: usize> Copy for ChainedArrayRef<'a, T, A, B> {}
+
+impl<'a, T, const A: usize, const B: usize> Index<usize> for ChainedArrayRef<'a, T, A, B> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        if index < A {
+            &self.first[index]
+        } else {
+            &self.second[index - A]
+        }
+    }
+}
+
+impl<'a, T, const A: usize, const B: usize> IntoIterator for ChainedArrayRef<'a, T, A, B> {
+    type Item = &'a T;
+    type IntoIter = Chain<slice::Iter<'a, T>, slice::Iter<'a, T>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn indexes_across_the_boundary() {
+        let a = [1, 2, 3];
+        let b = [4, 5];
+        let c = ChainedArrayRef::new(&a, &b);
+        let collected: std::vec::Vec<i32> = c.into_iter().cloned().collect();
+        assert_eq!(collected, [1, 2, 3, 4, 5]);
+        for i in 0..5 {
+            assert_eq!(c[i], collected[i]);
+            assert_eq!(c.get(i), Some(&collected[i]));
+        }
+        assert_eq!(c.get(5), None);
+        let mut out = [0; 5];
+        c.copy_to(&mut out);
+        assert_eq!(out, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_checks_bounds() {
+        let c = ChainedArrayRef::new(&[1u8], &[]);
+        let _ = c[1];
+    }
+}
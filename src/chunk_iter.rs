@@ -0,0 +1,630 @@
+//! Iterators over fixed-size chunks of slices.
+
+use core::iter::{FusedIterator, Zip};
+use core::{mem, slice};
+
+use chunks::{as_chunks, as_chunks_mut};
+
+/// An iterator over the non-overlapping `N`-element chunks of a slice,
+/// as array references, from `SliceExt::array_chunks_ref`.  Elements
+/// left over at the end, which do not make up a whole chunk, are not
+/// yielded but are available from `remainder`.
+#[derive(Debug, Clone)]
+pub struct ArrayChunks<'a, T: 'a, const N: usize> {
+    chunks: slice::Iter<'a, [T; N]>,
+    remainder: &'a [T],
+}
+
+impl<'a, T, const N: usize> ArrayChunks<'a, T, N> {
+    pub(crate) fn new(slice: &'a [T]) -> Self {
+        let (chunks, remainder) = as_chunks(slice);
+        ArrayChunks {
+            chunks: chunks.iter(),
+            remainder,
+        }
+    }
+
+    /// The elements at the end of the slice that do not make up a
+    /// whole chunk.
+    pub fn remainder(&self) -> &'a [T] {
+        self.remainder
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for ArrayChunks<'a, T, N> {
+    type Item = &'a [T; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [T; N]> {
+        self.chunks.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<&'a [T; N]> {
+        self.chunks.nth(n)
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for ArrayChunks<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [T; N]> {
+        self.chunks.next_back()
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for ArrayChunks<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for ArrayChunks<'a, T, N> {}
+
+/// An iterator over the non-overlapping `N`-element chunks of a
+/// mutable slice, as mutable array references, from
+/// `SliceExt::array_chunks_mut_ref`.  Elements left over at the end are
+/// not yielded but are available from `into_remainder`.
+#[derive(Debug)]
+pub struct ArrayChunksMut<'a, T: 'a, const N: usize> {
+    chunks: slice::IterMut<'a, [T; N]>,
+    remainder: &'a mut [T],
+}
+
+impl<'a, T, const N: usize> ArrayChunksMut<'a, T, N> {
+    pub(crate) fn new(slice: &'a mut [T]) -> Self {
+        let (chunks, remainder) = as_chunks_mut(slice);
+        ArrayChunksMut {
+            chunks: chunks.iter_mut(),
+            remainder,
+        }
+    }
+
+    /// The elements at the end of the slice that do not make up a
+    /// whole chunk.
+    pub fn remainder(&self) -> &[T] {
+        self.remainder
+    }
+
+    /// Gives up the iterator, returning the elements at the end of the
+    /// slice that do not make up a whole chunk.
+    pub fn into_remainder(self) -> &'a mut [T] {
+        self.remainder
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for ArrayChunksMut<'a, T, N> {
+    type Item = &'a mut [T; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut [T; N]> {
+        self.chunks.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<&'a mut [T; N]> {
+        self.chunks.nth(n)
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for ArrayChunksMut<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut [T; N]> {
+        self.chunks.next_back()
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for ArrayChunksMut<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for ArrayChunksMut<'a, T, N> {}
+
+/// An iterator over the overlapping `N`-element windows of a slice, as
+/// array references, from `SliceExt::array_windows_ref`.
+#[derive(Debug, Clone)]
+pub struct ArrayWindows<'a, T: 'a, const N: usize> {
+    slice: &'a [T],
+}
+
+impl<'a, T, const N: usize> ArrayWindows<'a, T, N> {
+    pub(crate) fn new(slice: &'a [T]) -> Self {
+        assert!(N != 0, "window length must be non-zero");
+        ArrayWindows { slice }
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for ArrayWindows<'a, T, N> {
+    type Item = &'a [T; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [T; N]> {
+        if self.slice.len() < N {
+            return None;
+        }
+        // The slice has at least `N` elements.
+        let window = unsafe { &*(self.slice.as_ptr() as *const [T; N]) };
+        self.slice = &self.slice[1..];
+        Some(window)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.slice.len().checked_sub(N) {
+            Some(extra) => extra + 1,
+            None => 0,
+        };
+        (len, Some(len))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for ArrayWindows<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [T; N]> {
+        if self.slice.len() < N {
+            return None;
+        }
+        let start = self.slice.len() - N;
+        let window = unsafe { &*(self.slice.as_ptr().add(start) as *const [T; N]) };
+        self.slice = &self.slice[..self.slice.len() - 1];
+        Some(window)
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for ArrayWindows<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for ArrayWindows<'a, T, N> {}
+
+/// An iterator over the non-overlapping `N`-element chunks of a slice,
+/// starting at the end, as array references, from
+/// `SliceExt::array_rchunks_ref`.  Elements left over at the *start*,
+/// which do not make up a whole chunk, are not yielded but are
+/// available from `remainder`.
+#[derive(Debug, Clone)]
+pub struct ArrayRChunks<'a, T: 'a, const N: usize> {
+    chunks: slice::Iter<'a, [T; N]>,
+    remainder: &'a [T],
+}
+
+impl<'a, T, const N: usize> ArrayRChunks<'a, T, N> {
+    pub(crate) fn new(slice: &'a [T]) -> Self {
+        assert!(N != 0, "chunk length must be non-zero");
+        let (remainder, rest) = slice.split_at(slice.len() % N);
+        ArrayRChunks {
+            chunks: as_chunks(rest).0.iter(),
+            remainder,
+        }
+    }
+
+    /// The elements at the start of the slice that do not make up a
+    /// whole chunk.
+    pub fn remainder(&self) -> &'a [T] {
+        self.remainder
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for ArrayRChunks<'a, T, N> {
+    type Item = &'a [T; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [T; N]> {
+        self.chunks.next_back()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<&'a [T; N]> {
+        self.chunks.nth_back(n)
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for ArrayRChunks<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [T; N]> {
+        self.chunks.next()
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for ArrayRChunks<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for ArrayRChunks<'a, T, N> {}
+
+/// An iterator over the non-overlapping `N`-element chunks of a
+/// mutable slice, starting at the end, as mutable array references,
+/// from `SliceExt::array_rchunks_mut_ref`.  Elements left over at the
+/// start are not yielded but are available from `into_remainder`.
+#[derive(Debug)]
+pub struct ArrayRChunksMut<'a, T: 'a, const N: usize> {
+    chunks: slice::IterMut<'a, [T; N]>,
+    remainder: &'a mut [T],
+}
+
+impl<'a, T, const N: usize> ArrayRChunksMut<'a, T, N> {
+    pub(crate) fn new(slice: &'a mut [T]) -> Self {
+        assert!(N != 0, "chunk length must be non-zero");
+        let len = slice.len();
+        let (remainder, rest) = slice.split_at_mut(len % N);
+        ArrayRChunksMut {
+            chunks: as_chunks_mut(rest).0.iter_mut(),
+            remainder,
+        }
+    }
+
+    /// The elements at the start of the slice that do not make up a
+    /// whole chunk.
+    pub fn remainder(&self) -> &[T] {
+        self.remainder
+    }
+
+    /// Gives up the iterator, returning the elements at the start of
+    /// the slice that do not make up a whole chunk.
+    pub fn into_remainder(self) -> &'a mut [T] {
+        self.remainder
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for ArrayRChunksMut<'a, T, N> {
+    type Item = &'a mut [T; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut [T; N]> {
+        self.chunks.next_back()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<&'a mut [T; N]> {
+        self.chunks.nth_back(n)
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for ArrayRChunksMut<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut [T; N]> {
+        self.chunks.next()
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for ArrayRChunksMut<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for ArrayRChunksMut<'a, T, N> {}
+
+/// The number of `N`-element arrays at offsets `0, stride, 2 * stride,
+/// ...` that fit in `len` elements.
+fn strided_count<const N: usize>(len: usize, stride: usize) -> usize {
+    match len.checked_sub(N) {
+        Some(extra) => extra / stride + 1,
+        None => 0,
+    }
+}
+
+fn check_stride<const N: usize>(stride: usize) {
+    assert!(
+        stride >= N && stride != 0,
+        "stride ({}) must be non-zero and at least the chunk length ({})",
+        stride,
+        N
+    );
+}
+
+/// An iterator over the `N`-element arrays at offsets `0, stride,
+/// 2 * stride, ...` of a slice, as array references, from
+/// `SliceExt::array_strided_ref`.  The elements between the arrays
+/// are skipped.
+#[derive(Debug, Clone)]
+pub struct ArrayStrided<'a, T: 'a, const N: usize> {
+    slice: &'a [T],
+    stride: usize,
+    count: usize,
+}
+
+impl<'a, T, const N: usize> ArrayStrided<'a, T, N> {
+    pub(crate) fn new(slice: &'a [T], stride: usize) -> Self {
+        check_stride::<N>(stride);
+        ArrayStrided {
+            slice,
+            stride,
+            count: strided_count::<N>(slice.len(), stride),
+        }
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for ArrayStrided<'a, T, N> {
+    type Item = &'a [T; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [T; N]> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        // There is at least one whole array left, so the slice has at
+        // least `N` elements.
+        let array = unsafe { &*(self.slice.as_ptr() as *const [T; N]) };
+        self.slice = &self.slice[self.stride.min(self.slice.len())..];
+        Some(array)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count, Some(self.count))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for ArrayStrided<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [T; N]> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        let offset = self.count * self.stride;
+        Some(unsafe { &*(self.slice.as_ptr().add(offset) as *const [T; N]) })
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for ArrayStrided<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for ArrayStrided<'a, T, N> {}
+
+/// An iterator over the `N`-element arrays at offsets `0, stride,
+/// 2 * stride, ...` of a mutable slice, as mutable array references,
+/// from `SliceExt::array_strided_mut_ref`.  The elements between the
+/// arrays are skipped.
+#[derive(Debug)]
+pub struct ArrayStridedMut<'a, T: 'a, const N: usize> {
+    slice: &'a mut [T],
+    stride: usize,
+    count: usize,
+}
+
+impl<'a, T, const N: usize> ArrayStridedMut<'a, T, N> {
+    pub(crate) fn new(slice: &'a mut [T], stride: usize) -> Self {
+        check_stride::<N>(stride);
+        let count = strided_count::<N>(slice.len(), stride);
+        ArrayStridedMut {
+            slice,
+            stride,
+            count,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> Iterator for ArrayStridedMut<'a, T, N> {
+    type Item = &'a mut [T; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut [T; N]> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        let slice = mem::take(&mut self.slice);
+        let step = self.stride.min(slice.len());
+        let (head, rest) = slice.split_at_mut(step);
+        self.slice = rest;
+        // Since `stride >= N`, `head` holds the whole array.
+        Some(unsafe { &mut *(head.as_mut_ptr() as *mut [T; N]) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count, Some(self.count))
+    }
+}
+
+impl<'a, T, const N: usize> DoubleEndedIterator for ArrayStridedMut<'a, T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut [T; N]> {
+        if self.count == 0 {
+            return None;
+        }
+        self.count -= 1;
+        let slice = mem::take(&mut self.slice);
+        let (front, back) = slice.split_at_mut(self.count * self.stride);
+        self.slice = front;
+        Some(unsafe { &mut *(back.as_mut_ptr() as *mut [T; N]) })
+    }
+}
+
+impl<'a, T, const N: usize> ExactSizeIterator for ArrayStridedMut<'a, T, N> {}
+
+impl<'a, T, const N: usize> FusedIterator for ArrayStridedMut<'a, T, N> {}
+
+/// You can use `zip_chunks` to walk two slices of the same length in
+/// lockstep, `N` elements at a time, getting pairs of array references.
+/// As for `SliceExt::array_chunks_ref`, a trailing partial chunk is not
+/// yielded; it is the last `a.len() % N` elements of each slice.
+///
+/// **Panics** if the slices have different lengths, or if `N` is zero.
+///
+/// ```
+/// use arrayref::zip_chunks;
+///
+/// let a = [1, 2, 3, 4, 5];
+/// let b = [10, 20, 30, 40, 50];
+/// let sums: Vec<[i32; 2]> = zip_chunks::<_, _, 2>(&a, &b)
+///     .map(|(x, y)| [x[0] + y[0], x[1] + y[1]])
+///     .collect();
+/// assert_eq!(sums, [[11, 22], [33, 44]]);
+/// ```
+pub fn zip_chunks<'a, 'b, T, U, const N: usize>(
+    a: &'a [T],
+    b: &'b [U],
+) -> Zip<ArrayChunks<'a, T, N>, ArrayChunks<'b, U, N>> {
+    check_zip_lengths(a.len(), b.len());
+    ArrayChunks::new(a).zip(ArrayChunks::new(b))
+}
+
+/// You can use `zip_chunks_mut` to walk a slice and a mutable slice of
+/// the same length in lockstep, `N` elements at a time, getting pairs
+/// of an array reference and a mutable array reference, as when
+/// combining a keystream into a message.  A trailing partial chunk is
+/// not yielded, as for `zip_chunks`.
+///
+/// **Panics** if the slices have different lengths, or if `N` is zero.
+///
+/// ```
+/// use arrayref::{xor_assign, zip_chunks_mut};
+///
+/// let keystream = [0x55u8; 8];
+/// let mut message = *b"ciphertext";
+/// for (key, block) in zip_chunks_mut::<_, _, 4>(&keystream, &mut message[..8]) {
+///     xor_assign(block, key);
+/// }
+/// assert_eq!(&message[8..], b"xt");
+/// assert_eq!(message[0], b'c' ^ 0x55);
+/// ```
+pub fn zip_chunks_mut<'a, 'b, T, U, const N: usize>(
+    a: &'a [T],
+    b: &'b mut [U],
+) -> Zip<ArrayChunks<'a, T, N>, ArrayChunksMut<'b, U, N>> {
+    check_zip_lengths(a.len(), b.len());
+    ArrayChunks::new(a).zip(ArrayChunksMut::new(b))
+}
+
+fn check_zip_lengths(a: usize, b: usize) {
+    assert!(
+        a == b,
+        "cannot zip chunks of slices of different lengths ({} and {})",
+        a,
+        b
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use slice_ext::SliceExt;
+    use std::vec::Vec;
+
+    #[test]
+    fn chunks_and_remainder_cover_the_slice() {
+        let data: Vec<u8> = (0..11).collect();
+        let mut chunks = data.array_chunks_ref::<4>();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks.next_back(), Some(&[4, 5, 6, 7]));
+        assert_eq!(chunks.remainder(), &[8, 9, 10]);
+        assert_eq!(chunks.collect::<Vec<_>>(), [&[0, 1, 2, 3]]);
+        assert_eq!(data[..8].array_chunks_ref::<4>().remainder(), &[]);
+        assert_eq!(data[..3].array_chunks_ref::<4>().count(), 0);
+    }
+
+    #[test]
+    fn mutable_chunks_are_disjoint() {
+        let mut data = [0u8; 10];
+        let mut chunks = data.array_chunks_mut_ref::<3>();
+        let (a, b) = (chunks.next().unwrap(), chunks.next_back().unwrap());
+        a[0] = 1;
+        b[0] = 3;
+        chunks.next().unwrap()[0] = 2;
+        assert_eq!(chunks.remainder(), &[0]);
+        chunks.into_remainder()[0] = 4;
+        assert_eq!(data, [1, 0, 0, 2, 0, 0, 3, 0, 0, 4]);
+    }
+
+    #[test]
+    fn windows_agree_with_slice_windows() {
+        let data: Vec<u32> = (0..7).collect();
+        for len in 0..=data.len() {
+            let slice = &data[..len];
+            let ours: Vec<&[u32]> = slice.array_windows_ref::<3>().map(|w| &w[..]).collect();
+            let theirs: Vec<&[u32]> = slice.windows(3).collect();
+            assert_eq!(ours, theirs);
+            assert_eq!(slice.array_windows_ref::<3>().len(), theirs.len());
+            let back: Vec<&[u32]> = slice
+                .array_windows_ref::<3>()
+                .rev()
+                .map(|w| &w[..])
+                .collect();
+            assert!(back.iter().eq(theirs.iter().rev()));
+        }
+    }
+
+    #[test]
+    fn rchunks_agree_with_slice_rchunks_exact() {
+        let mut data: Vec<u8> = (0..11).collect();
+        let theirs: Vec<&[u8]> = data.rchunks_exact(4).collect();
+        let ours = data.array_rchunks_ref::<4>();
+        assert_eq!(ours.remainder(), data.rchunks_exact(4).remainder());
+        assert!(ours.clone().map(|c| &c[..]).eq(theirs.iter().cloned()));
+        let mut ours = ours;
+        assert_eq!(ours.next_back(), Some(&[3, 4, 5, 6]));
+        {
+            let mut chunks = data.array_rchunks_mut_ref::<4>();
+            chunks.next().unwrap()[0] = 0xff;
+            assert_eq!(chunks.len(), 1);
+            chunks.into_remainder()[0] = 0xff;
+        }
+        assert_eq!((data[0], data[7]), (0xff, 0xff));
+    }
+
+    #[test]
+    fn zipped_chunks_stay_in_step() {
+        let a: Vec<u8> = (0..9).collect();
+        let mut b = [1u8; 9];
+        assert_eq!(zip_chunks::<_, _, 3>(&a, &b).len(), 3);
+        for (x, y) in zip_chunks_mut::<_, _, 2>(&a, &mut b) {
+            y[1] = x[0];
+        }
+        assert_eq!(b, [1, 0, 1, 2, 1, 4, 1, 6, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "different lengths (3 and 4)")]
+    fn zipped_lengths_must_agree() {
+        let _ = zip_chunks::<u8, u8, 1>(&[0; 3], &[0; 4]);
+    }
+
+    #[test]
+    fn strided_arrays_skip_the_gaps() {
+        let mut data: Vec<u8> = (0..11).collect();
+        let ours: Vec<&[u8; 2]> = data.array_strided_ref::<2>(4).collect();
+        assert_eq!(ours, [&[0, 1], &[4, 5], &[8, 9]]);
+        assert_eq!(data.array_strided_ref::<2>(4).len(), 3);
+        assert!(data
+            .array_strided_ref::<2>(4)
+            .rev()
+            .eq(ours.iter().rev().cloned()));
+        assert_eq!(data[..9].array_strided_ref::<3>(3).count(), 3);
+        assert_eq!(data[..2].array_strided_ref::<3>(5).count(), 0);
+        {
+            let mut rows = data.array_strided_mut_ref::<3>(4);
+            let (first, last) = (rows.next().unwrap(), rows.next_back().unwrap());
+            first[0] = 0xff;
+            last[2] = 0xff;
+            rows.next().unwrap()[1] = 0xff;
+            assert!(rows.next().is_none());
+        }
+        assert_eq!((data[0], data[5], data[10]), (0xff, 0xff, 0xff));
+    }
+
+    #[test]
+    #[should_panic]
+    fn stride_shorter_than_chunk_panics() {
+        [0u8; 8].array_strided_ref::<4>(3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_length_windows_panic() {
+        [0u8; 2].array_windows_ref::<0>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_length_chunks_panic() {
+        [0u8; 2].array_chunks_ref::<0>();
+    }
+}
@@ -0,0 +1,365 @@
+//! Functions that treat a slice as a sequence of fixed-size records.
+
+use core::cmp::Ordering;
+use core::slice;
+
+/// You can use `as_chunks` to view a slice as as many whole `N`-element
+/// arrays as fit, plus whatever is left over at the end, as the
+/// standard library's `slice::as_chunks` does.  No elements are
+/// copied.
+///
+/// **Panics** if `N` is zero.
+///
+/// ```
+/// use arrayref::as_chunks;
+///
+/// let data = [1u8, 2, 3, 4, 5, 6, 7];
+/// let (blocks, tail): (&[[u8; 3]], &[u8]) = as_chunks(&data);
+/// assert_eq!(blocks, &[[1, 2, 3], [4, 5, 6]]);
+/// assert_eq!(tail, &[7]);
+/// ```
+#[inline]
+pub fn as_chunks<T, const N: usize>(slice: &[T]) -> (&[[T; N]], &[T]) {
+    assert!(N != 0, "chunk length must be non-zero");
+    let count = slice.len() / N;
+    let (head, tail) = slice.split_at(count * N);
+    // `[T; N]` has the same layout as `N` consecutive `T`s, and `head`
+    // holds exactly `count * N` of them.
+    let chunks = unsafe { slice::from_raw_parts(head.as_ptr() as *const [T; N], count) };
+    (chunks, tail)
+}
+
+/// The mutable twin of `as_chunks`.
+///
+/// **Panics** if `N` is zero.
+///
+/// ```
+/// use arrayref::as_chunks_mut;
+///
+/// let mut data = [0u8; 10];
+/// let (blocks, tail) = as_chunks_mut::<u8, 4>(&mut data);
+/// for (i, block) in blocks.iter_mut().enumerate() {
+///     block[0] = i as u8 + 1;
+/// }
+/// tail.fill(9);
+/// assert_eq!(data, [1, 0, 0, 0, 2, 0, 0, 0, 9, 9]);
+/// ```
+#[inline]
+pub fn as_chunks_mut<T, const N: usize>(slice: &mut [T]) -> (&mut [[T; N]], &mut [T]) {
+    assert!(N != 0, "chunk length must be non-zero");
+    let count = slice.len() / N;
+    let (head, tail) = slice.split_at_mut(count * N);
+    let chunks = unsafe { slice::from_raw_parts_mut(head.as_mut_ptr() as *mut [T; N], count) };
+    (chunks, tail)
+}
+
+struct AssertTotalFits<const N: usize, const K: usize>;
+
+impl<const N: usize, const K: usize> AssertTotalFits<N, K> {
+    const TOTAL: usize = match N.checked_mul(K) {
+        Some(total) => total,
+        None => panic!("N * K must fit in a usize"),
+    };
+}
+
+struct AssertFlatLen<const N: usize, const M: usize, const L: usize>;
+
+impl<const N: usize, const M: usize, const L: usize> AssertFlatLen<N, M, L> {
+    const OK: () = assert!(
+        match N.checked_mul(M) {
+            Some(total) => total == L,
+            None => false,
+        },
+        "the flat array must have exactly N * M elements"
+    );
+}
+
+/// Support for `flatten_ref!`.
+#[doc(hidden)]
+#[inline]
+pub const fn flatten<T, const N: usize, const M: usize, const L: usize>(
+    nested: &[[T; N]; M],
+) -> &[T; L] {
+    let () = AssertFlatLen::<N, M, L>::OK;
+    // `[[T; N]; M]` has the same layout as `N * M` consecutive `T`s.
+    unsafe { &*(nested as *const [[T; N]; M] as *const [T; L]) }
+}
+
+/// Support for `flatten_mut_ref!`.
+#[doc(hidden)]
+#[inline]
+pub fn flatten_mut<T, const N: usize, const M: usize, const L: usize>(
+    nested: &mut [[T; N]; M],
+) -> &mut [T; L] {
+    let () = AssertFlatLen::<N, M, L>::OK;
+    unsafe { &mut *(nested as *mut [[T; N]; M] as *mut [T; L]) }
+}
+
+/// Support for `unflatten_ref!` and `split_even!`.
+#[doc(hidden)]
+#[inline]
+pub const fn unflatten<T, const N: usize, const M: usize, const L: usize>(
+    flat: &[T; L],
+) -> &[[T; N]; M] {
+    let () = AssertFlatLen::<N, M, L>::OK;
+    unsafe { &*(flat as *const [T; L] as *const [[T; N]; M]) }
+}
+
+/// Support for `unflatten_mut_ref!` and `split_even_mut!`.
+#[doc(hidden)]
+#[inline]
+pub fn unflatten_mut<T, const N: usize, const M: usize, const L: usize>(
+    flat: &mut [T; L],
+) -> &mut [[T; N]; M] {
+    let () = AssertFlatLen::<N, M, L>::OK;
+    unsafe { &mut *(flat as *mut [T; L] as *mut [[T; N]; M]) }
+}
+
+/// Support for `split_even!` and `split_even_mut!`, which know `M` but
+/// leave `N` and `L` to be inferred.  Naming `M` on the type rather
+/// than the function means `N` and `L` need no `_` placeholders.
+#[doc(hidden)]
+pub struct Pieces<const M: usize>;
+
+impl<const M: usize> Pieces<M> {
+    #[inline]
+    pub const fn split<T, const N: usize, const L: usize>(flat: &[T; L]) -> &[[T; N]; M] {
+        unflatten(flat)
+    }
+
+    #[inline]
+    pub fn split_mut<T, const N: usize, const L: usize>(flat: &mut [T; L]) -> &mut [[T; N]; M] {
+        unflatten_mut(flat)
+    }
+}
+
+/// You can use `take_many` to take `K` consecutive `N`-element arrays
+/// starting at `offset` in a single step, with one bounds check for
+/// the whole run rather than one per array.  It returns `None` if the
+/// run does not fit in the slice.
+///
+/// ```
+/// use arrayref::take_many;
+///
+/// // Three RGB pixels after a one-byte header.
+/// let data = [3, 255, 0, 0, 0, 255, 0, 0, 0, 255];
+/// let pixels: &[[u8; 3]; 3] = take_many(&data, 1).unwrap();
+/// assert_eq!(pixels[1], [0, 255, 0]);
+/// assert!(take_many::<u8, 3, 4>(&data, 1).is_none());
+/// ```
+#[inline]
+pub fn take_many<T, const N: usize, const K: usize>(
+    slice: &[T],
+    offset: usize,
+) -> Option<&[[T; N]; K]> {
+    let total = AssertTotalFits::<N, K>::TOTAL;
+    if offset > slice.len() || total > slice.len() - offset {
+        return None;
+    }
+    // `[[T; N]; K]` has the same layout as `N * K` consecutive `T`s.
+    Some(unsafe { &*(slice.as_ptr().add(offset) as *const [[T; N]; K]) })
+}
+
+/// The mutable version of `take_many`.
+#[inline]
+pub fn take_many_mut<T, const N: usize, const K: usize>(
+    slice: &mut [T],
+    offset: usize,
+) -> Option<&mut [[T; N]; K]> {
+    let total = AssertTotalFits::<N, K>::TOTAL;
+    if offset > slice.len() || total > slice.len() - offset {
+        return None;
+    }
+    Some(unsafe { &mut *(slice.as_mut_ptr().add(offset) as *mut [[T; N]; K]) })
+}
+
+/// You can use `binary_search_chunks` to binary search a slice that
+/// holds sorted records of `N` elements each, such as the entries of
+/// an on-disk sorted table.  The comparator is handed each record as
+/// an array reference, and should return whether that record is
+/// `Less`, `Equal` or `Greater` than the target, just as for
+/// `slice::binary_search_by`.
+///
+/// The result is the index of a matching *record* (not element), or
+/// the record index at which the target could be inserted.  Any
+/// trailing elements that do not make up a whole record are ignored.
+///
+/// **Panics** if `N` is zero.
+///
+/// ```
+/// use arrayref::binary_search_chunks;
+///
+/// // records are a big-endian u16 key followed by a u16 value
+/// let table = [0,1, 0,10,  0,4, 0,40,  0,9, 0,90];
+/// let find = |key: u16| binary_search_chunks(&table, |rec: &[u8; 4]| {
+///     u16::from_be_bytes([rec[0], rec[1]]).cmp(&key)
+/// });
+/// assert_eq!(find(4), Ok(1));
+/// assert_eq!(find(5), Err(2));
+/// ```
+pub fn binary_search_chunks<T, const N: usize>(
+    slice: &[T],
+    cmp: impl FnMut(&[T; N]) -> Ordering,
+) -> Result<usize, usize> {
+    as_chunks(slice).0.binary_search_by(cmp)
+}
+
+/// You can use `partition_point_chunks` to find the partition point
+/// of a slice made up of `N`-element records, that is the index of
+/// the first record for which `pred` returns `false`, assuming the
+/// records are partitioned so that all records for which `pred` is
+/// `true` come first.  Any trailing elements that do not make up a
+/// whole record are ignored.
+///
+/// **Panics** if `N` is zero.
+///
+/// ```
+/// use arrayref::partition_point_chunks;
+///
+/// let table = [1,0, 3,0, 3,1, 7,0];
+/// assert_eq!(partition_point_chunks(&table, |rec: &[u8; 2]| rec[0] < 3), 1);
+/// assert_eq!(partition_point_chunks(&table, |rec: &[u8; 2]| rec[0] <= 3), 3);
+/// ```
+pub fn partition_point_chunks<T, const N: usize>(
+    slice: &[T],
+    pred: impl FnMut(&[T; N]) -> bool,
+) -> usize {
+    as_chunks(slice).0.partition_point(pred)
+}
+
+/// Where `diff_chunks` found two slices to first differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkDiff<'a, 'b, T: 'a + 'b, const N: usize> {
+    /// The block at this index differs, and here are both versions of
+    /// it.
+    Block(usize, &'a [T; N], &'b [T; N]),
+    /// Every block the two slices both have in full matches, but they
+    /// differ from the block at this index on: either they have
+    /// different lengths, or their trailing partial blocks differ.
+    Tail(usize),
+}
+
+/// You can use `diff_chunks` to find the first `N`-element block at
+/// which two slices differ, as when comparing a file against a backup
+/// a page at a time.  It returns `ChunkDiff::Block` with the index of
+/// that block and both versions of it, `ChunkDiff::Tail` if the slices
+/// differ only in length or in a trailing partial block, or `None` if
+/// the slices are equal.
+///
+/// **Panics** if `N` is zero.
+///
+/// ```
+/// use arrayref::{diff_chunks, ChunkDiff};
+///
+/// let old = [0u8, 0, 0, 0, 1, 2, 3, 4, 9, 9];
+/// let new = [0u8, 0, 0, 0, 1, 2, 0, 4, 9];
+/// assert_eq!(
+///     diff_chunks(&old, &new),
+///     Some(ChunkDiff::Block(1, &[1, 2, 3, 4], &[1, 2, 0, 4]))
+/// );
+/// assert_eq!(diff_chunks::<u8, 4>(&old[..4], &new), Some(ChunkDiff::Tail(1)));
+/// assert_eq!(diff_chunks::<u8, 4>(&old, &old), None);
+/// ```
+pub fn diff_chunks<'a, 'b, T: PartialEq, const N: usize>(
+    a: &'a [T],
+    b: &'b [T],
+) -> Option<ChunkDiff<'a, 'b, T, N>> {
+    let (a_blocks, a_rest) = as_chunks::<T, N>(a);
+    let (b_blocks, b_rest) = as_chunks::<T, N>(b);
+    let block = a_blocks
+        .iter()
+        .zip(b_blocks)
+        .enumerate()
+        .find(|(_, (x, y))| x != y);
+    if let Some((i, (x, y))) = block {
+        Some(ChunkDiff::Block(i, x, y))
+    } else if a.len() != b.len() || a_rest != b_rest {
+        Some(ChunkDiff::Tail(a_blocks.len().min(b_blocks.len())))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn binary_search_finds_every_record() {
+        let table: Vec<u8> = (0..50u8).flat_map(|k| [2 * k, 0, 0]).collect();
+        for k in 0..50u8 {
+            let found = binary_search_chunks(&table, |rec: &[u8; 3]| rec[0].cmp(&(2 * k)));
+            assert_eq!(found, Ok(k as usize));
+            let missing = binary_search_chunks(&table, |rec: &[u8; 3]| rec[0].cmp(&(2 * k + 1)));
+            assert_eq!(missing, Err(k as usize + 1));
+        }
+    }
+
+    #[test]
+    fn take_many_matches_array_refs() {
+        let mut data = [0u8, 1, 2, 3, 4, 5, 6, 7];
+        let runs: &[[u8; 2]; 3] = take_many(&data, 1).unwrap();
+        assert_eq!(runs, &[[1, 2], [3, 4], [5, 6]]);
+        assert!(take_many::<u8, 2, 4>(&data, 1).is_none());
+        assert!(take_many::<u8, 1, 1>(&data, usize::MAX).is_none());
+        assert_eq!(take_many::<u8, 0, 9>(&data, 8), Some(&[[]; 9]));
+        take_many_mut::<u8, 4, 2>(&mut data, 0).unwrap()[1] = [9; 4];
+        assert_eq!(data, [0, 1, 2, 3, 9, 9, 9, 9]);
+    }
+
+    #[test]
+    fn trailing_partial_record_is_ignored() {
+        let table = [1u8, 2, 3, 4, 5];
+        assert_eq!(
+            binary_search_chunks(&table, |rec: &[u8; 2]| rec[0].cmp(&5)),
+            Err(2)
+        );
+        assert_eq!(partition_point_chunks(&table, |_: &[u8; 2]| true), 2);
+    }
+
+    #[test]
+    fn diff_finds_first_changed_block() {
+        let a: Vec<u8> = (0..64).collect();
+        let mut b = a.clone();
+        assert_eq!(diff_chunks::<u8, 8>(&a, &b), None);
+        b[45] = 0;
+        b[60] = 0;
+        match diff_chunks::<u8, 8>(&a, &b) {
+            Some(ChunkDiff::Block(i, x, y)) => assert_eq!((i, x[5], y[5]), (5, 45, 0)),
+            other => panic!("expected block 5 to differ, got {:?}", other),
+        }
+        assert_eq!(diff_chunks::<u8, 8>(&a[..40], &b[..40]), None);
+        assert_eq!(
+            diff_chunks::<u8, 8>(&a[..47], &b[..47]),
+            Some(ChunkDiff::Tail(5))
+        );
+    }
+
+    #[test]
+    fn diff_reports_unequal_lengths() {
+        assert_eq!(
+            diff_chunks::<u8, 4>(&[0; 8], &[0; 4096]),
+            Some(ChunkDiff::Tail(2))
+        );
+        assert_eq!(
+            diff_chunks::<u8, 4>(&[0; 4096], &[0; 9]),
+            Some(ChunkDiff::Tail(2))
+        );
+        assert_eq!(diff_chunks::<u8, 4>(&[], &[0]), Some(ChunkDiff::Tail(0)));
+    }
+
+    #[test]
+    fn diff_reports_differing_partial_tail() {
+        let a = [1u8, 2, 3, 4, 5, 6];
+        let b = [1u8, 2, 3, 4, 5, 7];
+        assert_eq!(diff_chunks::<u8, 4>(&a, &b), Some(ChunkDiff::Tail(1)));
+        assert_eq!(diff_chunks::<u8, 4>(&a, &a), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_length_records_panic() {
+        binary_search_chunks(&[1u8, 2], |_: &[u8; 0]| Ordering::Equal).ok();
+    }
+}
@@ -0,0 +1,128 @@
+//! Array references that may be borrowed or owned.
+
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+/// A `CowArrayRef` is either a borrowed `&'a [T; N]` or an owned
+/// `[T; N]`, and dereferences to a `[T; N]` either way.  It is
+/// returned by operations, such as reading across the wraparound of a
+/// ring buffer, that can usually borrow but sometimes have to copy,
+/// so that callers get one type and only pay for the copy when it
+/// happens.
+///
+/// Comparisons and hashing look only at the elements, not at whether
+/// they are borrowed.
+///
+/// ```
+/// use arrayref::CowArrayRef;
+///
+/// let data = [1, 2, 3];
+/// let borrowed = CowArrayRef::from(&data);
+/// let owned = CowArrayRef::from([1, 2, 3]);
+/// assert_eq!(borrowed, owned);
+/// assert!(borrowed.is_borrowed() && owned.is_owned());
+/// assert_eq!(borrowed.into_owned(), data);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub enum CowArrayRef<'a, T: 'a, const N: usize> {
+    /// The elements are borrowed.
+    Borrowed(&'a [T; N]),
+    /// The elements had to be copied.
+    Owned([T; N]),
+}
+
+impl<'a, T, const N: usize> CowArrayRef<'a, T, N> {
+    /// Whether the elements are borrowed.
+    pub fn is_borrowed(&self) -> bool {
+        match self {
+            CowArrayRef::Borrowed(_) => true,
+            CowArrayRef::Owned(_) => false,
+        }
+    }
+
+    /// Whether the elements are owned.
+    pub fn is_owned(&self) -> bool {
+        !self.is_borrowed()
+    }
+
+    /// The elements as an owned array, cloning them if borrowed.
+    pub fn into_owned(self) -> [T; N]
+    where
+        T: Clone,
+    {
+        match self {
+            CowArrayRef::Borrowed(a) => a.clone(),
+            CowArrayRef::Owned(a) => a,
+        }
+    }
+
+    /// A mutable reference to the elements, cloning them first if
+    /// they are borrowed.
+    pub fn to_mut(&mut self) -> &mut [T; N]
+    where
+        T: Clone,
+    {
+        if let CowArrayRef::Borrowed(a) = *self {
+            *self = CowArrayRef::Owned(a.clone());
+        }
+        match self {
+            CowArrayRef::Owned(a) => a,
+            CowArrayRef::Borrowed(_) => unreachable!(),
+        }
+    }
+}
+
+impl<'a, T, const N: usize> Deref for CowArrayRef<'a, T, N> {
+    type Target = [T; N];
+    fn deref(&self) -> &[T; N] {
+        match self {
+            CowArrayRef::Borrowed(a) => a,
+            CowArrayRef::Owned(a) => a,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> From<&'a [T; N]> for CowArrayRef<'a, T, N> {
+    fn from(a: &'a [T; N]) -> Self {
+        CowArrayRef::Borrowed(a)
+    }
+}
+
+impl<'a, T, const N: usize> From<[T; N]> for CowArrayRef<'a, T, N> {
+    fn from(a: [T; N]) -> Self {
+        CowArrayRef::Owned(a)
+    }
+}
+
+impl<'a, 'b, T: PartialEq, const N: usize> PartialEq<CowArrayRef<'b, T, N>>
+    for CowArrayRef<'a, T, N>
+{
+    fn eq(&self, other: &CowArrayRef<'b, T, N>) -> bool {
+        **self == **other
+    }
+}
+
+impl<'a, T: Eq, const N: usize> Eq for CowArrayRef<'a, T, N> {}
+
+impl<'a, T: Hash, const N: usize> Hash for CowArrayRef<'a, T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::String;
+
+    #[test]
+    fn to_mut_copies_only_once() {
+        let data = [String::from("a"), String::from("b")];
+        let mut cow = CowArrayRef::from(&data);
+        cow.to_mut()[0].push('!');
+        assert!(cow.is_owned());
+        cow.to_mut()[1].push('?');
+        assert_eq!(*cow, ["a!", "b?"]);
+        assert_eq!(data, ["a", "b"]);
+    }
+}
@@ -0,0 +1,493 @@
+//! Cursors that hand out consecutive array references.
+
+use core::marker::PhantomData;
+use core::mem;
+use core::slice;
+
+use bounds::{check, sub_slice, try_sub_slice};
+use chunks::{take_many, take_many_mut};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Whether a cursor checks each take against the length of its input:
+/// either `Checked`, the default, or `TrustedInput`.
+pub trait Checking: sealed::Sealed {
+    #[doc(hidden)]
+    const CHECKED: bool;
+}
+
+/// The default mode of a cursor, in which every take is checked
+/// against the length of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Checked;
+
+/// The mode of a cursor made with an unsafe `new_trusted` constructor,
+/// whose panicking takes skip the bounds check.  The caller vouches
+/// once, when making the cursor, that every take it will make fits;
+/// the `try_` methods still check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TrustedInput;
+
+impl sealed::Sealed for Checked {}
+impl sealed::Sealed for TrustedInput {}
+
+impl Checking for Checked {
+    const CHECKED: bool = true;
+}
+
+impl Checking for TrustedInput {
+    const CHECKED: bool = false;
+}
+
+/// Splits `slice` at `mid` without checking that `mid` is in bounds.
+#[inline]
+unsafe fn split_at_mut_unchecked<T>(slice: &mut [T], mid: usize) -> (&mut [T], &mut [T]) {
+    let (ptr, len) = (slice.as_mut_ptr(), slice.len());
+    (
+        slice::from_raw_parts_mut(ptr, mid),
+        slice::from_raw_parts_mut(ptr.add(mid), len - mid),
+    )
+}
+
+/// An `ArrayCursor` reads consecutive array references and slices from
+/// the front of a slice, keeping track of the position so that a
+/// parser does not have to repeat the offset of every field.
+///
+/// The panicking methods report failures just as `array_ref!` does,
+/// and the `try_` methods return `None` instead; either way, a failed
+/// take leaves the cursor where it was.  A parser that has already
+/// checked the length of its whole input, say along with a framing
+/// checksum, can make its cursor with `new_trusted` to skip the
+/// per-take checks.
+///
+/// ```
+/// use arrayref::ArrayCursor;
+///
+/// let packet = [0x01, 0x00, 0x05, 0xaa, 0xbb, b'h', b'e', b'l', b'l', b'o'];
+/// let mut cursor = ArrayCursor::new(&packet);
+/// let [version] = *cursor.take::<1>();
+/// let len = u16::from_be_bytes(*cursor.take());
+/// cursor.skip(2);
+/// let body = cursor.take_slice(len as usize);
+/// assert_eq!((version, body), (1, &b"hello"[..]));
+/// assert_eq!((cursor.position(), cursor.remaining()), (10, 0));
+/// assert!(cursor.try_take::<1>().is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ArrayCursor<'a, T: 'a, C = Checked> {
+    slice: &'a [T],
+    position: usize,
+    mode: PhantomData<C>,
+}
+
+impl<'a, T> ArrayCursor<'a, T> {
+    /// A cursor at the start of `slice`.
+    pub const fn new(slice: &'a [T]) -> Self {
+        ArrayCursor {
+            slice,
+            position: 0,
+            mode: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> ArrayCursor<'a, T, TrustedInput> {
+    /// A cursor at the start of `slice` whose panicking takes do not
+    /// check that there are enough elements left.
+    ///
+    /// ```
+    /// use arrayref::ArrayCursor;
+    ///
+    /// let frame = [0x00, 0x03, b'a', b'b', b'c'];
+    /// assert_eq!(frame.len(), 2 + frame[1] as usize);
+    /// // Safety: the length field has been checked against the frame.
+    /// let mut cursor = unsafe { ArrayCursor::new_trusted(&frame) };
+    /// let len = u16::from_be_bytes(*cursor.take());
+    /// assert_eq!(cursor.take_slice(len as usize), b"abc");
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Every `take`, `take_slice`, `take_many` and `skip` made through
+    /// the cursor must fit in what is left of `slice`.
+    pub const unsafe fn new_trusted(slice: &'a [T]) -> Self {
+        ArrayCursor {
+            slice,
+            position: 0,
+            mode: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, C: Checking> ArrayCursor<'a, T, C> {
+    /// Takes the next `N` elements as an array reference.
+    ///
+    /// **Panics** if fewer than `N` elements remain.
+    #[inline]
+    pub fn take<const N: usize>(&mut self) -> &'a [T; N] {
+        let slice = self.take_slice(N);
+        // `slice` has exactly `N` elements.
+        unsafe { &*(slice.as_ptr() as *const [T; N]) }
+    }
+
+    /// Takes the next `N` elements as an array reference, or returns
+    /// `None` if fewer than `N` remain.
+    #[inline]
+    pub fn try_take<const N: usize>(&mut self) -> Option<&'a [T; N]> {
+        let slice = try_sub_slice(self.slice, self.position, N)?;
+        self.position += N;
+        Some(unsafe { &*(slice.as_ptr() as *const [T; N]) })
+    }
+
+    /// Takes the next `N` elements as an array reference without
+    /// checking that they are there.
+    ///
+    /// # Safety
+    ///
+    /// At least `N` elements must remain.
+    #[inline]
+    pub unsafe fn take_unchecked<const N: usize>(&mut self) -> &'a [T; N] {
+        let slice = self.slice.get_unchecked(self.position..self.position + N);
+        self.position += N;
+        &*(slice.as_ptr() as *const [T; N])
+    }
+
+    /// Takes the next `len` elements as a slice.
+    ///
+    /// **Panics** if fewer than `len` elements remain.
+    #[inline]
+    pub fn take_slice(&mut self, len: usize) -> &'a [T] {
+        let slice = if C::CHECKED {
+            sub_slice(self.slice, self.position, len)
+        } else {
+            // The caller of `new_trusted` vouched that this fits.
+            unsafe { self.slice.get_unchecked(self.position..self.position + len) }
+        };
+        self.position += len;
+        slice
+    }
+
+    /// Takes the next `len` elements as a slice, or returns `None` if
+    /// fewer than `len` remain.
+    #[inline]
+    pub fn try_take_slice(&mut self, len: usize) -> Option<&'a [T]> {
+        let slice = try_sub_slice(self.slice, self.position, len)?;
+        self.position += len;
+        Some(slice)
+    }
+
+    /// Takes the next `N * K` elements as `K` consecutive `N`-element
+    /// arrays, with a single bounds check for the lot.
+    ///
+    /// **Panics** if fewer than `N * K` elements remain.
+    #[inline]
+    pub fn take_many<const N: usize, const K: usize>(&mut self) -> &'a [[T; N]; K] {
+        let slice = self.take_slice(N * K);
+        // `slice` has exactly `N * K` elements, so this cannot fail.
+        take_many(slice, 0).unwrap()
+    }
+
+    /// Takes the next `N * K` elements as `K` consecutive `N`-element
+    /// arrays, or returns `None` if fewer than `N * K` remain.
+    #[inline]
+    pub fn try_take_many<const N: usize, const K: usize>(&mut self) -> Option<&'a [[T; N]; K]> {
+        let arrays = take_many(self.slice, self.position)?;
+        self.position += N * K;
+        Some(arrays)
+    }
+
+    /// Skips over the next `len` elements.
+    ///
+    /// **Panics** if fewer than `len` elements remain.
+    #[inline]
+    pub fn skip(&mut self, len: usize) {
+        self.take_slice(len);
+    }
+
+    /// The number of elements taken or skipped so far.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The number of elements left to take.
+    pub const fn remaining(&self) -> usize {
+        self.slice.len() - self.position
+    }
+
+    /// The elements left to take, without taking them.
+    pub fn rest(&self) -> &'a [T] {
+        &self.slice[self.position..]
+    }
+}
+
+/// An `ArrayCursorMut` hands out consecutive mutable array references
+/// and slices from the front of a mutable slice, keeping track of how
+/// much has been written, for building a packet in a pre-allocated
+/// buffer.
+///
+/// As for `ArrayCursor`, the panicking methods report failures just as
+/// `array_mut_ref!` does, the `try_` methods return `None` instead,
+/// and a failed take leaves the cursor where it was.
+///
+/// ```
+/// use arrayref::ArrayCursorMut;
+///
+/// let mut buf = [0u8; 16];
+/// let mut cursor = ArrayCursorMut::new(&mut buf);
+/// *cursor.take_mut() = [0x01];
+/// let len = cursor.take_mut::<2>();
+/// let body = cursor.take_slice_mut(5);
+/// body.copy_from_slice(b"hello");
+/// *len = (body.len() as u16).to_be_bytes();
+/// assert_eq!((cursor.written(), cursor.remaining()), (8, 8));
+/// assert_eq!(buf[..8], [0x01, 0x00, 0x05, b'h', b'e', b'l', b'l', b'o']);
+/// ```
+#[derive(Debug)]
+pub struct ArrayCursorMut<'a, T: 'a, C = Checked> {
+    rest: &'a mut [T],
+    written: usize,
+    mode: PhantomData<C>,
+}
+
+impl<'a, T> ArrayCursorMut<'a, T> {
+    /// A cursor at the start of `slice`.
+    pub fn new(slice: &'a mut [T]) -> Self {
+        ArrayCursorMut {
+            rest: slice,
+            written: 0,
+            mode: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> ArrayCursorMut<'a, T, TrustedInput> {
+    /// A cursor at the start of `slice` whose panicking takes do not
+    /// check that there is enough room left, as for
+    /// `ArrayCursor::new_trusted`.
+    ///
+    /// # Safety
+    ///
+    /// Every `take_mut`, `take_slice_mut`, `take_many_mut` and `skip`
+    /// made through the cursor must fit in what is left of `slice`.
+    pub unsafe fn new_trusted(slice: &'a mut [T]) -> Self {
+        ArrayCursorMut {
+            rest: slice,
+            written: 0,
+            mode: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, C: Checking> ArrayCursorMut<'a, T, C> {
+    /// Takes the next `N` elements as a mutable array reference.
+    ///
+    /// **Panics** if fewer than `N` elements remain.
+    #[inline]
+    pub fn take_mut<const N: usize>(&mut self) -> &'a mut [T; N] {
+        let slice = self.take_slice_mut(N);
+        // `slice` has exactly `N` elements.
+        unsafe { &mut *(slice.as_mut_ptr() as *mut [T; N]) }
+    }
+
+    /// Takes the next `N` elements as a mutable array reference, or
+    /// returns `None` if fewer than `N` remain.
+    #[inline]
+    pub fn try_take_mut<const N: usize>(&mut self) -> Option<&'a mut [T; N]> {
+        let slice = self.try_take_slice_mut(N)?;
+        Some(unsafe { &mut *(slice.as_mut_ptr() as *mut [T; N]) })
+    }
+
+    /// Takes the next `len` elements as a mutable slice.
+    ///
+    /// **Panics** if fewer than `len` elements remain.
+    #[inline]
+    pub fn take_slice_mut(&mut self, len: usize) -> &'a mut [T] {
+        let (head, tail) = if C::CHECKED {
+            check(self.written, len, self.written + self.rest.len());
+            mem::take(&mut self.rest).split_at_mut(len)
+        } else {
+            // The caller of `new_trusted` vouched that this fits.
+            unsafe { split_at_mut_unchecked(mem::take(&mut self.rest), len) }
+        };
+        self.rest = tail;
+        self.written += len;
+        head
+    }
+
+    /// Takes the next `len` elements as a mutable slice, or returns
+    /// `None` if fewer than `len` remain.
+    #[inline]
+    pub fn try_take_slice_mut(&mut self, len: usize) -> Option<&'a mut [T]> {
+        if len > self.rest.len() {
+            return None;
+        }
+        Some(self.take_slice_mut(len))
+    }
+
+    /// Takes the next `N` elements as a mutable array reference without
+    /// checking that they are there.
+    ///
+    /// # Safety
+    ///
+    /// At least `N` elements must remain.
+    #[inline]
+    pub unsafe fn take_mut_unchecked<const N: usize>(&mut self) -> &'a mut [T; N] {
+        let (head, tail) = split_at_mut_unchecked(mem::take(&mut self.rest), N);
+        self.rest = tail;
+        self.written += N;
+        &mut *(head.as_mut_ptr() as *mut [T; N])
+    }
+
+    /// Takes the next `N * K` elements as `K` consecutive mutable
+    /// `N`-element arrays, with a single bounds check for the lot.
+    ///
+    /// **Panics** if fewer than `N * K` elements remain.
+    #[inline]
+    pub fn take_many_mut<const N: usize, const K: usize>(&mut self) -> &'a mut [[T; N]; K] {
+        let slice = self.take_slice_mut(N * K);
+        take_many_mut(slice, 0).unwrap()
+    }
+
+    /// Takes the next `N * K` elements as `K` consecutive mutable
+    /// `N`-element arrays, or returns `None` if fewer than `N * K`
+    /// remain.
+    #[inline]
+    pub fn try_take_many_mut<const N: usize, const K: usize>(
+        &mut self,
+    ) -> Option<&'a mut [[T; N]; K]> {
+        take_many::<T, N, K>(self.rest, 0)?;
+        Some(self.take_many_mut())
+    }
+
+    /// Skips over the next `len` elements, leaving them as they are.
+    ///
+    /// **Panics** if fewer than `len` elements remain.
+    #[inline]
+    pub fn skip(&mut self, len: usize) {
+        self.take_slice_mut(len);
+    }
+
+    /// The number of elements taken or skipped so far.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// The number of elements left to take.
+    pub fn remaining(&self) -> usize {
+        self.rest.len()
+    }
+
+    /// Gives up the cursor, returning the elements left to take.
+    pub fn into_rest(self) -> &'a mut [T] {
+        self.rest
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn takes_are_consecutive() {
+        let data = [0u8, 1, 2, 3, 4, 5, 6];
+        let mut cursor = ArrayCursor::new(&data);
+        assert_eq!(cursor.take::<2>(), &[0, 1]);
+        assert_eq!(cursor.try_take_slice(3), Some(&[2, 3, 4][..]));
+        assert_eq!(cursor.try_take::<3>(), None);
+        assert_eq!(cursor.try_take_slice(3), None);
+        assert_eq!((cursor.position(), cursor.rest()), (5, &[5, 6][..]));
+        assert_eq!(cursor.try_take::<2>(), Some(&[5, 6]));
+        assert_eq!(cursor.take::<0>(), &[]);
+        assert_eq!(cursor.remaining(), 0);
+    }
+
+    #[test]
+    fn mutable_takes_are_disjoint() {
+        let mut data = [0u8; 7];
+        {
+            let mut cursor = ArrayCursorMut::new(&mut data);
+            let a = cursor.take_mut::<2>();
+            cursor.skip(1);
+            let b = cursor.try_take_slice_mut(2).unwrap();
+            assert!(cursor.try_take_mut::<3>().is_none());
+            assert_eq!((cursor.written(), cursor.remaining()), (5, 2));
+            *a = [1, 1];
+            b[1] = 2;
+            cursor.into_rest()[1] = 3;
+        }
+        assert_eq!(data, [1, 1, 0, 0, 2, 0, 3]);
+    }
+
+    #[test]
+    fn take_many_checks_the_total() {
+        let mut data = [0u8, 1, 2, 3, 4, 5, 6];
+        let mut cursor = ArrayCursor::new(&data);
+        cursor.skip(1);
+        assert_eq!(cursor.take_many::<2, 2>(), &[[1, 2], [3, 4]]);
+        assert_eq!(cursor.try_take_many::<1, 3>(), None);
+        assert_eq!(cursor.try_take_many::<2, 1>(), Some(&[[5, 6]]));
+
+        let mut cursor = ArrayCursorMut::new(&mut data);
+        let pairs = cursor.take_many_mut::<2, 3>();
+        assert!(cursor.try_take_many_mut::<1, 2>().is_none());
+        assert_eq!(cursor.remaining(), 1);
+        pairs[1] = [9, 9];
+        assert_eq!(data, [0, 1, 9, 9, 4, 5, 6]);
+    }
+
+    #[test]
+    fn unchecked_takes_match_checked_ones() {
+        let mut data = [0u8, 1, 2, 3, 4];
+        let mut cursor = ArrayCursor::new(&data);
+        assert_eq!(unsafe { cursor.take_unchecked::<2>() }, &[0, 1]);
+        assert_eq!(cursor.take_slice(3), &[2, 3, 4]);
+
+        let mut cursor = ArrayCursorMut::new(&mut data);
+        *unsafe { cursor.take_mut_unchecked::<1>() } = [6];
+        assert_eq!((cursor.written(), cursor.remaining()), (1, 4));
+        cursor.take_slice_mut(4).fill(8);
+        assert_eq!(data, [6, 8, 8, 8, 8]);
+    }
+
+    #[test]
+    fn trusted_takes_match_checked_ones() {
+        let mut data = [0u8, 1, 2, 3, 4, 5, 6];
+        let mut cursor = unsafe { ArrayCursor::new_trusted(&data) };
+        assert_eq!(cursor.take::<2>(), &[0, 1]);
+        cursor.skip(1);
+        assert_eq!(cursor.take_many::<1, 2>(), &[[3], [4]]);
+        assert_eq!(cursor.try_take::<3>(), None);
+        assert_eq!(cursor.take_slice(2), &[5, 6]);
+
+        let mut cursor = unsafe { ArrayCursorMut::new_trusted(&mut data) };
+        cursor.take_slice_mut(2).fill(7);
+        cursor.skip(1);
+        *cursor.take_mut() = [9];
+        assert!(cursor.try_take_mut::<4>().is_none());
+        assert_eq!((cursor.written(), cursor.remaining()), (4, 3));
+        cursor.into_rest().fill(8);
+        assert_eq!(data, [7, 7, 2, 9, 8, 8, 8]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn take_many_checks_bounds() {
+        ArrayCursor::new(&[0u8; 5]).take_many::<2, 3>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn mutable_take_checks_bounds() {
+        ArrayCursorMut::new(&mut [0u8; 2]).take_mut::<3>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn skip_checks_bounds() {
+        let mut cursor = ArrayCursor::new(&[0u8; 4]);
+        cursor.skip(3);
+        cursor.skip(2);
+    }
+}
@@ -0,0 +1,251 @@
+//! Packed BCD and zoned-decimal numbers in fixed-size fields.
+//!
+//! Signed formats follow the IBM conventions: a sign nibble of `0xC`,
+//! `0xA`, `0xE` or `0xF` means positive and `0xD` or `0xB` negative.
+//! Values are written with `0xC` or `0xD`.
+
+use core::convert::TryFrom;
+
+use error::DecimalError;
+
+fn digit(nibble: u8, position: usize) -> Result<u8, DecimalError> {
+    if nibble < 10 {
+        Ok(nibble)
+    } else {
+        Err(DecimalError::InvalidDigit { position })
+    }
+}
+
+fn is_negative(sign: u8) -> Result<bool, DecimalError> {
+    match sign {
+        0xa | 0xc | 0xe | 0xf => Ok(false),
+        0xb | 0xd => Ok(true),
+        _ => Err(DecimalError::InvalidSign),
+    }
+}
+
+fn accumulate(digits: impl Iterator<Item = Result<u8, DecimalError>>) -> Result<u64, DecimalError> {
+    let mut total = 0u64;
+    for d in digits {
+        let d = d?;
+        total = total
+            .checked_mul(10)
+            .and_then(|t| t.checked_add(d as u64))
+            .ok_or(DecimalError::Overflow)?;
+    }
+    Ok(total)
+}
+
+fn apply_sign(magnitude: u64, negative: bool) -> Result<i64, DecimalError> {
+    if negative {
+        0i64.checked_sub_unsigned(magnitude)
+    } else {
+        i64::try_from(magnitude).ok()
+    }
+    .ok_or(DecimalError::Overflow)
+}
+
+/// The nibbles of `bytes`, most significant first.
+fn nibbles(bytes: &[u8]) -> impl Iterator<Item = u8> + '_ {
+    bytes.iter().flat_map(|&b| [b >> 4, b & 0xf])
+}
+
+/// Writes the low `bytes.len() * 2` decimal digits of `n` into `bytes`
+/// as packed BCD, returning what is left of `n`.
+fn pack_digits(bytes: &mut [u8], mut n: u64) -> u64 {
+    for b in bytes.iter_mut().rev() {
+        *b = (n % 10) as u8;
+        n /= 10;
+        *b |= ((n % 10) as u8) << 4;
+        n /= 10;
+    }
+    n
+}
+
+/// Reads an unsigned packed BCD number, two digits per byte with the
+/// most significant digit first.
+///
+/// ```
+/// use arrayref::read_bcd;
+///
+/// assert_eq!(read_bcd(&[0x20, 0x24, 0x12, 0x31]), Ok(20241231));
+/// assert!(read_bcd(&[0x1a]).is_err());
+/// ```
+pub fn read_bcd<const N: usize>(bytes: &[u8; N]) -> Result<u64, DecimalError> {
+    accumulate(nibbles(bytes).enumerate().map(|(i, n)| digit(n, i)))
+}
+
+/// Writes `n` as unsigned packed BCD, padded with leading zeros, or
+/// returns `DecimalError::Overflow` if it has more than `2 * N` digits.
+pub fn write_bcd<const N: usize>(bytes: &mut [u8; N], n: u64) -> Result<(), DecimalError> {
+    let mut packed = [0; N];
+    if pack_digits(&mut packed, n) != 0 {
+        return Err(DecimalError::Overflow);
+    }
+    *bytes = packed;
+    Ok(())
+}
+
+/// Reads the `M = 2 * N` digits of an unsigned packed BCD field as
+/// ASCII, keeping leading zeros.  This suits identifiers such as phone
+/// numbers that are stored as BCD but are not really numbers.  The
+/// relationship between `M` and `N` is checked at compile time.
+///
+/// ```
+/// use arrayref::bcd_digits;
+///
+/// let msisdn = [0x07, 0x70, 0x09, 0x00, 0x01];
+/// assert_eq!(&bcd_digits::<5, 10>(&msisdn).unwrap(), b"0770090001");
+/// ```
+pub fn bcd_digits<const N: usize, const M: usize>(
+    bytes: &[u8; N],
+) -> Result<[u8; M], DecimalError> {
+    let () = AssertDigits::<N, M>::OK;
+    let mut ascii = [b'0'; M];
+    for (i, (a, n)) in ascii.iter_mut().zip(nibbles(bytes)).enumerate() {
+        *a += digit(n, i)?;
+    }
+    Ok(ascii)
+}
+
+struct AssertDigits<const N: usize, const M: usize>;
+
+impl<const N: usize, const M: usize> AssertDigits<N, M> {
+    const OK: () = assert!(
+        N.checked_mul(2).is_some() && M == 2 * N,
+        "a packed BCD field of N bytes holds 2 * N digits"
+    );
+}
+
+/// Reads a signed packed decimal (COBOL `COMP-3`) number: `2 * N - 1`
+/// digits followed by a sign nibble.
+///
+/// ```
+/// use arrayref::read_packed_decimal;
+///
+/// assert_eq!(read_packed_decimal(&[0x01, 0x23, 0x4d]), Ok(-1234));
+/// assert_eq!(read_packed_decimal(&[0x99, 0x9c]), Ok(999));
+/// ```
+pub fn read_packed_decimal<const N: usize>(bytes: &[u8; N]) -> Result<i64, DecimalError> {
+    let sign = bytes.last().ok_or(DecimalError::InvalidSign)? & 0xf;
+    let magnitude = accumulate(
+        nibbles(bytes)
+            .take(2 * N - 1)
+            .enumerate()
+            .map(|(i, n)| digit(n, i)),
+    )?;
+    apply_sign(magnitude, is_negative(sign)?)
+}
+
+/// Writes `n` as signed packed decimal, or returns
+/// `DecimalError::Overflow` if it has more than `2 * N - 1` digits.
+pub fn write_packed_decimal<const N: usize>(
+    bytes: &mut [u8; N],
+    n: i64,
+) -> Result<(), DecimalError> {
+    let mut packed = [0; N];
+    let (last, rest) = packed.split_last_mut().ok_or(DecimalError::Overflow)?;
+    let magnitude = n.unsigned_abs();
+    if pack_digits(rest, magnitude / 10) != 0 {
+        return Err(DecimalError::Overflow);
+    }
+    *last = ((magnitude % 10) as u8) << 4 | if n < 0 { 0xd } else { 0xc };
+    *bytes = packed;
+    Ok(())
+}
+
+/// Reads a signed zoned decimal number, one digit per byte in the low
+/// nibble.  The zone (high) nibble of the last byte holds the sign and
+/// the others must be `0xF` (as in EBCDIC) or `0x3` (as in ASCII).
+///
+/// ```
+/// use arrayref::read_zoned_decimal;
+///
+/// assert_eq!(read_zoned_decimal(&[0xf1, 0xf2, 0xd3]), Ok(-123));
+/// assert_eq!(read_zoned_decimal(b"00042"), Err(arrayref::DecimalError::InvalidSign));
+/// ```
+pub fn read_zoned_decimal<const N: usize>(bytes: &[u8; N]) -> Result<i64, DecimalError> {
+    let (last, rest) = bytes.split_last().ok_or(DecimalError::InvalidSign)?;
+    let negative = is_negative(last >> 4)?;
+    let magnitude = accumulate(bytes.iter().enumerate().map(|(i, &b)| {
+        if i < rest.len() && b >> 4 != 0xf && b >> 4 != 0x3 {
+            return Err(DecimalError::InvalidDigit { position: i });
+        }
+        digit(b & 0xf, i)
+    }))?;
+    apply_sign(magnitude, negative)
+}
+
+/// Writes `n` as EBCDIC signed zoned decimal, or returns
+/// `DecimalError::Overflow` if it has more than `N` digits.
+pub fn write_zoned_decimal<const N: usize>(
+    bytes: &mut [u8; N],
+    n: i64,
+) -> Result<(), DecimalError> {
+    let mut zoned = [0xf0; N];
+    let mut magnitude = n.unsigned_abs();
+    for z in zoned.iter_mut().rev() {
+        *z |= (magnitude % 10) as u8;
+        magnitude /= 10;
+    }
+    match zoned.last_mut() {
+        Some(last) if magnitude == 0 => *last &= if n < 0 { 0xdf } else { 0xcf },
+        _ => return Err(DecimalError::Overflow),
+    }
+    *bytes = zoned;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    extern crate quickcheck;
+
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        fn f(n: i64) -> bool {
+            let n = n % 1_000_000_000_000_000;
+            let mut packed = [0u8; 10];
+            let mut zoned = [0u8; 19];
+            let mut bcd = [0u8; 10];
+            write_packed_decimal(&mut packed, n).unwrap();
+            write_zoned_decimal(&mut zoned, n).unwrap();
+            write_bcd(&mut bcd, n.unsigned_abs()).unwrap();
+            read_packed_decimal(&packed) == Ok(n)
+                && read_zoned_decimal(&zoned) == Ok(n)
+                && read_bcd(&bcd) == Ok(n.unsigned_abs())
+        }
+        quickcheck::quickcheck(f as fn(i64) -> bool);
+    }
+
+    #[test]
+    fn extremes_and_overflow() {
+        let mut packed = [0u8; 10];
+        write_packed_decimal(&mut packed, i64::MIN).unwrap();
+        assert_eq!(read_packed_decimal(&packed), Ok(i64::MIN));
+        assert_eq!(
+            write_packed_decimal(&mut [0u8; 2], 1000),
+            Err(DecimalError::Overflow)
+        );
+        assert_eq!(write_bcd(&mut [0u8; 1], 100), Err(DecimalError::Overflow));
+        assert_eq!(
+            write_zoned_decimal(&mut [0u8; 0], 0),
+            Err(DecimalError::Overflow)
+        );
+        assert_eq!(read_bcd(&[0x99; 11]), Err(DecimalError::Overflow));
+        assert_eq!(
+            read_packed_decimal(&[0x12, 0x34]),
+            Err(DecimalError::InvalidSign)
+        );
+    }
+
+    #[test]
+    fn zoned_accepts_ascii_zones() {
+        assert_eq!(read_zoned_decimal(b"0012\xc3"), Ok(123));
+        assert_eq!(
+            read_zoned_decimal(b"0 12\xc3"),
+            Err(DecimalError::InvalidDigit { position: 1 })
+        );
+    }
+}
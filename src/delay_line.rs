@@ -0,0 +1,109 @@
+//! A fixed-capacity history of the most recent samples.
+
+use core::convert::TryFrom;
+
+/// A `DelayLine` remembers the last `N` samples pushed into it, as is
+/// needed for echo effects, FIR filters and other DSP code.  Samples
+/// can be read back individually by their delay, or the most recent
+/// `K` of them can be viewed as a `&[T; K]` whenever they happen to be
+/// stored contiguously.
+///
+/// ```
+/// use arrayref::DelayLine;
+///
+/// let mut line = DelayLine::<i32, 4>::new(0);
+/// for x in 1..=6 {
+///     line.push(x);
+/// }
+/// assert_eq!(line.tap(0), 6);
+/// assert_eq!(line.tap(3), 3);
+/// assert_eq!(line.last::<2>(), Some(&[5, 6]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct DelayLine<T, const N: usize> {
+    samples: [T; N],
+    next: usize,
+}
+
+impl<T: Copy, const N: usize> DelayLine<T, N> {
+    /// Creates a delay line holding `N` copies of `fill`.
+    ///
+    /// **Panics** if `N` is zero.
+    pub fn new(fill: T) -> Self {
+        assert!(N != 0, "a delay line must hold at least one sample");
+        DelayLine {
+            samples: [fill; N],
+            next: 0,
+        }
+    }
+
+    /// Pushes a new sample, returning the sample that was pushed `N`
+    /// samples ago and has now fallen off the end.
+    pub fn push(&mut self, sample: T) -> T {
+        let old = core::mem::replace(&mut self.samples[self.next], sample);
+        self.next = if self.next + 1 == N { 0 } else { self.next + 1 };
+        old
+    }
+
+    /// The sample pushed `delay` pushes ago, so that `tap(0)` is the
+    /// most recent sample.
+    ///
+    /// **Panics** if `delay` is not less than `N`.
+    pub fn tap(&self, delay: usize) -> T {
+        assert!(delay < N, "delay is longer than the delay line");
+        // written to avoid computing `self.next + N`, which could
+        // overflow a 16-bit `usize`
+        let back = delay + 1;
+        if back <= self.next {
+            self.samples[self.next - back]
+        } else {
+            self.samples[N - (back - self.next)]
+        }
+    }
+
+    /// The `K` most recent samples, oldest first, or `None` if they
+    /// wrap around the end of the underlying storage (or if `K` is
+    /// greater than `N`).
+    pub fn last<const K: usize>(&self) -> Option<&[T; K]> {
+        // the newest sample sits just before `next`, unless `next` has
+        // wrapped back to zero, in which case it is at the very end.
+        let end = if self.next == 0 { N } else { self.next };
+        if K <= end {
+            <&[T; K]>::try_from(&self.samples[end - K..end]).ok()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn push_returns_what_falls_off() {
+        let mut line = DelayLine::<u32, 3>::new(0);
+        let out: std::vec::Vec<u32> = (1..=7).map(|x| line.push(x)).collect();
+        assert_eq!(out, [0, 0, 0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn last_matches_taps_when_contiguous() {
+        let mut line = DelayLine::<u32, 5>::new(0);
+        for x in 1..=23 {
+            line.push(x);
+            if let Some(recent) = line.last::<3>() {
+                assert_eq!(*recent, [line.tap(2), line.tap(1), line.tap(0)]);
+            } else {
+                assert!(x % 5 == 1 || x % 5 == 2);
+            }
+        }
+        assert_eq!(line.last::<6>(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn tap_checks_delay() {
+        DelayLine::<u8, 2>::new(0).tap(2);
+    }
+}
@@ -0,0 +1,111 @@
+//! A ping-pong buffer made of two fixed-size halves.
+
+use chunks::as_chunks_mut;
+
+/// A `DoubleBuffer` splits a buffer of `2*N` elements into two halves
+/// of `N` elements, one of which is the *front* (the one most recently
+/// completed, to be read) while the other is the *back* (the one being
+/// filled).  Calling `swap` exchanges their roles without copying.
+///
+/// Since stable rust cannot yet write the type `[T; 2*N]`, the buffer
+/// is given either as an array of two halves or as a slice of the
+/// right length.
+///
+/// ```
+/// use arrayref::DoubleBuffer;
+///
+/// let mut storage = [[0u8; 4]; 2];
+/// let mut frames = DoubleBuffer::new(&mut storage);
+/// *frames.back() = [1, 2, 3, 4];
+/// frames.swap();
+/// assert_eq!(frames.front(), &[1, 2, 3, 4]);
+/// let (front, back) = frames.split();
+/// for (b, f) in back.iter_mut().zip(front) {
+///     *b = 2 * f;
+/// }
+/// frames.swap();
+/// assert_eq!(frames.front(), &[2, 4, 6, 8]);
+/// ```
+#[derive(Debug)]
+pub struct DoubleBuffer<'a, T: 'a, const N: usize> {
+    halves: &'a mut [[T; N]; 2],
+    front: usize,
+}
+
+impl<'a, T, const N: usize> DoubleBuffer<'a, T, N> {
+    /// Creates a double buffer from its two halves.  Initially the
+    /// first half is the front and the second half is the back.
+    pub fn new(halves: &'a mut [[T; N]; 2]) -> Self {
+        DoubleBuffer { halves, front: 0 }
+    }
+
+    /// Creates a double buffer over a slice of exactly `2*N` elements,
+    /// or returns `None` if the slice has any other length.
+    ///
+    /// **Panics** if `N` is zero.
+    pub fn from_slice(slice: &'a mut [T]) -> Option<Self> {
+        match as_chunks_mut(slice) {
+            (halves @ [_, _], []) => Some(DoubleBuffer::new(array_mut_ref!(halves, 0, 2))),
+            _ => None,
+        }
+    }
+
+    /// The front half, which is the one to be read.
+    pub fn front(&self) -> &[T; N] {
+        &self.halves[self.front]
+    }
+
+    /// The back half, which is the one to be written.
+    pub fn back(&mut self) -> &mut [T; N] {
+        &mut self.halves[1 - self.front]
+    }
+
+    /// Both halves at once, as the front for reading and the back for
+    /// writing.
+    pub fn split(&mut self) -> (&[T; N], &mut [T; N]) {
+        let (first, second) = mut_array_refs!(&mut *self.halves, 1, 1);
+        if self.front == 0 {
+            (&first[0], &mut second[0])
+        } else {
+            (&second[0], &mut first[0])
+        }
+    }
+
+    /// Exchanges the roles of the two halves, so that the back becomes
+    /// the front and vice versa.
+    pub fn swap(&mut self) {
+        self.front = 1 - self.front;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn from_slice_checks_length() {
+        let mut buf = [0u32; 6];
+        assert!(DoubleBuffer::<_, 3>::from_slice(&mut buf).is_some());
+        assert!(DoubleBuffer::<_, 2>::from_slice(&mut buf).is_none());
+        assert!(DoubleBuffer::<_, 4>::from_slice(&mut buf).is_none());
+    }
+
+    #[test]
+    fn swap_flips_roles() {
+        let mut buf = [0u32; 4];
+        {
+            let mut halves = DoubleBuffer::<_, 2>::from_slice(&mut buf).unwrap();
+            for round in 1..4 {
+                {
+                    let (front, back) = halves.split();
+                    back[0] = front[0] + round;
+                    back[1] = round;
+                }
+                halves.swap();
+                assert_eq!(halves.front()[1], round);
+            }
+        }
+        // rounds wrote to the second, first and second halves in turn
+        assert_eq!(buf, [3, 2, 6, 3]);
+    }
+}
@@ -0,0 +1,109 @@
+//! Labeled hex dumps of segmented buffers, for debugging parsers.
+
+use core::fmt;
+
+/// A `SegmentDump` renders a byte buffer split into named segments,
+/// one hex-dumped segment per line.  When a parser built on
+/// `array_refs!` misbehaves, seeing where each field actually falls in
+/// the buffer is usually the quickest way to find the mistake.
+///
+/// Unlike `split_lengths`, the segment lengths need not fit within the
+/// buffer: a segment that runs off the end is shown with as many bytes
+/// as there are, and a note of how many are missing.  Any bytes after
+/// the last segment are shown on a final line labeled `..`.
+///
+/// `Display` and `Debug` give the same rendering.
+///
+/// ```
+/// use arrayref::SegmentDump;
+///
+/// let packet = [0x01, 0x00, 0x03, 0xaa, 0xbb, 0xff];
+/// let dump = SegmentDump::new(&packet, &[("kind", 1), ("len", 2), ("body", 2)]);
+/// assert_eq!(
+///     dump.to_string(),
+///     "kind: 01\nlen:  00 03\nbody: aa bb\n..:   ff\n"
+/// );
+///
+/// let truncated = SegmentDump::new(&packet[..2], &[("kind", 1), ("len", 2)]);
+/// assert_eq!(truncated.to_string(), "kind: 01\nlen:  00 (1 missing)\n");
+/// ```
+#[derive(Clone, Copy)]
+pub struct SegmentDump<'a, 'b> {
+    bytes: &'a [u8],
+    segments: &'b [(&'b str, usize)],
+}
+
+impl<'a, 'b> SegmentDump<'a, 'b> {
+    /// Describes `bytes` as consecutive `(name, length)` segments.
+    pub fn new(bytes: &'a [u8], segments: &'b [(&'b str, usize)]) -> Self {
+        SegmentDump { bytes, segments }
+    }
+}
+
+fn write_line(
+    f: &mut fmt::Formatter,
+    name: &str,
+    width: usize,
+    bytes: &[u8],
+    missing: usize,
+) -> fmt::Result {
+    write!(f, "{}:", name)?;
+    if !bytes.is_empty() || missing > 0 {
+        write!(f, "{:1$}", "", width - name.len())?;
+    }
+    for b in bytes {
+        write!(f, " {:02x}", b)?;
+    }
+    if missing > 0 {
+        write!(f, " ({} missing)", missing)?;
+    }
+    writeln!(f)
+}
+
+impl<'a, 'b> fmt::Display for SegmentDump<'a, 'b> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const REST: &str = "..";
+        let width = self
+            .segments
+            .iter()
+            .map(|&(name, _)| name.len())
+            .chain(Some(REST.len()))
+            .max()
+            .unwrap_or(0);
+        let mut rest = self.bytes;
+        for &(name, len) in self.segments {
+            let (segment, tail) = rest.split_at(len.min(rest.len()));
+            rest = tail;
+            write_line(f, name, width, segment, len - segment.len())?;
+        }
+        if !rest.is_empty() {
+            write_line(f, REST, width, rest, 0)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, 'b> fmt::Debug for SegmentDump<'a, 'b> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn empty_segments_and_buffers() {
+        assert_eq!(SegmentDump::new(&[], &[]).to_string(), "");
+        assert_eq!(
+            SegmentDump::new(&[7], &[("pad", 0)]).to_string(),
+            "pad:\n..:  07\n"
+        );
+        assert_eq!(
+            format!("{:?}", SegmentDump::new(&[], &[("x", 2)])),
+            "x:  (2 missing)\n"
+        );
+    }
+}
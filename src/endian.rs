@@ -0,0 +1,465 @@
+//! Reading and writing integers in either byte order.
+
+/// A `ByteOrder` converts integers to and from fixed-size byte arrays
+/// in a particular endianness.  Codecs for formats that come in both
+/// byte orders, such as TIFF, can write each accessor once, generic
+/// over `E: ByteOrder`, and pick `BigEndian` or `LittleEndian` at
+/// runtime from the file header.
+///
+/// The free functions `read_u32::<E>` and friends are shorthands for
+/// calling these methods.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// use arrayref::{BigEndian, ByteOrder, LittleEndian};
+///
+/// fn image_width<E: ByteOrder>(ifd_entry: &[u8; 12]) -> u32 {
+///     E::read_u32(array_ref!(ifd_entry, 8, 4))
+/// }
+///
+/// # fn main() {
+/// let entry = [0, 1, 0, 4, 0, 0, 0, 1, 0, 0, 2, 0];
+/// assert_eq!(image_width::<BigEndian>(&entry), 512);
+/// assert_eq!(image_width::<LittleEndian>(&entry), 0x20000);
+/// # }
+/// ```
+pub trait ByteOrder {
+    /// Reads a `u16` from `bytes`.
+    fn read_u16(bytes: &[u8; 2]) -> u16;
+    /// Reads a `u32` from `bytes`.
+    fn read_u32(bytes: &[u8; 4]) -> u32;
+    /// Reads a `u64` from `bytes`.
+    fn read_u64(bytes: &[u8; 8]) -> u64;
+    /// Reads a `u128` from `bytes`.
+    fn read_u128(bytes: &[u8; 16]) -> u128;
+    /// Writes `n` into `bytes`.
+    fn write_u16(bytes: &mut [u8; 2], n: u16);
+    /// Writes `n` into `bytes`.
+    fn write_u32(bytes: &mut [u8; 4], n: u32);
+    /// Writes `n` into `bytes`.
+    fn write_u64(bytes: &mut [u8; 8], n: u64);
+    /// Writes `n` into `bytes`.
+    fn write_u128(bytes: &mut [u8; 16], n: u128);
+    /// Reads a 24-bit unsigned integer from `bytes`.
+    fn read_u24(bytes: &[u8; 3]) -> u32;
+    /// Reads a 40-bit unsigned integer from `bytes`.
+    fn read_u40(bytes: &[u8; 5]) -> u64;
+    /// Reads a 48-bit unsigned integer from `bytes`.
+    fn read_u48(bytes: &[u8; 6]) -> u64;
+    /// Reads a 56-bit unsigned integer from `bytes`.
+    fn read_u56(bytes: &[u8; 7]) -> u64;
+    /// Writes `n` into `bytes` as a 24-bit unsigned integer, panicking
+    /// if it does not fit.
+    fn write_u24(bytes: &mut [u8; 3], n: u32);
+    /// Writes `n` into `bytes` as a 40-bit unsigned integer, panicking
+    /// if it does not fit.
+    fn write_u40(bytes: &mut [u8; 5], n: u64);
+    /// Writes `n` into `bytes` as a 48-bit unsigned integer, panicking
+    /// if it does not fit.
+    fn write_u48(bytes: &mut [u8; 6], n: u64);
+    /// Writes `n` into `bytes` as a 56-bit unsigned integer, panicking
+    /// if it does not fit.
+    fn write_u56(bytes: &mut [u8; 7], n: u64);
+}
+
+/// Big-endian byte order, most significant byte first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BigEndian {}
+
+/// Little-endian byte order, least significant byte first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LittleEndian {}
+
+/// The byte order used by network protocols, which is big-endian.
+pub type NetworkEndian = BigEndian;
+
+/// Zero-extends `bytes` to `M` bytes, adding the zeros at the most
+/// significant end.
+#[inline]
+fn widen<const N: usize, const M: usize>(bytes: &[u8; N], big: bool) -> [u8; M] {
+    let mut wide = [0; M];
+    if big {
+        wide[M - N..].copy_from_slice(bytes);
+    } else {
+        wide[..N].copy_from_slice(bytes);
+    }
+    wide
+}
+
+/// Truncates `wide` to its `N` least significant bytes, panicking if
+/// any of the dropped bytes is nonzero.
+#[inline]
+fn narrow<const N: usize, const M: usize>(bytes: &mut [u8; N], wide: [u8; M], big: bool) {
+    let (dropped, kept) = if big {
+        let (dropped, kept) = wide.split_at(M - N);
+        (dropped, kept)
+    } else {
+        let (kept, dropped) = wide.split_at(N);
+        (dropped, kept)
+    };
+    assert!(
+        dropped.iter().all(|&b| b == 0),
+        "value does not fit in {} bits",
+        8 * N
+    );
+    bytes.copy_from_slice(kept);
+}
+
+macro_rules! impl_byte_order {
+    ($order:ty, $from:ident, $to:ident, $big:expr) => {
+        impl ByteOrder for $order {
+            #[inline]
+            fn read_u16(bytes: &[u8; 2]) -> u16 {
+                u16::$from(*bytes)
+            }
+            #[inline]
+            fn read_u32(bytes: &[u8; 4]) -> u32 {
+                u32::$from(*bytes)
+            }
+            #[inline]
+            fn read_u64(bytes: &[u8; 8]) -> u64 {
+                u64::$from(*bytes)
+            }
+            #[inline]
+            fn read_u128(bytes: &[u8; 16]) -> u128 {
+                u128::$from(*bytes)
+            }
+            #[inline]
+            fn write_u16(bytes: &mut [u8; 2], n: u16) {
+                *bytes = n.$to();
+            }
+            #[inline]
+            fn write_u32(bytes: &mut [u8; 4], n: u32) {
+                *bytes = n.$to();
+            }
+            #[inline]
+            fn write_u64(bytes: &mut [u8; 8], n: u64) {
+                *bytes = n.$to();
+            }
+            #[inline]
+            fn write_u128(bytes: &mut [u8; 16], n: u128) {
+                *bytes = n.$to();
+            }
+            #[inline]
+            fn read_u24(bytes: &[u8; 3]) -> u32 {
+                u32::$from(widen(bytes, $big))
+            }
+            #[inline]
+            fn read_u40(bytes: &[u8; 5]) -> u64 {
+                u64::$from(widen(bytes, $big))
+            }
+            #[inline]
+            fn read_u48(bytes: &[u8; 6]) -> u64 {
+                u64::$from(widen(bytes, $big))
+            }
+            #[inline]
+            fn read_u56(bytes: &[u8; 7]) -> u64 {
+                u64::$from(widen(bytes, $big))
+            }
+            #[inline]
+            fn write_u24(bytes: &mut [u8; 3], n: u32) {
+                narrow(bytes, n.$to(), $big)
+            }
+            #[inline]
+            fn write_u40(bytes: &mut [u8; 5], n: u64) {
+                narrow(bytes, n.$to(), $big)
+            }
+            #[inline]
+            fn write_u48(bytes: &mut [u8; 6], n: u64) {
+                narrow(bytes, n.$to(), $big)
+            }
+            #[inline]
+            fn write_u56(bytes: &mut [u8; 7], n: u64) {
+                narrow(bytes, n.$to(), $big)
+            }
+        }
+    };
+}
+
+impl_byte_order!(BigEndian, from_be_bytes, to_be_bytes, true);
+impl_byte_order!(LittleEndian, from_le_bytes, to_le_bytes, false);
+
+/// Reads a `u16` from `bytes` in byte order `E`.
+#[inline]
+pub fn read_u16<E: ByteOrder>(bytes: &[u8; 2]) -> u16 {
+    E::read_u16(bytes)
+}
+
+/// Reads a `u32` from `bytes` in byte order `E`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// use arrayref::{read_u32, LittleEndian};
+///
+/// # fn main() {
+/// let header = [0x49, 0x49, 0x2a, 0x00, 0x08, 0x00, 0x00, 0x00];
+/// assert_eq!(read_u32::<LittleEndian>(array_ref!(header, 4, 4)), 8);
+/// # }
+/// ```
+#[inline]
+pub fn read_u32<E: ByteOrder>(bytes: &[u8; 4]) -> u32 {
+    E::read_u32(bytes)
+}
+
+/// Reads a `u64` from `bytes` in byte order `E`.
+#[inline]
+pub fn read_u64<E: ByteOrder>(bytes: &[u8; 8]) -> u64 {
+    E::read_u64(bytes)
+}
+
+/// Reads a `u128` from `bytes` in byte order `E`.
+#[inline]
+pub fn read_u128<E: ByteOrder>(bytes: &[u8; 16]) -> u128 {
+    E::read_u128(bytes)
+}
+
+/// Writes `n` into `bytes` in byte order `E`.
+#[inline]
+pub fn write_u16<E: ByteOrder>(bytes: &mut [u8; 2], n: u16) {
+    E::write_u16(bytes, n)
+}
+
+/// Writes `n` into `bytes` in byte order `E`.
+#[inline]
+pub fn write_u32<E: ByteOrder>(bytes: &mut [u8; 4], n: u32) {
+    E::write_u32(bytes, n)
+}
+
+/// Writes `n` into `bytes` in byte order `E`.
+#[inline]
+pub fn write_u64<E: ByteOrder>(bytes: &mut [u8; 8], n: u64) {
+    E::write_u64(bytes, n)
+}
+
+/// Writes `n` into `bytes` in byte order `E`.
+#[inline]
+pub fn write_u128<E: ByteOrder>(bytes: &mut [u8; 16], n: u128) {
+    E::write_u128(bytes, n)
+}
+
+/// Reads a 24-bit unsigned integer from `bytes` in byte order `E`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// use arrayref::{read_u24, BigEndian};
+///
+/// # fn main() {
+/// // An FLV tag header: type, 24-bit data size, 24-bit timestamp.
+/// let tag = [0x09, 0x00, 0x01, 0x2c, 0x00, 0x00, 0x21];
+/// let (kind, size, timestamp) = array_refs!(&tag, 1, 3, 3);
+/// assert_eq!(kind[0], 9);
+/// assert_eq!(read_u24::<BigEndian>(size), 300);
+/// assert_eq!(read_u24::<BigEndian>(timestamp), 33);
+/// # }
+/// ```
+#[inline]
+pub fn read_u24<E: ByteOrder>(bytes: &[u8; 3]) -> u32 {
+    E::read_u24(bytes)
+}
+
+/// Reads a 40-bit unsigned integer from `bytes` in byte order `E`.
+#[inline]
+pub fn read_u40<E: ByteOrder>(bytes: &[u8; 5]) -> u64 {
+    E::read_u40(bytes)
+}
+
+/// Reads a 48-bit unsigned integer from `bytes` in byte order `E`.
+#[inline]
+pub fn read_u48<E: ByteOrder>(bytes: &[u8; 6]) -> u64 {
+    E::read_u48(bytes)
+}
+
+/// Reads a 56-bit unsigned integer from `bytes` in byte order `E`.
+#[inline]
+pub fn read_u56<E: ByteOrder>(bytes: &[u8; 7]) -> u64 {
+    E::read_u56(bytes)
+}
+
+/// Writes `n` into `bytes` as a 24-bit unsigned integer in byte order
+/// `E`.
+///
+/// # Panics
+///
+/// If `n` does not fit in 24 bits.
+#[inline]
+pub fn write_u24<E: ByteOrder>(bytes: &mut [u8; 3], n: u32) {
+    E::write_u24(bytes, n)
+}
+
+/// Writes `n` into `bytes` as a 40-bit unsigned integer in byte order
+/// `E`.
+///
+/// # Panics
+///
+/// If `n` does not fit in 40 bits.
+#[inline]
+pub fn write_u40<E: ByteOrder>(bytes: &mut [u8; 5], n: u64) {
+    E::write_u40(bytes, n)
+}
+
+/// Writes `n` into `bytes` as a 48-bit unsigned integer in byte order
+/// `E`.
+///
+/// # Panics
+///
+/// If `n` does not fit in 48 bits.
+#[inline]
+pub fn write_u48<E: ByteOrder>(bytes: &mut [u8; 6], n: u64) {
+    E::write_u48(bytes, n)
+}
+
+/// Writes `n` into `bytes` as a 56-bit unsigned integer in byte order
+/// `E`.
+///
+/// # Panics
+///
+/// If `n` does not fit in 56 bits.
+#[inline]
+pub fn write_u56<E: ByteOrder>(bytes: &mut [u8; 7], n: u64) {
+    E::write_u56(bytes, n)
+}
+
+/// `SwapBytes` is implemented by the integer types wider than a byte,
+/// so that `bswap_array` can be generic over the element width.
+pub trait SwapBytes: Copy {
+    /// Reverses the byte order of `self`.
+    fn swap_bytes(self) -> Self;
+}
+
+macro_rules! impl_swap_bytes {
+    ($( $t:ty ),*) => {
+        $(
+            impl SwapBytes for $t {
+                #[inline]
+                fn swap_bytes(self) -> Self {
+                    <$t>::swap_bytes(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_swap_bytes!(u16, u32, u64, u128, usize, i16, i32, i64, i128, isize);
+
+/// Reverses the byte order of every element of `array` in place.  The
+/// loop has a constant trip count and no dependencies between
+/// elements, so it compiles to vector shuffles on targets that have
+/// them.
+///
+/// This is the bulk counterpart of `read_u32::<E>` and friends, for
+/// when a whole table has been copied out of a buffer in one go:
+///
+/// ```
+/// use arrayref::bswap_array;
+///
+/// // A table of big-endian offsets, copied straight off the disk.
+/// let mut offsets: [u32; 3] = [0x0000_0010u32.to_be(), 0x0000_0100u32.to_be(), 0x0001_0000u32.to_be()];
+/// if cfg!(target_endian = "little") {
+///     bswap_array(&mut offsets);
+/// }
+/// assert_eq!(offsets, [0x10, 0x100, 0x10000]);
+/// ```
+#[inline]
+pub fn bswap_array<T: SwapBytes, const N: usize>(array: &mut [T; N]) {
+    for x in array.iter_mut() {
+        *x = x.swap_bytes();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate quickcheck;
+
+    use super::*;
+
+    #[test]
+    fn round_trips_in_both_orders() {
+        fn f(a: u16, b: u32, c: u64, d: u128) -> bool {
+            fn check<E: ByteOrder>(a: u16, b: u32, c: u64, d: u128) -> bool {
+                let mut buf = [0u8; 30];
+                {
+                    let (wa, wb, wc, wd) = mut_array_refs!(&mut buf, 2, 4, 8, 16);
+                    write_u16::<E>(wa, a);
+                    write_u32::<E>(wb, b);
+                    write_u64::<E>(wc, c);
+                    write_u128::<E>(wd, d);
+                }
+                let (ra, rb, rc, rd) = array_refs!(&buf, 2, 4, 8, 16);
+                (
+                    read_u16::<E>(ra),
+                    read_u32::<E>(rb),
+                    read_u64::<E>(rc),
+                    read_u128::<E>(rd),
+                ) == (a, b, c, d)
+            }
+            check::<BigEndian>(a, b, c, d) && check::<LittleEndian>(a, b, c, d)
+        }
+        quickcheck::quickcheck(f as fn(u16, u32, u64, u128) -> bool);
+    }
+
+    #[test]
+    fn odd_widths_round_trip() {
+        fn f(a: u32, b: u64) -> bool {
+            fn check<E: ByteOrder>(a: u32, b: u64) -> bool {
+                let (a, b) = (a & 0xff_ffff, b & 0xff_ffff_ffff);
+                let mut buf = [0u8; 3 + 5 + 6 + 7];
+                {
+                    let (w24, w40, w48, w56) = mut_array_refs!(&mut buf, 3, 5, 6, 7);
+                    write_u24::<E>(w24, a);
+                    write_u40::<E>(w40, b);
+                    write_u48::<E>(w48, b << 8);
+                    write_u56::<E>(w56, b << 16);
+                }
+                let (r24, r40, r48, r56) = array_refs!(&buf, 3, 5, 6, 7);
+                read_u24::<E>(r24) == a
+                    && read_u40::<E>(r40) == b
+                    && read_u48::<E>(r48) == b << 8
+                    && read_u56::<E>(r56) == b << 16
+            }
+            check::<BigEndian>(a, b) && check::<LittleEndian>(a, b)
+        }
+        quickcheck::quickcheck(f as fn(u32, u64) -> bool);
+    }
+
+    #[test]
+    fn odd_widths_match_wider_reads() {
+        let bytes = [1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(read_u24::<BigEndian>(array_ref!(bytes, 0, 3)), 0x010203);
+        assert_eq!(read_u24::<LittleEndian>(array_ref!(bytes, 0, 3)), 0x030201);
+        assert_eq!(read_u56::<BigEndian>(&bytes), 0x01020304050607);
+        assert_eq!(read_u56::<LittleEndian>(&bytes), 0x07060504030201);
+    }
+
+    #[test]
+    #[should_panic(expected = "value does not fit in 24 bits")]
+    fn write_u24_checks_range() {
+        write_u24::<LittleEndian>(&mut [0; 3], 1 << 24);
+    }
+
+    #[test]
+    fn bswap_array_matches_byte_order() {
+        let bytes = [1u8, 2, 3, 4, 5, 6, 7, 8];
+        let mut words = [0u16; 4];
+        for (w, b) in words.iter_mut().zip(bytes.chunks(2)) {
+            *w = u16::from_le_bytes([b[0], b[1]]);
+        }
+        bswap_array(&mut words);
+        assert_eq!(words[3], read_u16::<BigEndian>(&[7, 8]));
+        let mut mixed = [-2i64, 1 << 8];
+        bswap_array(&mut mixed);
+        assert_eq!(mixed, [(-2i64).swap_bytes(), 1 << 48]);
+    }
+
+    #[test]
+    fn orders_differ() {
+        let mut buf = [0u8; 4];
+        write_u32::<BigEndian>(&mut buf, 0x01020304);
+        assert_eq!(buf, [1, 2, 3, 4]);
+        write_u32::<LittleEndian>(&mut buf, 0x01020304);
+        assert_eq!(buf, [4, 3, 2, 1]);
+        assert_eq!(read_u16::<NetworkEndian>(&[0x12, 0x34]), 0x1234);
+    }
+}
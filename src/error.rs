@@ -0,0 +1,192 @@
+//! Error types shared by the fallible parts of this crate.
+
+use core::fmt;
+
+/// A `LengthError` reports that an operation required more elements
+/// (or room for more elements) than were available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LengthError {
+    required: usize,
+    available: usize,
+}
+
+impl LengthError {
+    pub(crate) const fn new(required: usize, available: usize) -> Self {
+        LengthError {
+            required,
+            available,
+        }
+    }
+
+    /// The number of elements that were required.
+    pub fn required(&self) -> usize {
+        self.required
+    }
+
+    /// The number of elements that were actually available.
+    pub fn available(&self) -> usize {
+        self.available
+    }
+
+    /// How many elements were missing.
+    pub fn missing(&self) -> usize {
+        self.required.saturating_sub(self.available)
+    }
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "required {} elements but only {} were available",
+            self.required, self.available
+        )
+    }
+}
+
+/// A `BoundsFailure` describes an out-of-bounds window passed to
+/// `array_ref!`, `array_mut_ref!` or `array_ref_end!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoundsFailure {
+    offset: usize,
+    len: usize,
+    available: usize,
+    from_end: bool,
+}
+
+impl BoundsFailure {
+    pub(crate) fn new(offset: usize, len: usize, available: usize) -> Self {
+        BoundsFailure {
+            offset,
+            len,
+            available,
+            from_end: false,
+        }
+    }
+
+    pub(crate) fn from_end(offset: usize, len: usize, available: usize) -> Self {
+        BoundsFailure {
+            from_end: true,
+            ..BoundsFailure::new(offset, len, available)
+        }
+    }
+
+    /// The offset of the requested window.  This is measured from the
+    /// end of the slice if `is_from_end` is true.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Whether the offset was measured back from the end of the slice,
+    /// as for `array_ref_end!`.
+    pub fn is_from_end(&self) -> bool {
+        self.from_end
+    }
+
+    /// The length of the requested window.
+    pub fn window_len(&self) -> usize {
+        self.len
+    }
+
+    /// The length of the slice the window was taken from.
+    pub fn available(&self) -> usize {
+        self.available
+    }
+}
+
+impl fmt::Display for BoundsFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "range of {} elements at offset {}{} is out of bounds for length {}",
+            self.len,
+            self.offset,
+            if self.from_end { " from the end" } else { "" },
+            self.available
+        )
+    }
+}
+
+/// A `CollectError` reports that an iterator did not yield exactly
+/// the number of items needed to fill an array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollectError {
+    /// The iterator ran out of items, with this many still missing.
+    TooShort {
+        /// How many more items were needed.
+        missing: usize,
+    },
+    /// The iterator still had items left once the array was full.
+    TooLong,
+}
+
+impl fmt::Display for CollectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CollectError::TooShort { missing } => {
+                write!(f, "iterator was {} items too short", missing)
+            }
+            CollectError::TooLong => write!(f, "iterator had too many items"),
+        }
+    }
+}
+
+/// A `DecimalError` reports that a packed BCD or zoned-decimal field
+/// could not be read or written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecimalError {
+    /// A digit was not in the range 0-9, or a zoned digit had the
+    /// wrong zone.
+    InvalidDigit {
+        /// The index of the offending digit, counting from the most
+        /// significant.
+        position: usize,
+    },
+    /// The sign nibble was not one of the recognised values.
+    InvalidSign,
+    /// The number does not fit in the field or in the integer type.
+    Overflow,
+}
+
+impl fmt::Display for DecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecimalError::InvalidDigit { position } => {
+                write!(f, "invalid decimal digit at position {}", position)
+            }
+            DecimalError::InvalidSign => write!(f, "invalid decimal sign"),
+            DecimalError::Overflow => write!(f, "decimal number out of range"),
+        }
+    }
+}
+
+/// A `LayoutError` reports why a list of fields is not a valid
+/// `LayoutPlan`.  Fields are identified by their index in the list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LayoutError {
+    /// A field extends past the end of the record.
+    OutOfBounds {
+        /// The index of the field.
+        field: usize,
+    },
+    /// Two fields overlap.
+    Overlap {
+        /// The index of the earlier field.
+        first: usize,
+        /// The index of the later field.
+        second: usize,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::OutOfBounds { field } => {
+                write!(f, "field {} extends past the end of the record", field)
+            }
+            LayoutError::Overlap { first, second } => {
+                write!(f, "fields {} and {} overlap", first, second)
+            }
+        }
+    }
+}
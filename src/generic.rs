@@ -0,0 +1,123 @@
+//! Conversions between array references and `GenericArray` references,
+//! available with the `generic-array` feature.
+
+use core::marker::PhantomData;
+use generic_array::typenum::Unsigned;
+use generic_array::{ArrayLength, GenericArray};
+
+struct AssertSameLen<N, const M: usize>(PhantomData<N>);
+
+impl<N: Unsigned, const M: usize> AssertSameLen<N, M> {
+    const OK: () = assert!(N::USIZE == M, "the arrays must be the same length");
+}
+
+/// You can use `generic_array_ref` to view an array reference, such
+/// as one from `array_ref!`, as a `GenericArray` of the same length,
+/// for APIs like RustCrypto's that take `GenericArray`s.  A mismatch
+/// between `M` and `N` is a compile error.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// extern crate generic_array;
+///
+/// use arrayref::generic_array_ref;
+/// use generic_array::typenum::U4;
+/// use generic_array::GenericArray;
+///
+/// fn block_sum(block: &GenericArray<u8, U4>) -> u32 {
+///     block.iter().map(|&b| b as u32).sum()
+/// }
+///
+/// # fn main() {
+/// let packet = [9, 1, 2, 3, 4, 9];
+/// assert_eq!(block_sum(generic_array_ref(array_ref!(packet, 1, 4))), 10);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// extern crate arrayref;
+/// extern crate generic_array;
+///
+/// use generic_array::typenum::U4;
+/// use generic_array::GenericArray;
+///
+/// # fn main() {
+/// let _: &GenericArray<u8, U4> = arrayref::generic_array_ref(&[0u8; 3]);
+/// # }
+/// ```
+#[inline]
+pub fn generic_array_ref<T, N: ArrayLength<T>, const M: usize>(
+    array: &[T; M],
+) -> &GenericArray<T, N> {
+    let () = AssertSameLen::<N, M>::OK;
+    GenericArray::from_slice(array)
+}
+
+/// The mutable version of `generic_array_ref`.
+#[inline]
+pub fn generic_array_mut<T, N: ArrayLength<T>, const M: usize>(
+    array: &mut [T; M],
+) -> &mut GenericArray<T, N> {
+    let () = AssertSameLen::<N, M>::OK;
+    GenericArray::from_mut_slice(array)
+}
+
+/// You can use `array_from_generic_ref` to view a `GenericArray`, such
+/// as a digest output, as an array reference of the same length, which
+/// the rest of this crate works with.  A mismatch between `N` and `M`
+/// is a compile error.
+///
+/// ```
+/// extern crate arrayref;
+/// extern crate generic_array;
+///
+/// use arrayref::array_from_generic_ref;
+/// use generic_array::typenum::U4;
+/// use generic_array::GenericArray;
+///
+/// # fn main() {
+/// let digest: &GenericArray<u8, U4> = GenericArray::from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+/// let bytes: &[u8; 4] = array_from_generic_ref(digest);
+/// assert_eq!(u32::from_be_bytes(*bytes), 0xdeadbeef);
+/// # }
+/// ```
+#[inline]
+pub fn array_from_generic_ref<T, N: ArrayLength<T>, const M: usize>(
+    array: &GenericArray<T, N>,
+) -> &[T; M] {
+    let () = AssertSameLen::<N, M>::OK;
+    unsafe { &*(array.as_ptr() as *const [T; M]) }
+}
+
+/// The mutable version of `array_from_generic_ref`.
+#[inline]
+pub fn array_from_generic_mut<T, N: ArrayLength<T>, const M: usize>(
+    array: &mut GenericArray<T, N>,
+) -> &mut [T; M] {
+    let () = AssertSameLen::<N, M>::OK;
+    unsafe { &mut *(array.as_mut_ptr() as *mut [T; M]) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use generic_array::typenum::{U0, U3};
+
+    #[test]
+    fn conversions_alias_the_same_elements() {
+        let mut data = [1u16, 2, 3, 4, 5];
+        {
+            let g: &mut GenericArray<u16, U3> = generic_array_mut(array_mut_ref!(data, 2, 3));
+            g[0] = 30;
+            let back: &mut [u16; 3] = array_from_generic_mut(g);
+            back[2] = 50;
+        }
+        assert_eq!(data, [1, 2, 30, 4, 50]);
+        let g: &GenericArray<u16, U3> = generic_array_ref(array_ref!(data, 0, 3));
+        assert_eq!(g.as_slice(), [1, 2, 30]);
+        assert_eq!(array_from_generic_ref::<_, _, 3>(g), &[1, 2, 30]);
+        let empty: &GenericArray<u16, U0> = generic_array_ref(&[]);
+        assert!(array_from_generic_ref::<_, _, 0>(empty).is_empty());
+    }
+}
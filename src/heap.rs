@@ -0,0 +1,251 @@
+//! Moving fixed-size arrays in and out of heap-allocated buffers,
+//! available with the `alloc` feature.
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::ptr;
+
+use cow::CowArrayRef;
+use error::LengthError;
+
+/// You can use `take_array` to remove the first `N` elements of a
+/// `Vec` and return them as an owned array, leaving the rest of the
+/// `Vec` in place, as a streaming decoder does with the bytes it has
+/// buffered.  It returns a `LengthError` and leaves the `Vec` alone if
+/// there are fewer than `N` elements.
+///
+/// ```
+/// use arrayref::take_array;
+///
+/// let mut backlog = vec![0, 3, b'a', b'b', b'c', 0];
+/// let header: [u8; 2] = take_array(&mut backlog).unwrap();
+/// let len = u16::from_be_bytes(header) as usize;
+/// assert_eq!(&backlog[..len], b"abc");
+/// assert_eq!(take_array::<u8, 8>(&mut backlog).unwrap_err().missing(), 4);
+/// assert_eq!(backlog.len(), 4);
+/// ```
+pub fn take_array<T, const N: usize>(vec: &mut Vec<T>) -> Result<[T; N], LengthError> {
+    let len = vec.len();
+    if len < N {
+        return Err(LengthError::new(N, len));
+    }
+    unsafe {
+        // The first `N` elements are moved into `array`, and the rest
+        // moved down over them, so each element is still owned once.
+        let p = vec.as_mut_ptr();
+        let array = ptr::read(p as *const [T; N]);
+        ptr::copy(p.add(N), p, len - N);
+        vec.set_len(len - N);
+        Ok(array)
+    }
+}
+
+/// You can use `deque_array_at` to read the `N` elements starting at
+/// `offset` in a `VecDeque`, or `None` if they are out of bounds.  They
+/// are borrowed in place when they are contiguous in the deque's
+/// storage, and copied only when they wrap around its end, so a header
+/// can be read from a ring-buffered reader without `make_contiguous`.
+///
+/// ```
+/// use arrayref::deque_array_at;
+/// use std::collections::VecDeque;
+///
+/// let mut backlog = VecDeque::with_capacity(4);
+/// backlog.extend([9, 9, 0, 5]);
+/// backlog.drain(..2);
+/// backlog.extend([b'h', b'i']);
+/// let len = deque_array_at::<u8, 2>(&backlog, 0).unwrap();
+/// assert_eq!(u16::from_be_bytes(*len), 5);
+/// assert_eq!(*deque_array_at::<u8, 2>(&backlog, 2).unwrap(), *b"hi");
+/// assert!(deque_array_at::<u8, 2>(&backlog, 3).is_none());
+/// ```
+pub fn deque_array_at<T: Copy, const N: usize>(
+    deque: &VecDeque<T>,
+    offset: usize,
+) -> Option<CowArrayRef<'_, T, N>> {
+    if offset > deque.len() || N > deque.len() - offset {
+        return None;
+    }
+    let (first, second) = deque.as_slices();
+    Some(if offset + N <= first.len() {
+        CowArrayRef::Borrowed(<&[T; N]>::try_from(&first[offset..offset + N]).unwrap())
+    } else if offset >= first.len() {
+        let start = offset - first.len();
+        CowArrayRef::Borrowed(<&[T; N]>::try_from(&second[start..start + N]).unwrap())
+    } else {
+        CowArrayRef::Owned(core::array::from_fn(|i| deque[offset + i]))
+    })
+}
+
+/// You can use `take_deque_array` to remove the first `N` elements of
+/// a `VecDeque` and return them as an owned array, as `take_array`
+/// does for a `Vec`.  It returns a `LengthError` and leaves the deque
+/// alone if there are fewer than `N` elements.
+///
+/// ```
+/// use arrayref::take_deque_array;
+/// use std::collections::VecDeque;
+///
+/// let mut backlog: VecDeque<u8> = (0..6).collect();
+/// let header: [u8; 4] = take_deque_array(&mut backlog).unwrap();
+/// assert_eq!(header, [0, 1, 2, 3]);
+/// assert_eq!(backlog, [4, 5]);
+/// assert_eq!(take_deque_array::<u8, 4>(&mut backlog).unwrap_err().missing(), 2);
+/// ```
+pub fn take_deque_array<T, const N: usize>(deque: &mut VecDeque<T>) -> Result<[T; N], LengthError> {
+    if deque.len() < N {
+        return Err(LengthError::new(N, deque.len()));
+    }
+    Ok(core::array::from_fn(|_| deque.pop_front().unwrap()))
+}
+
+/// You can use `try_into_boxed_array` to turn a boxed slice of exactly
+/// `N` elements into a boxed array, without copying or reallocating,
+/// so that a large buffer allocated on the heap can be handed to code
+/// that wants its length in its type.  If the slice has any other
+/// length, it is returned unchanged as the error.
+///
+/// ```
+/// use arrayref::try_into_boxed_array;
+///
+/// let buffer = vec![0u8; 4096].into_boxed_slice();
+/// let page: Box<[u8; 4096]> = try_into_boxed_array(buffer).unwrap();
+/// assert_eq!(page.len(), 4096);
+///
+/// let short = vec![0u8; 10].into_boxed_slice();
+/// assert_eq!(try_into_boxed_array::<u8, 16>(short).unwrap_err().len(), 10);
+/// ```
+pub fn try_into_boxed_array<T, const N: usize>(slice: Box<[T]>) -> Result<Box<[T; N]>, Box<[T]>> {
+    Box::try_from(slice)
+}
+
+/// You can use `into_boxed_slice` to forget the length of a boxed
+/// array, the reverse of `try_into_boxed_array`.  This never copies or
+/// reallocates.
+///
+/// ```
+/// use arrayref::into_boxed_slice;
+///
+/// let page = Box::new([7u8; 64]);
+/// let buffer: Box<[u8]> = into_boxed_slice(page);
+/// assert_eq!(buffer.len(), 64);
+/// ```
+pub fn into_boxed_slice<T, const N: usize>(array: Box<[T; N]>) -> Box<[T]> {
+    array
+}
+
+/// You can use `try_into_rc_array` to turn a shared `Rc<[T]>` of
+/// exactly `N` elements into an `Rc<[T; N]>` sharing the same
+/// allocation and reference count.  If the slice has any other length,
+/// it is returned unchanged as the error.
+///
+/// ```
+/// use arrayref::try_into_rc_array;
+/// use std::rc::Rc;
+///
+/// let id: Rc<[u8]> = Rc::from(&[1u8, 2, 3, 4][..]);
+/// let id: Rc<[u8; 4]> = try_into_rc_array(id).unwrap();
+/// assert_eq!(*id, [1, 2, 3, 4]);
+/// ```
+pub fn try_into_rc_array<T, const N: usize>(slice: Rc<[T]>) -> Result<Rc<[T; N]>, Rc<[T]>> {
+    Rc::try_from(slice)
+}
+
+/// You can use `into_rc_slice` to forget the length of an
+/// `Rc<[T; N]>`, the reverse of `try_into_rc_array`.
+pub fn into_rc_slice<T, const N: usize>(array: Rc<[T; N]>) -> Rc<[T]> {
+    array
+}
+
+/// You can use `try_into_arc_array` to turn a shared `Arc<[T]>` of
+/// exactly `N` elements into an `Arc<[T; N]>` sharing the same
+/// allocation and reference count, so that APIs can demand a key of a
+/// fixed size.  If the slice has any other length, it is returned
+/// unchanged as the error.
+///
+/// ```
+/// use arrayref::{into_arc_slice, try_into_arc_array};
+/// use std::sync::Arc;
+///
+/// let key: Arc<[u8]> = Arc::from(vec![0x42u8; 32]);
+/// let key: Arc<[u8; 32]> = try_into_arc_array(key).unwrap();
+/// let shared: Arc<[u8]> = into_arc_slice(key.clone());
+/// assert_eq!(Arc::strong_count(&key), 2);
+/// assert!(try_into_arc_array::<u8, 16>(shared).is_err());
+/// ```
+pub fn try_into_arc_array<T, const N: usize>(slice: Arc<[T]>) -> Result<Arc<[T; N]>, Arc<[T]>> {
+    Arc::try_from(slice)
+}
+
+/// You can use `into_arc_slice` to forget the length of an
+/// `Arc<[T; N]>`, the reverse of `try_into_arc_array`.
+pub fn into_arc_slice<T, const N: usize>(array: Arc<[T; N]>) -> Arc<[T]> {
+    array
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::{String, ToString};
+
+    #[test]
+    fn boxed_arrays_keep_their_allocation() {
+        let slice: Box<[u32]> = vec![1, 2, 3].into_boxed_slice();
+        let ptr = slice.as_ptr();
+        let array: Box<[u32; 3]> = try_into_boxed_array(slice).unwrap();
+        assert_eq!(*array, [1, 2, 3]);
+        let slice = into_boxed_slice(array);
+        assert_eq!(slice.as_ptr(), ptr);
+        let slice = try_into_boxed_array::<u32, 2>(slice).unwrap_err();
+        assert_eq!(&*slice, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn shared_arrays_keep_their_reference_count() {
+        let rc: Rc<[u8]> = Rc::from(&[1u8, 2][..]);
+        let other = rc.clone();
+        let array: Rc<[u8; 2]> = try_into_rc_array(rc).unwrap();
+        assert_eq!(Rc::strong_count(&array), 2);
+        assert!(Rc::ptr_eq(&into_rc_slice(array), &other));
+        assert!(try_into_rc_array::<u8, 3>(other).is_err());
+
+        let arc: Arc<[u8]> = Arc::from(&[3u8; 8][..]);
+        let array: Arc<[u8; 8]> = try_into_arc_array(arc).unwrap();
+        assert_eq!(*into_arc_slice(array), [3; 8]);
+    }
+
+    #[test]
+    fn deque_arrays_borrow_unless_wrapped() {
+        let mut deque: VecDeque<u8> = VecDeque::with_capacity(6);
+        deque.extend(0..6);
+        deque.drain(..3);
+        deque.extend(6..9);
+        let (first, second) = deque.as_slices();
+        assert_eq!((first.len(), second.len()), (3, 3));
+        for offset in 0..5 {
+            let array = deque_array_at::<u8, 2>(&deque, offset).unwrap();
+            assert_eq!(*array, [offset as u8 + 3, offset as u8 + 4]);
+            assert_eq!(array.is_owned(), offset == 2);
+        }
+        assert!(deque_array_at::<u8, 2>(&deque, 5).is_none());
+        assert!(deque_array_at::<u8, 0>(&deque, 6).unwrap().is_borrowed());
+        assert_eq!(take_deque_array::<u8, 4>(&mut deque).unwrap(), [3, 4, 5, 6]);
+        assert_eq!(deque, [7, 8]);
+    }
+
+    #[test]
+    fn take_array_moves_the_prefix() {
+        let mut words: Vec<String> = ["a", "b", "c"].iter().map(|w| w.to_string()).collect();
+        let [a]: [String; 1] = take_array(&mut words).unwrap();
+        assert_eq!(a, "a");
+        assert_eq!(words, ["b", "c"]);
+        assert!(take_array::<String, 3>(&mut words).is_err());
+        let all: [String; 2] = take_array(&mut words).unwrap();
+        assert_eq!(all, ["b", "c"]);
+        assert!(words.is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+//! Reading and writing fixed-size arrays through `std::io`, available with the
+//! `std` feature.
+
+use std::io::{Read, Result, Write};
+
+/// Extends every `std::io::Read` with a method for reading a
+/// fixed-size array.
+pub trait ReadArrayExt: Read {
+    /// You can use `read_exact_array` to read exactly `N` bytes into a
+    /// new array on the stack, as `read_exact` does, which is how most
+    /// binary formats begin: a magic number, then a fixed-size header.
+    /// It fails with `ErrorKind::UnexpectedEof` if the reader ends
+    /// first.  (The name avoids the unstable `Read::read_array` in
+    /// `std`.)
+    ///
+    /// ```
+    /// use arrayref::ReadArrayExt;
+    ///
+    /// let mut file = &b"\x89PNG\r\n\x1a\n\0\0\0\x0d"[..];
+    /// let magic: [u8; 8] = file.read_exact_array()?;
+    /// assert_eq!(&magic, b"\x89PNG\r\n\x1a\n");
+    /// let len = u32::from_be_bytes(file.read_exact_array()?);
+    /// assert_eq!(len, 13);
+    /// assert!(file.read_exact_array::<1>().is_err());
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn read_exact_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        self.read_exact(&mut array)?;
+        Ok(array)
+    }
+}
+
+impl<R: Read + ?Sized> ReadArrayExt for R {}
+
+/// Extends every `std::io::Write` with a method for writing a
+/// fixed-size array, to match `ReadArrayExt`.
+pub trait WriteArrayExt: Write {
+    /// You can use `write_array` to write all `N` bytes of `array`, as
+    /// `write_all` does.
+    ///
+    /// ```
+    /// use arrayref::WriteArrayExt;
+    ///
+    /// let mut out = Vec::new();
+    /// out.write_array(b"\x89PNG\r\n\x1a\n")?;
+    /// out.write_array(&13u32.to_be_bytes())?;
+    /// assert_eq!(out, b"\x89PNG\r\n\x1a\n\0\0\0\x0d");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn write_array<const N: usize>(&mut self, array: &[u8; N]) -> Result<()> {
+        self.write_all(array)
+    }
+}
+
+impl<W: Write + ?Sized> WriteArrayExt for W {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::{Cursor, ErrorKind};
+
+    #[test]
+    fn reads_arrays_in_sequence() {
+        let mut reader = Cursor::new([1u8, 2, 3, 4, 5]);
+        assert_eq!(reader.read_exact_array::<2>().unwrap(), [1, 2]);
+        assert_eq!(reader.read_exact_array::<0>().unwrap(), []);
+        assert_eq!(reader.read_exact_array::<3>().unwrap(), [3, 4, 5]);
+        let err = reader.read_exact_array::<1>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        let reader: &mut dyn Read = &mut &[6u8, 7][..];
+        assert_eq!(reader.read_exact_array::<2>().unwrap(), [6, 7]);
+    }
+
+    #[test]
+    fn writes_and_reads_back_arrays() {
+        let mut out = Cursor::new([0u8; 5]);
+        out.write_array(&[1, 2, 3]).unwrap();
+        out.write_array(&[]).unwrap();
+        out.write_array(&[4, 5]).unwrap();
+        let err = out.write_array(&[6]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        let mut frame = [0u8; 6];
+        let mut reader = Cursor::new(out.into_inner());
+        read_exact_into!(reader, frame, 1, 4).unwrap();
+        read_exact_into!(reader, frame[..], 5, 1).unwrap();
+        assert_eq!(frame, [0, 1, 2, 3, 4, 5]);
+        let err = read_exact_into!(reader, frame, 0, 1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+}
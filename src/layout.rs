@@ -0,0 +1,306 @@
+//! Record layouts described at runtime and validated once.
+
+use core::convert::TryFrom;
+use core::slice;
+
+use error::{LayoutError, LengthError};
+
+/// A `Field` describes one named field of a record: `len` elements
+/// starting at `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Field<'a> {
+    /// The field's name, for lookup with `LayoutPlan::find`.
+    pub name: &'a str,
+    /// The offset of the field within the record.
+    pub offset: usize,
+    /// The number of elements in the field.
+    pub len: usize,
+}
+
+impl<'a> Field<'a> {
+    /// Describes a field.
+    pub const fn new(name: &'a str, offset: usize, len: usize) -> Self {
+        Field { name, offset, len }
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// A `LayoutPlan` is a list of fields, such as one read from a schema
+/// at startup, that has been checked to fit in records of a given
+/// length without overlapping.  Once a record has been bound to the
+/// plan (which checks its length), fields can be read from it with no
+/// further bounds checks on the record, which keeps the checks out of
+/// the inner loop of code parsing many records of the same shape.
+///
+/// The read-only parts of the API are `const fn`s, so a plan can also
+/// be applied to a `static` blob at compile time, where a malformed
+/// layout or a blob that is too short is a build error:
+///
+/// ```
+/// use arrayref::{Field, LayoutPlan};
+///
+/// const DESCRIPTOR: &[u8; 8] = b"\x12\x01\x00\x02\xff\x00\x00\x40";
+/// const FIELDS: &[Field] = &[Field::new("bcd_usb", 2, 2), Field::new("max_packet", 7, 1)];
+/// const PLAN: LayoutPlan = match LayoutPlan::new(FIELDS, 8) {
+///     Ok(plan) => plan,
+///     Err(_) => panic!("bad descriptor layout"),
+/// };
+/// const MAX_PACKET: u8 = match PLAN.bind(DESCRIPTOR) {
+///     Ok(record) => record.field(1)[0],
+///     Err(_) => panic!("descriptor too short"),
+/// };
+/// assert_eq!(MAX_PACKET, 64);
+/// ```
+///
+/// ```compile_fail
+/// use arrayref::{Field, LayoutPlan};
+///
+/// const TRUNCATED: &[u8; 4] = b"\x12\x01\x00\x02";
+/// const PLAN: LayoutPlan = match LayoutPlan::new(&[Field::new("max_packet", 7, 1)], 8) {
+///     Ok(plan) => plan,
+///     Err(_) => panic!("bad descriptor layout"),
+/// };
+/// const MAX_PACKET: u8 = match PLAN.bind(TRUNCATED) {
+///     Ok(record) => record.field(0)[0],
+///     Err(_) => panic!("descriptor too short"),
+/// };
+/// # fn main() { let _ = MAX_PACKET; }
+/// ```
+///
+/// ```
+/// use arrayref::{Field, LayoutPlan};
+///
+/// let fields = [Field::new("id", 0, 4), Field::new("flags", 4, 1), Field::new("name", 8, 8)];
+/// let plan = LayoutPlan::new(&fields, 16).unwrap();
+/// let name = plan.find("name").unwrap();
+///
+/// let table = [0u8; 16 * 100];
+/// for chunk in table.chunks_exact(plan.record_len()) {
+///     let record = plan.bind(chunk).unwrap();
+///     assert_eq!(record.field(name), &[0; 8]);
+///     assert_eq!(record.array::<4>(0), Some(&[0; 4]));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayoutPlan<'a> {
+    fields: &'a [Field<'a>],
+    record_len: usize,
+}
+
+impl<'a> LayoutPlan<'a> {
+    /// Checks that every field lies within a record of `record_len`
+    /// elements and that no two fields overlap.  Empty fields never
+    /// overlap anything.
+    pub const fn new(fields: &'a [Field<'a>], record_len: usize) -> Result<Self, LayoutError> {
+        let mut i = 0;
+        while i < fields.len() {
+            let f = fields[i];
+            match f.offset.checked_add(f.len) {
+                Some(end) if end <= record_len => {}
+                _ => return Err(LayoutError::OutOfBounds { field: i }),
+            }
+            let mut j = 0;
+            while j < i {
+                let g = fields[j];
+                if f.len > 0
+                    && g.len > 0
+                    && f.offset < g.offset + g.len
+                    && g.offset < f.offset + f.len
+                {
+                    return Err(LayoutError::Overlap {
+                        first: j,
+                        second: i,
+                    });
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        Ok(LayoutPlan { fields, record_len })
+    }
+
+    /// The fields of the plan.
+    pub const fn fields(&self) -> &'a [Field<'a>] {
+        self.fields
+    }
+
+    /// The record length the plan was validated against.
+    pub const fn record_len(&self) -> usize {
+        self.record_len
+    }
+
+    /// The index of the field called `name`, if there is one.  Look
+    /// fields up once, outside any loop over records.
+    pub const fn find(&self, name: &str) -> Option<usize> {
+        let mut i = 0;
+        while i < self.fields.len() {
+            if str_eq(self.fields[i].name, name) {
+                return Some(i);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Binds a record to the plan, checking that it is at least
+    /// `record_len` elements long.
+    pub const fn bind<'b, T>(&self, record: &'b [T]) -> Result<Record<'a, 'b, T>, LengthError> {
+        if record.len() < self.record_len {
+            return Err(LengthError::new(self.record_len, record.len()));
+        }
+        Ok(Record {
+            fields: self.fields,
+            record,
+        })
+    }
+
+    /// Binds a mutable record to the plan, checking that it is at
+    /// least `record_len` elements long.
+    pub fn bind_mut<'b, T>(
+        &self,
+        record: &'b mut [T],
+    ) -> Result<RecordMut<'a, 'b, T>, LengthError> {
+        if record.len() < self.record_len {
+            return Err(LengthError::new(self.record_len, record.len()));
+        }
+        Ok(RecordMut {
+            fields: self.fields,
+            record,
+        })
+    }
+}
+
+/// A record bound to a `LayoutPlan`, from `LayoutPlan::bind`.
+///
+/// **Panics** (in each accessor) if the field index is not that of a
+/// field in the plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Record<'a, 'b, T: 'b> {
+    fields: &'a [Field<'a>],
+    record: &'b [T],
+}
+
+impl<'a, 'b, T> Record<'a, 'b, T> {
+    /// The elements of field `index`.
+    #[inline]
+    pub const fn field(&self, index: usize) -> &'b [T] {
+        let f = self.fields[index];
+        // The plan checked that every field ends within `record_len`,
+        // and `bind` checked that the record is at least that long.
+        unsafe { slice::from_raw_parts(self.record.as_ptr().add(f.offset), f.len) }
+    }
+
+    /// The elements of field `index` as an array reference, or `None`
+    /// if the field does not have exactly `N` elements.
+    #[inline]
+    pub const fn array<const N: usize>(&self, index: usize) -> Option<&'b [T; N]> {
+        let field = self.field(index);
+        if field.len() == N {
+            Some(unsafe { &*(field.as_ptr() as *const [T; N]) })
+        } else {
+            None
+        }
+    }
+}
+
+/// A mutable record bound to a `LayoutPlan`, from
+/// `LayoutPlan::bind_mut`.
+///
+/// **Panics** (in each accessor) if the field index is not that of a
+/// field in the plan.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RecordMut<'a, 'b, T: 'b> {
+    fields: &'a [Field<'a>],
+    record: &'b mut [T],
+}
+
+impl<'a, 'b, T> RecordMut<'a, 'b, T> {
+    /// The elements of field `index`.
+    #[inline]
+    pub fn field(&self, index: usize) -> &[T] {
+        let f = self.fields[index];
+        // See `Record::field`.
+        unsafe { self.record.get_unchecked(f.offset..f.offset + f.len) }
+    }
+
+    /// The elements of field `index`, mutably.
+    #[inline]
+    pub fn field_mut(&mut self, index: usize) -> &mut [T] {
+        let f = self.fields[index];
+        // See `Record::field`.
+        unsafe { self.record.get_unchecked_mut(f.offset..f.offset + f.len) }
+    }
+
+    /// The elements of field `index` as an array reference, or `None`
+    /// if the field does not have exactly `N` elements.
+    #[inline]
+    pub fn array<const N: usize>(&self, index: usize) -> Option<&[T; N]> {
+        <&[T; N]>::try_from(self.field(index)).ok()
+    }
+
+    /// The elements of field `index` as a mutable array reference, or
+    /// `None` if the field does not have exactly `N` elements.
+    #[inline]
+    pub fn array_mut<const N: usize>(&mut self, index: usize) -> Option<&mut [T; N]> {
+        <&mut [T; N]>::try_from(self.field_mut(index)).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn validation_catches_bad_layouts() {
+        let fields = [
+            Field::new("a", 0, 2),
+            Field::new("b", 4, 2),
+            Field::new("c", 1, 2),
+        ];
+        assert_eq!(
+            LayoutPlan::new(&fields, 6),
+            Err(LayoutError::Overlap {
+                first: 0,
+                second: 2
+            })
+        );
+        assert_eq!(
+            LayoutPlan::new(&fields[..2], 5),
+            Err(LayoutError::OutOfBounds { field: 1 })
+        );
+        let huge = [Field::new("x", usize::MAX, 2)];
+        assert!(LayoutPlan::new(&huge, usize::MAX).is_err());
+        let empty = [Field::new("x", 1, 0), Field::new("y", 0, 2)];
+        assert!(LayoutPlan::new(&empty, 2).is_ok());
+    }
+
+    #[test]
+    fn records_read_and_write_fields() {
+        let fields = [Field::new("len", 0, 2), Field::new("body", 2, 3)];
+        let plan = LayoutPlan::new(&fields, 5).unwrap();
+        let mut buf = [0u8; 6];
+        {
+            let mut rec = plan.bind_mut(&mut buf).unwrap();
+            *rec.array_mut::<2>(0).unwrap() = [0, 3];
+            rec.field_mut(1).copy_from_slice(b"abc");
+            assert_eq!(rec.array::<3>(0), None);
+        }
+        let rec = plan.bind(&buf).unwrap();
+        assert_eq!(rec.field(plan.find("body").unwrap()), b"abc");
+        assert_eq!(plan.bind(&buf[..4]).unwrap_err().missing(), 1);
+    }
+}
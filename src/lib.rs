@@ -1,6 +1,6 @@
-//! This package contains just four macros, which enable the creation
-//! of array references to portions of arrays or slices (or things
-//! that can be sliced).
+//! This package contains a handful of macros, which enable the
+//! creation of array references to portions of arrays or slices (or
+//! things that can be sliced).
 //!
 //! # Examples
 //!
@@ -27,10 +27,28 @@
 //! ```
 #![deny(warnings)]
 #![no_std]
+#![cfg_attr(feature = "nightly", feature(generic_const_exprs))]
+#![cfg_attr(feature = "nightly", allow(incomplete_features))]
 
-#[cfg(test)]
-#[macro_use]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(any(feature = "derive", feature = "layout", feature = "proc-macros"))]
+extern crate arrayref_derive;
+#[cfg(feature = "bytemuck")]
+extern crate bytemuck;
+#[cfg(feature = "futures-io")]
+extern crate futures_io;
+#[cfg(feature = "generic-array")]
+extern crate generic_array;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(any(test, feature = "std", feature = "futures-io"))]
+#[cfg_attr(test, macro_use)]
 extern crate std;
+#[cfg(feature = "uuid")]
+extern crate uuid;
+#[cfg(feature = "zerocopy")]
+extern crate zerocopy;
 
 /// You can use `array_ref` to generate an array reference to a subset
 /// of a sliceable bit of data (which could be an array, or a slice,
@@ -52,7 +70,6 @@ extern crate std;
 /// assert_eq!(4, read_u16(array_ref![data,4,2]));
 /// # }
 /// ```
-
 #[macro_export]
 macro_rules! array_ref {
     ($arr:expr, $offset:expr, $len:expr) => {{
@@ -62,7 +79,7 @@ macro_rules! array_ref {
                 &*(slice.as_ptr() as *const [_; $len])
             }
             let offset = $offset;
-            let slice = &$arr[offset..offset + $len];
+            let slice = $crate::__private::sub_slice(&$arr[..], offset, $len);
             #[allow(unused_unsafe)]
             unsafe {
                 as_array(slice)
@@ -71,193 +88,211 @@ macro_rules! array_ref {
     }};
 }
 
-/// You can use `array_refs` to generate a series of array references
-/// to an input array reference.  The idea is if you want to break an
-/// array into a series of contiguous and non-overlapping arrays.
-/// `array_refs` is a bit funny in that it insists on slicing up the
-/// *entire* array.  This is intentional, as I find it handy to make
-/// me ensure that my sub-arrays add up to the entire array.  This
-/// macro will *never* panic, since the sizes are all checked at
-/// compile time.
+/// You can use `array_ref_end` to generate an array reference to a
+/// subset of a sliceable bit of data, measuring the position from the
+/// end rather than the start.  `array_ref_end!(buf, offset, len)`
+/// refers to the `len` elements that end `offset` elements before the
+/// end of `buf`, so `array_ref_end!(buf, 0, 32)` is the last 32
+/// elements.  This saves computing `buf.len() - offset - len` by hand,
+/// which underflows rather than failing cleanly on a short buffer.
 ///
-/// Note that unlike `array_ref!`, `array_refs` *requires* that the
-/// first argument be an array reference.  The following arguments are
-/// the lengths of each subarray you wish a reference to.  The total
-/// of these arguments *must* equal the size of the array itself.
+/// **Panics** if the slice is out of bounds.
 ///
 /// ```
 /// #[macro_use]
 /// extern crate arrayref;
 ///
-/// fn read_u16(bytes: &[u8; 2]) -> u16 {
-///      bytes[0] as u16 + ((bytes[1] as u16) << 8)
-/// }
-/// // ...
 /// # fn main() {
-/// let data = [0,1,2,3,4,0,6,7];
-/// let (a,b,c) = array_refs![&data,2,2,4];
-/// assert_eq!(read_u16(a), 256);
-/// assert_eq!(read_u16(b), 3*256+2);
-/// assert_eq!(*c, [4,0,6,7]);
+/// // A message followed by a 4-byte CRC and a 2-byte footer.
+/// let frame = [b'h', b'i', 0xde, 0xad, 0xbe, 0xef, 0x0d, 0x0a];
+/// assert_eq!(array_ref_end!(frame, 0, 2), b"\r\n");
+/// assert_eq!(u32::from_be_bytes(*array_ref_end!(frame, 2, 4)), 0xdeadbeef);
 /// # }
 /// ```
 #[macro_export]
-macro_rules! array_refs {
-    ( $arr:expr, $( $pre:expr ),* ; .. ;  $( $post:expr ),* ) => {{
+macro_rules! array_ref_end {
+    ($arr:expr, $offset:expr, $len:expr) => {{
         {
-            use core::slice;
             #[inline]
-            #[allow(unused_assignments)]
-            #[allow(clippy::eval_order_dependence)]
-            const unsafe fn as_arrays<T>(a: &[T]) -> ( $( &[T; $pre], )* &[T],  $( &[T; $post], )*) {
-                const MIN_LEN: usize = 0usize $( .saturating_add($pre) )* $( .saturating_add($post) )*;
-                assert!(MIN_LEN < usize::MAX, "Your arrays are too big, are you trying to hack yourself?!");
-                let var_len = a.len() - MIN_LEN;
-                assert!(a.len() >= MIN_LEN);
-                let mut p = a.as_ptr();
-                ( $( {
-                    let aref = & *(p as *const [T; $pre]);
-                    p = p.add($pre);
-                    aref
-                }, )* {
-                    let sl = slice::from_raw_parts(p as *const T, var_len);
-                    p = p.add(var_len);
-                    sl
-                }, $( {
-                    let aref = & *(p as *const [T; $post]);
-                    p = p.add($post);
-                    aref
-                }, )*)
+            const unsafe fn as_array<T>(slice: &[T]) -> &[T; $len] {
+                &*(slice.as_ptr() as *const [_; $len])
             }
-            let input = $arr;
+            let offset = $offset;
+            let slice = $crate::__private::sub_slice_end(&$arr[..], offset, $len);
             #[allow(unused_unsafe)]
             unsafe {
-                as_arrays(input)
+                as_array(slice)
             }
         }
     }};
-    ( $arr:expr, $( $len:expr ),* ) => {{
+}
+
+/// You can use `const_array_ref` just like `array_ref!`, except that
+/// it can also be used in a `const` or `static` initializer or a
+/// `const fn`, for picking apart a binary blob embedded at compile
+/// time.  `array_ref!` cannot, since it slices through the `Index`
+/// trait and its bounds check can call a runtime failure handler.
+/// `$arr` must be something that derefs to a slice, such as an array
+/// or a slice reference.
+///
+/// **Panics** if the slice is out of bounds, which in a `const` is a
+/// compile error.  The panic does not go through the handler set up by
+/// the `bounds-hook` feature.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// const BLOB: [u8; 48] = [7; 48];
+/// const KEY: &[u8; 32] = const_array_ref!(BLOB, 16, 32);
+///
+/// # fn main() {
+/// assert_eq!(KEY, &[7; 32]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! const_array_ref {
+    ($arr:expr, $offset:expr, $len:expr) => {{
         {
             #[inline]
-            #[allow(unused_assignments)]
-            #[allow(clippy::eval_order_dependence)]
-            const unsafe fn as_arrays<T>(a: &[T; $( $len + )* 0 ]) -> ( $( &[T; $len], )* ) {
-                let mut p = a.as_ptr();
-                ( $( {
-                    let aref = &*(p as *const [T; $len]);
-                    p = p.offset($len as isize);
-                    aref
-                }, )* )
-            }
-            let input = $arr;
-            #[allow(unused_unsafe)]
-            unsafe {
-                as_arrays(input)
+            #[allow(unused_comparisons)]
+            const fn as_array<T>(slice: &[T], offset: usize) -> &[T; $len] {
+                assert!(
+                    offset <= slice.len() && $len <= slice.len() - offset,
+                    "const_array_ref! out of bounds"
+                );
+                unsafe { &*(slice.as_ptr().add(offset) as *const [T; $len]) }
             }
+            as_array(&$arr, $offset)
         }
-    }}
+    }};
 }
 
-/// You can use `mut_array_refs` to generate a series of mutable array
-/// references to an input mutable array reference.  The idea is if
-/// you want to break an array into a series of contiguous and
-/// non-overlapping mutable array references.  Like `array_refs!`,
-/// `mut_array_refs!` is a bit funny in that it insists on slicing up
-/// the *entire* array.  This is intentional, as I find it handy to
-/// make me ensure that my sub-arrays add up to the entire array.
-/// This macro will *never* panic, since the sizes are all checked at
-/// compile time.
+/// You can use `cell_array_ref` to take an array reference to a subset
+/// of a `&Cell<[T]>`, giving a `&[Cell<T>; N]` whose elements can be
+/// set through a shared reference.  A `&Cell<[T]>` comes from
+/// `Cell::from_mut` on a mutable slice.  A slice of `Cell`s,
+/// `&[Cell<T>]`, needs no special handling, since `array_ref!` works on
+/// it directly.
 ///
-/// Note that unlike `array_mut_ref!`, `mut_array_refs` *requires*
-/// that the first argument be a mutable array reference.  The
-/// following arguments are the lengths of each subarray you wish a
-/// reference to.  The total of these arguments *must* equal the size
-/// of the array itself.  Also note that this macro allows you to take
-/// out multiple mutable references to a single object, which is both
-/// weird and powerful.
+/// **Panics** if the slice is out of bounds.
 ///
 /// ```
 /// #[macro_use]
 /// extern crate arrayref;
 ///
-/// fn write_u16(bytes: &mut [u8; 2], num: u16) {
-///      bytes[0] = num as u8;
-///      bytes[1] = (num >> 8) as u8;
+/// use std::cell::Cell;
+///
+/// # fn main() {
+/// let mut frame = [0u8; 8];
+/// {
+///     let cells = Cell::from_mut(&mut frame[..]);
+///     let header = cell_array_ref!(cells, 0, 2);
+///     let trailer = cell_array_ref!(cells, 6, 2);
+///     header[0].set(0xaa);
+///     trailer[1].set(header[0].get());
 /// }
-/// fn write_u32(bytes: &mut [u8; 4], num: u32) {
-///      bytes[0] = num as u8;
-///      bytes[1] = (num >> 8) as u8; // this is buggy to save space...
+/// assert_eq!(frame, [0xaa, 0, 0, 0, 0, 0, 0, 0xaa]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! cell_array_ref {
+    ($cells:expr, $offset:expr, $len:expr) => {{
+        let cells: &$crate::__private::Cell<[_]> = $cells;
+        $crate::array_ref!(cells.as_slice_of_cells(), $offset, $len)
+    }};
+}
+
+/// You can use `try_array_ref` just like `array_ref!`, except that it
+/// returns `None` rather than panicking if the array would be out of
+/// bounds.  This is the one to use on untrusted input, where a short
+/// buffer is an error to report rather than a bug, and it works well
+/// with `?` in functions that return an `Option`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn payload_len(packet: &[u8]) -> Option<u16> {
+///     Some(u16::from_be_bytes(*try_array_ref!(packet, 4, 2)?))
 /// }
-/// // ...
+///
 /// # fn main() {
-/// let mut data = [0,1,2,3,4,0,6,7];
-/// let (a,b,c) = mut_array_refs![&mut data,2,2,4];
-/// // let's write out some nice prime numbers!
-/// write_u16(a, 37);
-/// write_u16(b, 73);
-/// write_u32(c, 137); // approximate inverse of the fine structure constant!
+/// assert_eq!(payload_len(&[0, 0, 0, 0, 1, 2, 3]), Some(258));
+/// assert_eq!(payload_len(&[0, 0, 0, 0, 1]), None);
 /// # }
 /// ```
 #[macro_export]
-macro_rules! mut_array_refs {
-    ( $arr:expr, $( $pre:expr ),* ; .. ;  $( $post:expr ),* ) => {{
+macro_rules! try_array_ref {
+    ($arr:expr, $offset:expr, $len:expr) => {{
         {
-            use core::slice;
             #[inline]
-            #[allow(unused_assignments)]
-            #[allow(clippy::eval_order_dependence)]
-            unsafe fn as_arrays<T>(a: &mut [T]) -> ( $( &mut [T; $pre], )* &mut [T],  $( &mut [T; $post], )*) {
-                const MIN_LEN: usize = 0usize $( .saturating_add($pre) )* $( .saturating_add($post) )*;
-                assert!(MIN_LEN < usize::MAX, "Your arrays are too big, are you trying to hack yourself?!");
-                let var_len = a.len() - MIN_LEN;
-                assert!(a.len() >= MIN_LEN);
-                let mut p = a.as_mut_ptr();
-                ( $( {
-                    let aref = &mut *(p as *mut [T; $pre]);
-                    p = p.add($pre);
-                    aref
-                }, )* {
-                    let sl = slice::from_raw_parts_mut(p as *mut T, var_len);
-                    p = p.add(var_len);
-                    sl
-                }, $( {
-                    let aref = &mut *(p as *mut [T; $post]);
-                    p = p.add($post);
-                    aref
-                }, )*)
-            }
-            let input = $arr;
-            #[allow(unused_unsafe)]
-            unsafe {
-                as_arrays(input)
+            const unsafe fn as_array<T>(slice: &[T]) -> &[T; $len] {
+                &*(slice.as_ptr() as *const [_; $len])
             }
+            let offset = $offset;
+            $crate::__private::try_sub_slice(&$arr[..], offset, $len).map(|slice| {
+                #[allow(unused_unsafe)]
+                unsafe {
+                    as_array(slice)
+                }
+            })
         }
     }};
-    ( $arr:expr, $( $len:expr ),* ) => {{
+}
+
+/// You can use `array_ref_unchecked` to generate an array reference
+/// just as with `array_ref!`, but without checking that it is in
+/// bounds, as `slice::get_unchecked` does for `slice::get`.  This is
+/// for inner loops where the length of the input has already been
+/// checked once and the optimizer cannot see it.  The bounds are still
+/// checked with `debug_assert!`.
+///
+/// The expansion calls an `unsafe fn`, so the macro must be used
+/// inside an `unsafe` block.
+///
+/// # Safety
+///
+/// `offset + len` must not be greater than the length of the input,
+/// otherwise the result is undefined behavior.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn sum_words(data: &[u8]) -> u32 {
+///     let mut sum = 0u32;
+///     for i in 0..data.len() / 4 {
+///         // `4 * i + 4 <= data.len()` for every `i` in the loop.
+///         let word = unsafe { array_ref_unchecked!(data, 4 * i, 4) };
+///         sum = sum.wrapping_add(u32::from_le_bytes(*word));
+///     }
+///     sum
+/// }
+///
+/// # fn main() {
+/// assert_eq!(sum_words(&[1, 0, 0, 0, 2, 0, 0, 0, 9]), 3);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! array_ref_unchecked {
+    ($arr:expr, $offset:expr, $len:expr) => {{
         {
             #[inline]
-            #[allow(unused_assignments)]
-            #[allow(clippy::eval_order_dependence)]
-            unsafe fn as_arrays<T>(a: &mut [T; $( $len + )* 0 ]) -> ( $( &mut [T; $len], )* ) {
-                let mut p = a.as_mut_ptr();
-                ( $( {
-                    let aref = &mut *(p as *mut [T; $len]);
-                    p = p.add($len);
-                    aref
-                }, )* )
-            }
-            let input = $arr;
-            #[allow(unused_unsafe)]
-            unsafe {
-                as_arrays(input)
+            unsafe fn as_array<T>(slice: &[T], offset: usize) -> &[T; $len] {
+                debug_assert!(offset <= slice.len() && $len <= slice.len() - offset);
+                &*(slice.as_ptr().add(offset) as *const [_; $len])
             }
+            let offset = $offset;
+            as_array(&$arr[..], offset)
         }
     }};
 }
 
-/// You can use `array_mut_ref` to generate a mutable array reference
-/// to a subset of a sliceable bit of data (which could be an array,
-/// or a slice, or a Vec).
+/// You can use `array_copy` to copy `len` elements of a sliceable bit
+/// of data, starting at `offset`, into an owned array, for when a
+/// borrow is not wanted, as when storing the array in a struct.  The
+/// elements must be `Copy`.
 ///
 /// **Panics** if the slice is out of bounds.
 ///
@@ -265,36 +300,2361 @@ macro_rules! mut_array_refs {
 /// #[macro_use]
 /// extern crate arrayref;
 ///
-/// fn write_u16(bytes: &mut [u8; 2], num: u16) {
-///      bytes[0] = num as u8;
-///      bytes[1] = (num >> 8) as u8;
+/// struct Session {
+///     id: [u8; 4],
 /// }
-/// // ...
+///
+/// # fn main() {
+/// let packet = vec![1, 0xde, 0xad, 0xbe, 0xef, 0];
+/// let session = Session { id: array_copy!(packet, 1, 4) };
+/// drop(packet);
+/// assert_eq!(session.id, [0xde, 0xad, 0xbe, 0xef]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! array_copy {
+    ($arr:expr, $offset:expr, $len:expr) => {
+        *$crate::array_ref!($arr, $offset, $len)
+    };
+}
+
+/// You can use `read_exact_into` to fill `len` elements of a sliceable
+/// bit of data, starting at `offset`, from a `std::io::Read`, as
+/// `reader.read_exact(array_mut_ref!(buf, offset, len))` would.  It
+/// returns an `io::Result<()>`, failing with `UnexpectedEof` if the
+/// reader ends first.  It is available with the `std` feature.
+///
+/// **Panics** if the slice is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut reader = &b"\x01\x02abc"[..];
+/// let mut frame = [0u8; 8];
+/// read_exact_into!(reader, frame, 0, 2)?;
+/// read_exact_into!(reader, frame, 5, 3)?;
+/// assert_eq!(&frame, b"\x01\x02\0\0\0abc");
+/// assert!(read_exact_into!(reader, frame, 0, 1).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! read_exact_into {
+    ($reader:expr, $buf:expr, $offset:expr, $len:expr) => {
+        $crate::__private::Read::read_exact(
+            &mut $reader,
+            $crate::array_mut_ref!($buf, $offset, $len),
+        )
+    };
+}
+
+/// You can use `struct_ref` to view `size_of::<T>()` elements of a
+/// sliceable bit of bytes, starting at `offset`, as a `&T`, for a type
+/// `T` that derives zerocopy's `FromBytes`, `KnownLayout` and
+/// `Immutable`.  This takes the place of `array_ref!` followed by a
+/// zerocopy conversion, with a single bounds check.  It is available
+/// with the `zerocopy` feature.
+///
+/// **Panics** if the slice is out of bounds, or if the region is not
+/// aligned for `T`.  Wire headers made of byte arrays or zerocopy's
+/// byte-order types have an alignment of one, so this never happens
+/// for them.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// extern crate zerocopy;
+///
+/// use zerocopy::{FromBytes, Immutable, KnownLayout};
+///
+/// #[derive(FromBytes, KnownLayout, Immutable)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     flags: u8,
+///     len: [u8; 2],
+/// }
+///
+/// # fn main() {
+/// let frame = [0xff, 0xff, 7, 0, 0, 3, b'a', b'b', b'c'];
+/// let header = struct_ref!(frame, 2, Header);
+/// assert_eq!((header.kind, u16::from_be_bytes(header.len)), (7, 3));
+/// # }
+/// ```
+#[cfg(feature = "zerocopy")]
+#[macro_export]
+macro_rules! struct_ref {
+    ($buf:expr, $offset:expr, $ty:ty) => {
+        $crate::__private::struct_ref::<$ty>(&$buf[..], $offset)
+    };
+}
+
+/// You can use `struct_mut_ref` just like `struct_ref!`, except that
+/// it gives a `&mut T`, for a type that also derives zerocopy's
+/// `IntoBytes` (and need not derive `Immutable`).
+///
+/// **Panics** if the slice is out of bounds, or if the region is not
+/// aligned for `T`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// extern crate zerocopy;
+///
+/// use zerocopy::{FromBytes, IntoBytes, KnownLayout};
+///
+/// #[derive(FromBytes, IntoBytes, KnownLayout)]
+/// #[repr(C)]
+/// struct Header {
+///     kind: u8,
+///     len: u8,
+/// }
+///
+/// # fn main() {
+/// let mut frame = [0u8; 6];
+/// let header = struct_mut_ref!(frame, 0, Header);
+/// header.kind = 1;
+/// header.len = 4;
+/// assert_eq!(frame, [1, 4, 0, 0, 0, 0]);
+/// # }
+/// ```
+#[cfg(feature = "zerocopy")]
+#[macro_export]
+macro_rules! struct_mut_ref {
+    ($buf:expr, $offset:expr, $ty:ty) => {
+        $crate::__private::struct_mut_ref::<$ty>(&mut $buf[..], $offset)
+    };
+}
+
+/// You can use `matrix_ref` to view `ROWS * COLS` elements of a
+/// sliceable bit of data, starting at `offset`, as a row-major matrix
+/// `&[[T; COLS]; ROWS]`, so that `m[r][c]` indexing needs no further
+/// bounds checks.
+///
+/// **Panics** if the slice is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let pixels = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+/// let m = matrix_ref!(pixels, 1, 3, 3);
+/// assert_eq!(m[0], [1, 2, 3]);
+/// assert_eq!(m[2][1], 8);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! matrix_ref {
+    ($arr:expr, $offset:expr, $rows:expr, $cols:expr) => {{
+        {
+            #[inline]
+            const unsafe fn as_matrix<T>(slice: &[T]) -> &[[T; $cols]; $rows] {
+                &*(slice.as_ptr() as *const [[_; $cols]; $rows])
+            }
+            let offset = $offset;
+            let slice = $crate::__private::sub_slice(&$arr[..], offset, ($rows) * ($cols));
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_matrix(slice)
+            }
+        }
+    }};
+}
+
+/// You can use `flatten_ref` to view an array reference of arrays,
+/// `&[[T; N]; M]`, as the flat array reference `&[T; L]` holding the
+/// same elements, where `L` is usually inferred from how the result
+/// is used.  That `L` is `N * M` is checked at compile time.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn checksum(bytes: &[u8; 8]) -> u8 {
+///     bytes.iter().fold(0, |sum, b| sum ^ b)
+/// }
+///
+/// # fn main() {
+/// let blocks = [[1, 2], [4, 8], [16, 32], [64, 128]];
+/// assert_eq!(checksum(flatten_ref!(&blocks)), 255);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let blocks = [[0u8; 16]; 4];
+/// let flat: &[u8; 60] = flatten_ref!(&blocks);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! flatten_ref {
+    ($arr:expr) => {
+        $crate::__private::flatten($arr)
+    };
+}
+
+/// You can use `flatten_mut_ref` to view a mutable array reference of
+/// arrays as a flat mutable array reference, as `flatten_ref!` does
+/// for an immutable one.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut blocks = [[0u8; 2]; 3];
+/// let flat: &mut [u8; 6] = flatten_mut_ref!(&mut blocks);
+/// flat.copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+/// assert_eq!(blocks, [[1, 2], [3, 4], [5, 6]]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! flatten_mut_ref {
+    ($arr:expr) => {
+        $crate::__private::flatten_mut($arr)
+    };
+}
+
+/// You can use `unflatten_ref` to view a flat array reference
+/// `&[T; L]` as an array reference of arrays, `&[[T; N]; M]`, the
+/// reverse of `flatten_ref!`.  `N` and `M` are usually inferred from
+/// how the result is used, and that `N * M` is `L` is checked at
+/// compile time.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let key_schedule = [0u8; 64];
+/// let round_keys: &[[u8; 16]; 4] = unflatten_ref!(&key_schedule);
+/// assert_eq!(round_keys[3], [0; 16]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! unflatten_ref {
+    ($arr:expr) => {
+        $crate::__private::unflatten($arr)
+    };
+}
+
+/// You can use `unflatten_mut_ref` to view a flat mutable array
+/// reference as a mutable array reference of arrays, as
+/// `unflatten_ref!` does for an immutable one.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut pixels = [0u8; 6];
+/// let rgb: &mut [[u8; 3]; 2] = unflatten_mut_ref!(&mut pixels);
+/// rgb[1] = [255, 128, 0];
+/// assert_eq!(pixels, [0, 0, 0, 255, 128, 0]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! unflatten_mut_ref {
+    ($arr:expr) => {
+        $crate::__private::unflatten_mut($arr)
+    };
+}
+
+/// You can use `split_even` to split an array reference `&[T; L]` into
+/// `K` equally sized pieces, as an array reference of arrays
+/// `&[[T; L / K]; K]` that can be destructured with a pattern such as
+/// `[a, b, c, d]`.  Since stable Rust cannot compute `L / K` in a type,
+/// the piece length is inferred from how the pieces are used, and it
+/// fails to compile if `K` pieces of that length do not exactly cover
+/// the array.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn round(state: &[u32; 4]) -> u32 {
+///     state.iter().fold(0, |a, &b| a.rotate_left(7) ^ b)
+/// }
+///
+/// # fn main() {
+/// let block = [1u32; 16];
+/// let [a, b, c, d] = split_even!(&block, 4);
+/// assert_eq!(round(a) ^ round(b), round(c) ^ round(d));
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let block = [0u8; 10];
+/// let [a, b, c]: &[[u8; 3]; 3] = split_even!(&block, 3);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! split_even {
+    ($arr:expr, $pieces:expr) => {
+        $crate::__private::Pieces::<{ $pieces }>::split($arr)
+    };
+}
+
+/// You can use `split_even_mut` to split a mutable array reference into
+/// `K` equally sized mutable pieces, as `split_even!` does for an
+/// immutable one.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut buf = [0u8; 8];
+/// {
+///     let [lo, hi]: &mut [[u8; 4]; 2] = split_even_mut!(&mut buf, 2);
+///     *lo = 1u32.to_le_bytes();
+///     *hi = 2u32.to_le_bytes();
+/// }
+/// assert_eq!(buf, [1, 0, 0, 0, 2, 0, 0, 0]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! split_even_mut {
+    ($arr:expr, $pieces:expr) => {
+        $crate::__private::Pieces::<{ $pieces }>::split_mut($arr)
+    };
+}
+
+/// You can use `array_refs` to generate a series of array references
+/// to an input array reference.  The idea is if you want to break an
+/// array into a series of contiguous and non-overlapping arrays.
+/// `array_refs` is a bit funny in that it insists on slicing up the
+/// *entire* array.  This is intentional, as I find it handy to make
+/// me ensure that my sub-arrays add up to the entire array.  This
+/// macro will *never* panic, since the sizes are all checked at
+/// compile time.
+///
+/// Note that unlike `array_ref!`, `array_refs` *requires* that the
+/// first argument be an array reference.  The following arguments are
+/// the lengths of each subarray you wish a reference to.  The total
+/// of these arguments *must* equal the size of the array itself.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn read_u16(bytes: &[u8; 2]) -> u16 {
+///      bytes[0] as u16 + ((bytes[1] as u16) << 8)
+/// }
+/// // ...
+/// # fn main() {
+/// let data = [0,1,2,3,4,0,6,7];
+/// let (a,b,c) = array_refs![&data,2,2,4];
+/// assert_eq!(read_u16(a), 256);
+/// assert_eq!(read_u16(b), 3*256+2);
+/// assert_eq!(*c, [4,0,6,7]);
+/// # }
+/// ```
+///
+/// Lengths can be grouped in parentheses, to get nested tuples whose
+/// shape follows the structure of the data.  As with tuples, a group
+/// of one needs a trailing comma, `(4,)`, since `(4)` is just a
+/// length.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let frame = [0u8; 60];
+/// let ((src, dst), payload, (nonce, tag, crc)) = array_refs!(&frame, (4, 4), 32, (8, 8, 4));
+/// assert_eq!((src.len() + dst.len(), payload.len()), (8, 32));
+/// assert_eq!((nonce.len(), tag.len(), crc.len()), (8, 8, 4));
+/// # }
+/// ```
+///
+/// A length written after a `_`, as in `_ 4`, skips that many elements
+/// without a reference to them in the result, which suits padding and
+/// reserved fields.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let record = [1, 0, 0, 0, 2, 3];
+/// let (kind, value) = array_refs!(&record, 1, _ 3, 2);
+/// assert_eq!((kind, value), (&[1], &[2, 3]));
+/// # }
+/// ```
+///
+/// A `..` in place of one of the lengths takes whatever is left as a
+/// slice, for formats with a fixed header and trailer around a
+/// variable-length body.  The input may then be a slice, and since its
+/// length is only known at run time, this form panics if it is shorter
+/// than the fixed-length segments.  Groups and `_` cannot be used
+/// with `..`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let packet = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+/// let (header, body, checksum) = array_refs!(&packet[..], 2, .., 4);
+/// assert_eq!(header, &[1, 2]);
+/// assert_eq!(body, &[3, 4, 5]);
+/// assert_eq!(checksum, &[6, 7, 8, 9]);
+/// # }
+/// ```
+///
+/// `array_refs!` can be used in a `const` or `static` initializer, given
+/// a reference to an array, or to a slice for the `..` form.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// const BLOB: [u8; 6] = [0, 1, 2, 3, 4, 5];
+/// const PARTS: (&[u8; 2], &[u8; 4]) = array_refs!(&BLOB, 2, 4);
+///
+/// # fn main() {
+/// assert_eq!(PARTS, (&[0, 1], &[2, 3, 4, 5]));
+/// # }
+/// ```
+#[cfg_attr(not(feature = "proc-macros"), macro_export)]
+macro_rules! array_refs {
+    ( $arr:expr, $( $segs:tt )* ) => {
+        $crate::__array_refs_tree!(@check ref [$arr] [ $( $segs )* ] [] $( $segs )*)
+    };
+}
+
+/// Support for `array_refs!` and `mut_array_refs!`.
+///
+/// `@check KIND [ARR] [ALL] [DONE] SEGMENTS` parses the segments as
+/// expressions, four at a time, to see whether any is a `_`, a `..` or
+/// a parenthesized group, keeping all of them in `ALL` and the lengths
+/// parsed so far in `DONE`.  If none is, the lengths go
+/// to `@plain`, or to `@semi` if they have the older `; .. ;` in them,
+/// neither of which recurses, so that long lists of lengths stay clear
+/// of the recursion limit however they are written.  Otherwise the
+/// segments go to `@scan`, which looks for a `..` (or `; .. ;`) among
+/// them, and expands to the `@remainder` form if there is one, and to
+/// the `@fixed` form otherwise.
+///
+/// `@list MODE ARGS [DONE] [SKIPPED] [CURRENT] SEGMENTS`
+/// splits the segments at their commas, and expands each one
+/// according to `MODE`: its total length (`len`), its type (`ty`,
+/// `ty_mut`) or the expression taking it from the pointer `p`
+/// (`take`, `take_mut`).  A segment that is a parenthesized list with a
+/// comma in it is a nested group, just as `(4,)` is a tuple and `(4)`
+/// is not.  The lengths of `_` segments are collected in `SKIPPED`
+/// until the next segment, or the end of the group, advances `p` past
+/// them.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __array_refs_tree {
+    (@check $kind:tt $arr:tt $all:tt $done:tt ; $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@resemi $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt .. $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt _ $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt ( $( $group:tt )* ) $( , $( $rest:tt )* )?) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $last:expr ; $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@resemi $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , .. $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , _ $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , ( $( $group:tt )* ) $( , $( $rest:tt )* )?) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $last:expr ; $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@resemi $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $s1:expr , .. $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $s1:expr , _ $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $s1:expr , ( $( $group:tt )* ) $( , $( $rest:tt )* )?) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $s1:expr , $last:expr ; $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@resemi $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $s1:expr , $s2:expr , .. $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $s1:expr , $s2:expr , _ $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $s1:expr , $s2:expr , ( $( $group:tt )* ) $( , $( $rest:tt )* )?) => {
+        $crate::__array_refs_tree!(@rescan $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt $done:tt $s0:expr , $s1:expr , $s2:expr , $last:expr ; $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@resemi $kind $arr $all)
+    };
+    (@check $kind:tt $arr:tt $all:tt [ $( $done:expr, )* ]
+        $s0:expr , $s1:expr , $s2:expr , $s3:expr , $( $rest:tt )+) => {
+        $crate::__array_refs_tree!(@check $kind $arr $all [ $( $done, )* $s0, $s1, $s2, $s3, ] $( $rest )+)
+    };
+    (@check $kind:tt $arr:tt $all:tt [ $( $done:expr, )* ] $( $len:expr ),* $(,)?) => {
+        $crate::__array_refs_tree!(@plain $kind $arr [ $( $done, )* $( $len, )* ])
+    };
+    (@rescan $kind:tt $arr:tt [ $( $segs:tt )* ]) => {
+        $crate::__array_refs_tree!(@scan $kind $arr [] $( $segs )*)
+    };
+    (@resemi $kind:tt $arr:tt [ $( $segs:tt )* ]) => {
+        $crate::__array_refs_tree!(@semi $kind $arr $( $segs )*)
+    };
+    (@semi $kind:tt $arr:tt $( $pre:expr ),* ; .. ; $( $post:expr ),*) => {
+        $crate::__array_refs_tree!(@remainder $kind $arr [ $( $pre ),* ] [ $( $post ),* ])
+    };
+
+    (@scan $kind:tt [$arr:expr] [ $( $pre:tt )* ] , .. $( , $( $post:tt )* )?) => {
+        $crate::__array_refs_tree!(@remainder $kind [$arr] [ $( $pre )* ] [ $( $( $post )* )? ])
+    };
+    (@scan $kind:tt [$arr:expr] [ $( $pre:tt )* ] ; .. ; $( $post:tt )*) => {
+        $crate::__array_refs_tree!(@remainder $kind [$arr] [ $( $pre )* ] [ $( $post )* ])
+    };
+    (@scan $kind:tt [$arr:expr] [] .. $( , $( $post:tt )* )?) => {
+        $crate::__array_refs_tree!(@remainder $kind [$arr] [] [ $( $( $post )* )? ])
+    };
+    (@scan $kind:tt [$arr:expr] [ $( $pre:tt )* ] $next:tt $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@scan $kind [$arr] [ $( $pre )* $next ] $( $rest )*)
+    };
+    (@scan $kind:tt [$arr:expr] [ $( $segs:tt )* ]) => {
+        $crate::__array_refs_tree!(@fixed $kind [$arr] $( $segs )*)
+    };
+
+    (@remainder ref [$arr:expr] [ $( $pre:expr ),* ] [ $( $post:expr ),* ]) => {{
+        {
+            use $crate::__private::slice;
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            const unsafe fn as_arrays<T>(a: &[T]) -> ( $( &[T; $pre], )* &[T],  $( &[T; $post], )*) {
+                const MIN_LEN: usize = 0usize $( .saturating_add($pre) )* $( .saturating_add($post) )*;
+                assert!(MIN_LEN < usize::MAX, "Your arrays are too big, are you trying to hack yourself?!");
+                let var_len = a.len() - MIN_LEN;
+                assert!(a.len() >= MIN_LEN);
+                let mut p = a.as_ptr();
+                ( $( {
+                    let aref = & *(p as *const [T; $pre]);
+                    p = p.add($pre);
+                    aref
+                }, )* {
+                    let sl = slice::from_raw_parts(p as *const T, var_len);
+                    p = p.add(var_len);
+                    sl
+                }, $( {
+                    let aref = & *(p as *const [T; $post]);
+                    p = p.add($post);
+                    aref
+                }, )*)
+            }
+            let input = $arr;
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_arrays(input)
+            }
+        }
+    }};
+    (@remainder mut [$arr:expr] [ $( $pre:expr ),* ] [ $( $post:expr ),* ]) => {{
+        {
+            use $crate::__private::slice;
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            unsafe fn as_arrays<T>(a: &mut [T]) -> ( $( &mut [T; $pre], )* &mut [T],  $( &mut [T; $post], )*) {
+                const MIN_LEN: usize = 0usize $( .saturating_add($pre) )* $( .saturating_add($post) )*;
+                assert!(MIN_LEN < usize::MAX, "Your arrays are too big, are you trying to hack yourself?!");
+                let var_len = a.len() - MIN_LEN;
+                assert!(a.len() >= MIN_LEN);
+                let mut p = a.as_mut_ptr();
+                ( $( {
+                    let aref = &mut *(p as *mut [T; $pre]);
+                    p = p.add($pre);
+                    aref
+                }, )* {
+                    let sl = slice::from_raw_parts_mut(p as *mut T, var_len);
+                    p = p.add(var_len);
+                    sl
+                }, $( {
+                    let aref = &mut *(p as *mut [T; $post]);
+                    p = p.add($post);
+                    aref
+                }, )*)
+            }
+            let input = $arr;
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_arrays(input)
+            }
+        }
+    }};
+    (@plain ref [$arr:expr] [ $( $len:expr, )+ ]) => {{
+        {
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            const unsafe fn as_arrays<T>(a: &[T; 0 $( + $len )+]) -> ( $( &[T; $len], )+ ) {
+                let mut p = a.as_ptr();
+                ( $( {
+                    let aref = &*(p as *const [T; $len]);
+                    p = p.add($len);
+                    aref
+                }, )+ )
+            }
+            let input = $arr;
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_arrays(input)
+            }
+        }
+    }};
+    (@plain mut [$arr:expr] [ $( $len:expr, )+ ]) => {{
+        {
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            unsafe fn as_arrays<T>(a: &mut [T; 0 $( + $len )+]) -> ( $( &mut [T; $len], )+ ) {
+                let mut p = a.as_mut_ptr();
+                ( $( {
+                    let aref = &mut *(p as *mut [T; $len]);
+                    p = p.add($len);
+                    aref
+                }, )+ )
+            }
+            let input = $arr;
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_arrays(input)
+            }
+        }
+    }};
+    (@fixed ref [$arr:expr] $( $segs:tt )*) => {{
+        {
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            const unsafe fn as_arrays<T>(
+                a: &[T; $crate::__array_refs_tree!(@list len [] [] [] [] $( $segs )*)],
+            ) -> $crate::__array_refs_tree!(@list ty [T] [] [] [] $( $segs )*) {
+                let mut p = a.as_ptr();
+                $crate::__array_refs_tree!(@list take [T p] [] [] [] $( $segs )*)
+            }
+            let input = $arr;
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_arrays(input)
+            }
+        }
+    }};
+    (@fixed mut [$arr:expr] $( $segs:tt )*) => {{
+        {
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            unsafe fn as_arrays<T>(
+                a: &mut [T; $crate::__array_refs_tree!(@list len [] [] [] [] $( $segs )*)],
+            ) -> $crate::__array_refs_tree!(@list ty_mut [T] [] [] [] $( $segs )*) {
+                let mut p = a.as_mut_ptr();
+                $crate::__array_refs_tree!(@list take_mut [T p] [] [] [] $( $segs )*)
+            }
+            let input = $arr;
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_arrays(input)
+            }
+        }
+    }};
+
+    (@list $mode:ident $args:tt $done:tt $skipped:tt []) => {
+        $crate::__array_refs_tree!(@join $mode $args $done $skipped)
+    };
+    (@list $mode:ident $args:tt $done:tt $skipped:tt [ $( $cur:tt )+ ]) => {
+        $crate::__array_refs_tree!(@list $mode $args $done $skipped [ $( $cur )+ ] ,)
+    };
+    (@list len [] [ $( $done:tt )* ] [] [ _ $( $len:tt )+ ] , $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@list len [] [ $( $done )* [ ( $( $len )+ ) ] ] [] [] $( $rest )*)
+    };
+    (@list $mode:ident $args:tt $done:tt [ $( $skipped:tt )* ] [ _ $( $len:tt )+ ] , $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@list $mode $args $done [ $( $skipped )* + ( $( $len )+ ) ] [] $( $rest )*)
+    };
+    (@list $mode:ident $args:tt [ $( $done:tt )* ] $skipped:tt [ $( $cur:tt )+ ] , $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@list $mode $args [
+            $( $done )* [ $crate::__array_refs_tree!(@skip $mode $args $skipped [
+                $crate::__array_refs_tree!(@seg $mode $args $( $cur )+)
+            ]) ]
+        ] [] [] $( $rest )*)
+    };
+    (@list $mode:ident $args:tt $done:tt $skipped:tt [ $( $cur:tt )* ] $next:tt $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@list $mode $args $done $skipped [ $( $cur )* $next ] $( $rest )*)
+    };
+
+    (@skip $mode:ident $args:tt [] [ $( $seg:tt )* ]) => { $( $seg )* };
+    (@skip $mode:ident [$T:ident $p:ident] [ $( $skipped:tt )+ ] [ $( $seg:tt )* ]) => {{
+        $p = $p.add(0 $( $skipped )+);
+        $( $seg )*
+    }};
+    (@skip $mode:ident $args:tt $skipped:tt [ $( $seg:tt )* ]) => { $( $seg )* };
+
+    (@join len [] [ $( [ $( $item:tt )* ] )* ] []) => { (0 $( + $( $item )* )*) };
+    (@join $mode:ident [$T:ident $p:ident] [ $( [ $( $item:tt )* ] )* ] [ $( $skipped:tt )+ ]) => {{
+        let group = ( $( $( $item )*, )* );
+        $p = $p.add(0 $( $skipped )+);
+        group
+    }};
+    (@join $mode:ident $args:tt [ $( [ $( $item:tt )* ] )* ] $skipped:tt) => { ( $( $( $item )*, )* ) };
+
+    (@seg $mode:ident $args:tt ( $( $inner:tt )* )) => {
+        $crate::__array_refs_tree!(@group $mode $args [ $( $inner )* ] $( $inner )*)
+    };
+    (@seg $mode:ident $args:tt $( $len:tt )+) => {
+        $crate::__array_refs_tree!(@leaf $mode $args $( $len )+)
+    };
+    (@group $mode:ident $args:tt [ $( $inner:tt )* ] _ $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@list $mode $args [] [] [] $( $inner )*)
+    };
+    (@group $mode:ident $args:tt [ $( $inner:tt )* ] $first:expr , $( $rest:tt )*) => {
+        $crate::__array_refs_tree!(@list $mode $args [] [] [] $( $inner )*)
+    };
+    (@group $mode:ident $args:tt [ $( $inner:tt )* ] $( $other:tt )*) => {
+        $crate::__array_refs_tree!(@leaf $mode $args ( $( $inner )* ))
+    };
+
+    (@leaf len [] $( $len:tt )+) => { ( $( $len )+ ) };
+    (@leaf ty [$T:ident] $( $len:tt )+) => { &[$T; $( $len )+] };
+    (@leaf ty_mut [$T:ident] $( $len:tt )+) => { &mut [$T; $( $len )+] };
+    (@leaf take [$T:ident $p:ident] $( $len:tt )+) => {{
+        let aref = &*($p as *const [$T; $( $len )+]);
+        $p = $p.add($( $len )+);
+        aref
+    }};
+    (@leaf take_mut [$T:ident $p:ident] $( $len:tt )+) => {{
+        let aref = &mut *($p as *mut [$T; $( $len )+]);
+        $p = $p.add($( $len )+);
+        aref
+    }};
+}
+
+/// You can use `try_array_refs` to split the start of a slice into a
+/// series of array references, as `array_refs!` does for an array
+/// reference.  It returns a `LengthError` saying how many elements are
+/// missing if the slice is too short.  Unlike `array_refs!`, it does
+/// not insist on using the whole slice: any elements beyond the total
+/// of the lengths are ignored.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let packet = [1, 2, 3, 4, 5, 6, 7, 8];
+/// let (kind, len, rest) = try_array_refs!(&packet[..], 1, 2, 4).unwrap();
+/// assert_eq!((kind, len, rest), (&[1], &[2, 3], &[4, 5, 6, 7]));
+///
+/// let err = try_array_refs!(&packet[..5], 1, 2, 4).unwrap_err();
+/// assert_eq!(err.missing(), 2);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! try_array_refs {
+    ( $arr:expr, $( $len:expr ),* ) => {{
+        {
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            const unsafe fn as_arrays<T>(a: &[T]) -> ( $( &[T; $len], )* ) {
+                let mut p = a.as_ptr();
+                ( $( {
+                    let aref = &*(p as *const [T; $len]);
+                    p = p.add($len);
+                    aref
+                }, )* )
+            }
+            const LEN: usize = $( $len + )* 0;
+            let input: &[_] = $arr;
+            if input.len() < LEN {
+                Err($crate::__private::length_error(LEN, input.len()))
+            } else {
+                #[allow(unused_unsafe)]
+                let arrays = unsafe { as_arrays(input) };
+                Ok(arrays)
+            }
+        }
+    }};
+}
+
+/// You can use `mut_array_refs` to generate a series of mutable array
+/// references to an input mutable array reference.  The idea is if
+/// you want to break an array into a series of contiguous and
+/// non-overlapping mutable array references.  Like `array_refs!`,
+/// `mut_array_refs!` is a bit funny in that it insists on slicing up
+/// the *entire* array.  This is intentional, as I find it handy to
+/// make me ensure that my sub-arrays add up to the entire array.
+/// This macro will *never* panic, since the sizes are all checked at
+/// compile time.
+///
+/// Note that unlike `array_mut_ref!`, `mut_array_refs` *requires*
+/// that the first argument be a mutable array reference.  The
+/// following arguments are the lengths of each subarray you wish a
+/// reference to.  The total of these arguments *must* equal the size
+/// of the array itself.  Also note that this macro allows you to take
+/// out multiple mutable references to a single object, which is both
+/// weird and powerful.  As for `array_refs!`, lengths can be grouped
+/// in parentheses to get nested tuples, `_ LEN` skips `LEN` elements,
+/// and a `..` takes the remainder of a mutable slice.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn write_u16(bytes: &mut [u8; 2], num: u16) {
+///      bytes[0] = num as u8;
+///      bytes[1] = (num >> 8) as u8;
+/// }
+/// fn write_u32(bytes: &mut [u8; 4], num: u32) {
+///      bytes[0] = num as u8;
+///      bytes[1] = (num >> 8) as u8; // this is buggy to save space...
+/// }
+/// // ...
+/// # fn main() {
+/// let mut data = [0,1,2,3,4,0,6,7];
+/// let (a,b,c) = mut_array_refs![&mut data,2,2,4];
+/// // let's write out some nice prime numbers!
+/// write_u16(a, 37);
+/// write_u16(b, 73);
+/// write_u32(c, 137); // approximate inverse of the fine structure constant!
+/// # }
+/// ```
+#[cfg_attr(not(feature = "proc-macros"), macro_export)]
+macro_rules! mut_array_refs {
+    ( $arr:expr, $( $segs:tt )* ) => {
+        $crate::__array_refs_tree!(@check mut [$arr] [ $( $segs )* ] [] $( $segs )*)
+    };
+}
+
+/// You can use `try_mut_array_refs` to split the start of a mutable
+/// slice into a series of mutable array references, as
+/// `mut_array_refs!` does for a mutable array reference.  As for
+/// `try_array_refs!`, it returns a `LengthError` if the slice is too
+/// short, and ignores any elements beyond the total of the lengths.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut buf = vec![0u8; 8];
+/// {
+///     let (kind, len) = try_mut_array_refs!(&mut buf[..], 1, 2).unwrap();
+///     *kind = [3];
+///     *len = 5u16.to_be_bytes();
+/// }
+/// assert_eq!(buf[..3], [3, 0, 5]);
+/// assert!(try_mut_array_refs!(&mut buf[..2], 1, 2).is_err());
+/// # }
+/// ```
+#[macro_export]
+macro_rules! try_mut_array_refs {
+    ( $arr:expr, $( $len:expr ),* ) => {{
+        {
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            unsafe fn as_arrays<T>(a: &mut [T]) -> ( $( &mut [T; $len], )* ) {
+                let mut p = a.as_mut_ptr();
+                ( $( {
+                    let aref = &mut *(p as *mut [T; $len]);
+                    p = p.add($len);
+                    aref
+                }, )* )
+            }
+            const LEN: usize = $( $len + )* 0;
+            let input: &mut [_] = $arr;
+            if input.len() < LEN {
+                Err($crate::__private::length_error(LEN, input.len()))
+            } else {
+                #[allow(unused_unsafe)]
+                let arrays = unsafe { as_arrays(input) };
+                Ok(arrays)
+            }
+        }
+    }};
+}
+
+/// You can use `slice_refs` to split the start of a slice into a
+/// series of array references, followed by a slice of whatever is left.
+/// This is `array_refs!` with a trailing `..`, for when the input is a
+/// `Vec` or a buffer whose length is only known at run time.  The
+/// length is checked once, and `slice_refs!` panics if the slice is
+/// shorter than the total of the lengths.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let packet = vec![1, 2, 3, 4, 5, 6, 7, 8];
+/// let (kind, len, payload) = slice_refs!(&packet, 1, 2);
+/// assert_eq!((kind, len, payload), (&[1], &[2, 3], &[4, 5, 6, 7, 8][..]));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! slice_refs {
+    ( $arr:expr, $( $len:expr ),* $(,)? ) => {
+        $crate::__array_refs_tree!(@remainder ref [$arr] [ $( $len ),* ] [])
+    };
+}
+
+/// You can use `mut_slice_refs` to split the start of a mutable slice
+/// into a series of mutable array references, followed by a mutable
+/// slice of whatever is left, as `slice_refs!` does for a slice.  It
+/// panics if the slice is shorter than the total of the lengths.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut packet = vec![0u8; 8];
+/// {
+///     let (kind, len, payload) = mut_slice_refs!(&mut packet, 1, 2);
+///     kind[0] = 7;
+///     *len = 5u16.to_be_bytes();
+///     payload[..5].copy_from_slice(b"hello");
+/// }
+/// assert_eq!(packet, b"\x07\x00\x05hello");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! mut_slice_refs {
+    ( $arr:expr, $( $len:expr ),* $(,)? ) => {
+        $crate::__array_refs_tree!(@remainder mut [$arr] [ $( $len ),* ] [])
+    };
+}
+
+/// You can use `ends_refs` to split a slice into an array reference to
+/// its first `HEAD` elements, a slice of the middle, and an array
+/// reference to its last `TAIL` elements, which is the shape of a
+/// format with a fixed header and footer around a variable-length
+/// body.  The length is checked once, and `ends_refs!` panics if the
+/// slice is shorter than `HEAD + TAIL`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let frame = [0x7e, 3, b'a', b'b', b'c', 0x12, 0x34];
+/// let (header, body, crc) = ends_refs!(&frame[..], 2, 2);
+/// assert_eq!(header, &[0x7e, 3]);
+/// assert_eq!(body, b"abc");
+/// assert_eq!(u16::from_be_bytes(*crc), 0x1234);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! ends_refs {
+    ( $arr:expr, $head:expr, $tail:expr ) => {
+        $crate::__array_refs_tree!(@remainder ref [$arr] [$head] [$tail])
+    };
+}
+
+/// You can use `mut_ends_refs` to split a mutable slice into disjoint
+/// mutable references to its first `HEAD` elements, its middle, and
+/// its last `TAIL` elements, as `ends_refs!` does for a slice.  It
+/// panics if the slice is shorter than `HEAD + TAIL`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut frame = [0u8; 7];
+/// {
+///     let (header, body, crc) = mut_ends_refs!(&mut frame[..], 2, 2);
+///     *header = [0x7e, body.len() as u8];
+///     body.copy_from_slice(b"abc");
+///     *crc = 0x1234u16.to_be_bytes();
+/// }
+/// assert_eq!(frame, [0x7e, 3, b'a', b'b', b'c', 0x12, 0x34]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! mut_ends_refs {
+    ( $arr:expr, $head:expr, $tail:expr ) => {
+        $crate::__array_refs_tree!(@remainder mut [$arr] [$head] [$tail])
+    };
+}
+
+/// You can use `array_refs_by` to split an array reference just like
+/// `array_refs!`, but with the segment lengths taken from a constant
+/// array `LENS: [usize; K]`, so that a single table of field widths
+/// can be shared between a parser and a serializer.  Since a macro
+/// cannot tell how many entries the table has, the indices of the
+/// segments are listed after a semicolon; they are checked at compile
+/// time to be exactly `0, 1, ..., K-1`.
+///
+/// As for `array_refs!`, the lengths must add up to the length of the
+/// input array.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// const WIDTHS: [usize; 3] = [2, 4, 2];
+///
+/// # fn main() {
+/// let record = [1,2, 3,4,5,6, 7,8];
+/// let (kind, body, crc) = array_refs_by!(&record, WIDTHS; 0, 1, 2);
+/// assert_eq!(*kind, [1,2]);
+/// assert_eq!(*body, [3,4,5,6]);
+/// assert_eq!(*crc, [7,8]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! array_refs_by {
+    ( $arr:expr, $lens:expr; $( $index:expr ),* $(,)* ) => {{
+        {
+            const _: () = {
+                let indices = [ $( $index ),* ];
+                assert!(indices.len() == $lens.len(), "every segment must be listed");
+                let mut i = 0;
+                while i < indices.len() {
+                    assert!(indices[i] == i, "segments must be listed in order");
+                    i += 1;
+                }
+            };
+            $crate::array_refs!($arr, $( $lens[$index] ),*)
+        }
+    }};
+}
+
+/// You can use `mut_array_refs_by` to split a mutable array reference
+/// just like `mut_array_refs!`, with the segment lengths taken from a
+/// constant array as for `array_refs_by!`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// const WIDTHS: [usize; 2] = [1, 3];
+///
+/// # fn main() {
+/// let mut record = [0u8; 4];
+/// {
+///     let (kind, body) = mut_array_refs_by!(&mut record, WIDTHS; 0, 1);
+///     *kind = [9];
+///     *body = [1, 2, 3];
+/// }
+/// assert_eq!(record, [9, 1, 2, 3]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! mut_array_refs_by {
+    ( $arr:expr, $lens:expr; $( $index:expr ),* $(,)* ) => {{
+        {
+            const _: () = {
+                let indices = [ $( $index ),* ];
+                assert!(indices.len() == $lens.len(), "every segment must be listed");
+                let mut i = 0;
+                while i < indices.len() {
+                    assert!(indices[i] == i, "segments must be listed in order");
+                    i += 1;
+                }
+            };
+            $crate::mut_array_refs!($arr, $( $lens[$index] ),*)
+        }
+    }};
+}
+
+/// You can use `array_mut_ref` to generate a mutable array reference
+/// to a subset of a sliceable bit of data (which could be an array,
+/// or a slice, or a Vec).
+///
+/// **Panics** if the slice is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn write_u16(bytes: &mut [u8; 2], num: u16) {
+///      bytes[0] = num as u8;
+///      bytes[1] = (num >> 8) as u8;
+/// }
+/// // ...
+/// # fn main() {
+/// let mut data = [0,1,2,3,4,0,6,7,8,9];
+/// write_u16(array_mut_ref![data,0,2], 1);
+/// write_u16(array_mut_ref![data,2,2], 5);
+/// assert_eq!(*array_ref![data,0,4], [1,0,5,0]);
+/// *array_mut_ref![data,4,5] = [4,3,2,1,0];
+/// assert_eq!(data, [1,0,5,0,4,3,2,1,0,9]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! array_mut_ref {
+    ($arr:expr, $offset:expr, $len:expr) => {{
+        {
+            #[inline]
+            unsafe fn as_array<T>(slice: &mut [T]) -> &mut [T; $len] {
+                &mut *(slice.as_mut_ptr() as *mut [_; $len])
+            }
+            let offset = $offset;
+            let slice = $crate::__private::sub_slice_mut(&mut $arr[..], offset, $len);
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_array(slice)
+            }
+        }
+    }};
+}
+
+/// You can use `matrix_mut_ref` to view `ROWS * COLS` elements of a
+/// mutable sliceable bit of data, starting at `offset`, as a mutable
+/// row-major matrix `&mut [[T; COLS]; ROWS]`, as `matrix_ref!` does
+/// for an immutable one.
+///
+/// **Panics** if the slice is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// // Draw a 2x2 tile at row 1, column 1 of a 4x4 framebuffer.
+/// let mut framebuffer = [0u8; 16];
+/// for row in 1..3 {
+///     let tile_row = matrix_mut_ref!(framebuffer, row * 4 + 1, 1, 2);
+///     tile_row[0] = [row as u8; 2];
+/// }
+/// let screen = matrix_mut_ref!(framebuffer, 0, 4, 4);
+/// assert_eq!(screen[2], [0, 2, 2, 0]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! matrix_mut_ref {
+    ($arr:expr, $offset:expr, $rows:expr, $cols:expr) => {{
+        {
+            #[inline]
+            unsafe fn as_matrix<T>(slice: &mut [T]) -> &mut [[T; $cols]; $rows] {
+                &mut *(slice.as_mut_ptr() as *mut [[_; $cols]; $rows])
+            }
+            let offset = $offset;
+            let slice = $crate::__private::sub_slice_mut(&mut $arr[..], offset, ($rows) * ($cols));
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_matrix(slice)
+            }
+        }
+    }};
+}
+
+/// You can use `array_mut_ref_unchecked` to generate a mutable array
+/// reference just as with `array_mut_ref!`, but without checking that
+/// it is in bounds.  See `array_ref_unchecked!`.
+///
+/// # Safety
+///
+/// `offset + len` must not be greater than the length of the input,
+/// otherwise the result is undefined behavior.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut data = [0u8; 8];
+/// for i in 0..4 {
+///     unsafe { *array_mut_ref_unchecked!(data, 2 * i, 2) = [i as u8; 2] };
+/// }
+/// assert_eq!(data, [0, 0, 1, 1, 2, 2, 3, 3]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! array_mut_ref_unchecked {
+    ($arr:expr, $offset:expr, $len:expr) => {{
+        {
+            #[inline]
+            unsafe fn as_array<T>(slice: &mut [T], offset: usize) -> &mut [T; $len] {
+                debug_assert!(offset <= slice.len() && $len <= slice.len() - offset);
+                &mut *(slice.as_mut_ptr().add(offset) as *mut [_; $len])
+            }
+            let offset = $offset;
+            as_array(&mut $arr[..], offset)
+        }
+    }};
+}
+
+/// You can use `array_mut_ref_end` to generate a mutable array
+/// reference measuring the position from the end, just as for
+/// `array_ref_end!`.
+///
+/// **Panics** if the slice is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut frame = vec![1u8, 2, 3, 0, 0];
+/// let sum = frame[..3].iter().sum::<u8>();
+/// *array_mut_ref_end!(frame, 0, 2) = [0, sum];
+/// assert_eq!(frame, [1, 2, 3, 0, 6]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! array_mut_ref_end {
+    ($arr:expr, $offset:expr, $len:expr) => {{
+        {
+            #[inline]
+            unsafe fn as_array<T>(slice: &mut [T]) -> &mut [T; $len] {
+                &mut *(slice.as_mut_ptr() as *mut [_; $len])
+            }
+            let offset = $offset;
+            let slice = $crate::__private::sub_slice_end_mut(&mut $arr[..], offset, $len);
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_array(slice)
+            }
+        }
+    }};
+}
+
+/// You can use `try_array_mut_ref` just like `array_mut_ref!`, except
+/// that it returns `None` rather than panicking if the array would be
+/// out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn set_ttl(packet: &mut [u8], ttl: u8) -> Option<()> {
+///     *try_array_mut_ref!(packet, 8, 1)? = [ttl];
+///     Some(())
+/// }
+///
+/// # fn main() {
+/// let mut packet = [0u8; 20];
+/// assert_eq!(set_ttl(&mut packet, 64), Some(()));
+/// assert_eq!(packet[8], 64);
+/// assert_eq!(set_ttl(&mut packet[..8], 64), None);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! try_array_mut_ref {
+    ($arr:expr, $offset:expr, $len:expr) => {{
+        {
+            #[inline]
+            unsafe fn as_array<T>(slice: &mut [T]) -> &mut [T; $len] {
+                &mut *(slice.as_mut_ptr() as *mut [_; $len])
+            }
+            let offset = $offset;
+            $crate::__private::try_sub_slice_mut(&mut $arr[..], offset, $len).map(|slice| {
+                #[allow(unused_unsafe)]
+                unsafe {
+                    as_array(slice)
+                }
+            })
+        }
+    }};
+}
+
+/// You can use `split_array_ref` to split a sliceable bit of data into
+/// an array reference to its first `len` elements and a slice of the
+/// rest, which is the usual way to separate a fixed-size header from a
+/// variable-length payload.
+///
+/// **Panics** if there are fewer than `len` elements.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let packet = [0x45, 0x00, 0x00, 0x05, b'h', b'e', b'l', b'l', b'o'];
+/// let (header, payload) = split_array_ref!(packet, 4);
+/// assert_eq!(u16::from_be_bytes([header[2], header[3]]), 5);
+/// assert_eq!(payload, b"hello");
+/// # }
+/// ```
+#[macro_export]
+macro_rules! split_array_ref {
+    ($arr:expr, $len:expr) => {{
+        {
+            #[inline]
+            const unsafe fn as_array<T>(slice: &[T]) -> &[T; $len] {
+                &*(slice.as_ptr() as *const [_; $len])
+            }
+            let (head, tail) = $crate::__private::split_head(&$arr[..], $len);
+            #[allow(unused_unsafe)]
+            let head = unsafe { as_array(head) };
+            (head, tail)
+        }
+    }};
+}
+
+/// You can use `split_array_mut_ref` to split a sliceable bit of data
+/// into a mutable array reference to its first `len` elements and a
+/// mutable slice of the rest, as for `split_array_ref!`.
+///
+/// **Panics** if there are fewer than `len` elements.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut buf = [0u8; 7];
+/// let (len, body) = split_array_mut_ref!(buf, 2);
+/// body[..3].copy_from_slice(b"abc");
+/// *len = 3u16.to_le_bytes();
+/// assert_eq!(buf, [3, 0, b'a', b'b', b'c', 0, 0]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! split_array_mut_ref {
+    ($arr:expr, $len:expr) => {{
+        {
+            #[inline]
+            unsafe fn as_array<T>(slice: &mut [T]) -> &mut [T; $len] {
+                &mut *(slice.as_mut_ptr() as *mut [_; $len])
+            }
+            let (head, tail) = $crate::__private::split_head_mut(&mut $arr[..], $len);
+            #[allow(unused_unsafe)]
+            let head = unsafe { as_array(head) };
+            (head, tail)
+        }
+    }};
+}
+
+/// You can use `rsplit_array_ref` to split a sliceable bit of data into
+/// a slice of all but its last `len` elements and an array reference to
+/// those last elements, such as a payload and its trailing checksum.
+///
+/// **Panics** if there are fewer than `len` elements.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let frame = [1u8, 2, 3, 0x00, 0x06];
+/// let (body, crc) = rsplit_array_ref!(frame, 2);
+/// assert_eq!(body.iter().map(|&b| b as u16).sum::<u16>(), u16::from_be_bytes(*crc));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! rsplit_array_ref {
+    ($arr:expr, $len:expr) => {{
+        {
+            #[inline]
+            const unsafe fn as_array<T>(slice: &[T]) -> &[T; $len] {
+                &*(slice.as_ptr() as *const [_; $len])
+            }
+            let (head, tail) = $crate::__private::split_tail(&$arr[..], $len);
+            #[allow(unused_unsafe)]
+            let tail = unsafe { as_array(tail) };
+            (head, tail)
+        }
+    }};
+}
+
+/// You can use `rsplit_array_mut_ref` to split a sliceable bit of data
+/// into a mutable slice of all but its last `len` elements and a
+/// mutable array reference to those last elements, as for
+/// `rsplit_array_ref!`.
+///
+/// **Panics** if there are fewer than `len` elements.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut frame = [1u8, 2, 3, 0, 0];
+/// let (body, crc) = rsplit_array_mut_ref!(frame, 2);
+/// *crc = body.iter().map(|&b| b as u16).sum::<u16>().to_be_bytes();
+/// assert_eq!(frame, [1, 2, 3, 0, 6]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! rsplit_array_mut_ref {
+    ($arr:expr, $len:expr) => {{
+        {
+            #[inline]
+            unsafe fn as_array<T>(slice: &mut [T]) -> &mut [T; $len] {
+                &mut *(slice.as_mut_ptr() as *mut [_; $len])
+            }
+            let (head, tail) = $crate::__private::split_tail_mut(&mut $arr[..], $len);
+            #[allow(unused_unsafe)]
+            let tail = unsafe { as_array(tail) };
+            (head, tail)
+        }
+    }};
+}
+
+/// You can use `zip_chunks` to walk two sliceable bits of data of the
+/// same length in lockstep, `len` elements at a time, getting pairs of
+/// array references.  Writing `mut` before the second one makes its
+/// half of each pair a mutable array reference.  This is shorthand for
+/// the `zip_chunks` and `zip_chunks_mut` functions.
+///
+/// **Panics** if the lengths differ.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let keystream = vec![0xaau8; 128];
+/// let mut message = vec![0u8; 128];
+/// for (key, block) in zip_chunks!(keystream, mut message, 64) {
+///     arrayref::xor_assign(block, key);
+/// }
+/// assert!(message.iter().all(|&b| b == 0xaa));
+/// assert_eq!(zip_chunks!(keystream, message, 32).count(), 4);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! zip_chunks {
+    ($a:expr, mut $b:expr, $len:expr) => {
+        $crate::zip_chunks_mut::<_, _, { $len }>(&$a[..], &mut $b[..])
+    };
+    ($a:expr, $b:expr, $len:expr) => {
+        $crate::zip_chunks::<_, _, { $len }>(&$a[..], &$b[..])
+    };
+}
+
+/// You can use `read_int` to read a number out of a sliceable bit of
+/// data in one expression: `read_int!(buf, offset, u32, le)` copies
+/// the `size_of::<u32>()` bytes at `offset` with `read_small_array`,
+/// and decodes them with `u32::from_le_bytes`.  The
+/// byte order is one of `le`, `be` or `ne` (native), and the type can
+/// be any of the primitive integer or floating point types.
+///
+/// **Panics** if the number is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let header = [0x7f, b'E', b'L', b'F', 2, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x3e, 0x00];
+/// assert_eq!(read_int!(header, 0, u32, be), 0x7f454c46);
+/// assert_eq!(read_int!(header, 16, u16, le), 0x3e);
+/// assert_eq!(read_int!(header, 4, i8, ne), 2);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! read_int {
+    ($arr:expr, $offset:expr, $t:ty, le) => {
+        <$t>::from_le_bytes($crate::read_small_array(&$arr[..], $offset))
+    };
+    ($arr:expr, $offset:expr, $t:ty, be) => {
+        <$t>::from_be_bytes($crate::read_small_array(&$arr[..], $offset))
+    };
+    ($arr:expr, $offset:expr, $t:ty, ne) => {
+        <$t>::from_ne_bytes($crate::read_small_array(&$arr[..], $offset))
+    };
+}
+
+/// You can use `write_int` to write a number into a sliceable bit of
+/// data in one expression: `write_int!(buf, offset, value, le)` encodes
+/// `value` with `to_le_bytes` and stores the bytes at `offset` with
+/// `write_small_array`, as `*array_mut_ref!(buf, offset, N) =
+/// value.to_le_bytes()` would, with `N` worked out from the type of
+/// `value`.  The byte order is one of
+/// `le`, `be` or `ne` (native), as for `read_int!`.
+///
+/// The type of `value` decides how many bytes are written, so give
+/// literals a suffix.
+///
+/// **Panics** if the number is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut header = [0u8; 8];
+/// write_int!(header, 0, 0xcafebabeu32, be);
+/// write_int!(header, 4, 52u16, be);
+/// write_int!(header, 6, -1i16, le);
+/// assert_eq!(header, [0xca, 0xfe, 0xba, 0xbe, 0, 52, 0xff, 0xff]);
+/// assert_eq!(read_int!(header, 0, u32, be), 0xcafebabe);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! write_int {
+    ($arr:expr, $offset:expr, $value:expr, le) => {
+        $crate::write_small_array(&mut $arr[..], $offset, ($value).to_le_bytes())
+    };
+    ($arr:expr, $offset:expr, $value:expr, be) => {
+        $crate::write_small_array(&mut $arr[..], $offset, ($value).to_be_bytes())
+    };
+    ($arr:expr, $offset:expr, $value:expr, ne) => {
+        $crate::write_small_array(&mut $arr[..], $offset, ($value).to_ne_bytes())
+    };
+}
+
+/// You can use `fields` to parse a fixed binary layout described once,
+/// field by field: `fields!(buf; magic: u32_be, flags: u16_le, nonce:
+/// [u8; 24], rest: ..)` checks up front that `buf` is long enough for
+/// every field, and then returns a struct whose fields are named as
+/// given, laid out one after another from the start of `buf`.
+///
+/// Each field is one of
+///
+/// - a number, as `u8` or `i8`, or as one of the other primitive
+///   integer or floating point types followed by `_le` or `_be` for
+///   its byte order, such as `u32_be` or `f64_le`, which is decoded,
+/// - `[u8; N]`, which becomes an array reference into `buf`, or
+/// - `..`, which becomes a slice of whatever follows the other fields,
+///   and which must come last.
+///
+/// The result is a `Result` whose error is a `LengthError` if `buf` is
+/// too short, so protocol code can pass it along with `?`.  The struct
+/// is declared by the macro and so cannot be named, but it can be bound
+/// to a variable and its fields read, and it is `Copy` and `Debug`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut packet = vec![0xca, 0xfe, 0xba, 0xbe, 0x02, 0x01];
+/// packet.extend_from_slice(&[7; 24]);
+/// packet.extend_from_slice(b"payload");
+///
+/// let header = fields!(packet; magic: u32_be, flags: u16_le, nonce: [u8; 24], rest: ..).unwrap();
+/// assert_eq!(header.magic, 0xcafebabe);
+/// assert_eq!(header.flags, 0x0102);
+/// assert_eq!(header.nonce, &[7; 24]);
+/// assert_eq!(header.rest, b"payload");
+///
+/// let err = fields!(packet[..20]; magic: u32_be, flags: u16_le, nonce: [u8; 24]).unwrap_err();
+/// assert_eq!(err.missing(), 10);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! fields {
+    ($arr:expr; $( $name:ident : $kind:tt ),+ $(,)*) => {{
+        {
+            #[derive(Debug, Clone, Copy, PartialEq)]
+            #[allow(dead_code)]
+            struct Fields<'a> {
+                $( $name: $crate::__fields_kind!(@ty 'a $kind), )+
+                _input: $crate::__private::PhantomData<&'a [u8]>,
+            }
+            const IS_REST: &[bool] = &[ $( $crate::__fields_kind!(@is_rest $kind) ),+ ];
+            const _: () = {
+                let mut i = 0;
+                while i + 1 < IS_REST.len() {
+                    assert!(!IS_REST[i], "`..` must be the last field");
+                    i += 1;
+                }
+            };
+            const LEN: usize = $( $crate::__fields_kind!(@len $kind) + )+ 0;
+            let input: &[u8] = &$arr[..];
+            if input.len() < LEN {
+                Err($crate::__private::length_error(LEN, input.len()))
+            } else {
+                let mut cursor = $crate::ArrayCursor::new(input);
+                Ok(Fields {
+                    $( $name: $crate::__fields_kind!(@read cursor, $kind), )+
+                    _input: $crate::__private::PhantomData,
+                })
+            }
+        }
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __fields_kind {
+    (@ty $lt:lifetime [u8; $len:expr]) => { &$lt [u8; $len] };
+    (@ty $lt:lifetime ..) => { &$lt [u8] };
+    (@ty $lt:lifetime u8) => { u8 };
+    (@ty $lt:lifetime i8) => { i8 };
+    (@ty $lt:lifetime u16_le) => { u16 };
+    (@ty $lt:lifetime u16_be) => { u16 };
+    (@ty $lt:lifetime u32_le) => { u32 };
+    (@ty $lt:lifetime u32_be) => { u32 };
+    (@ty $lt:lifetime u64_le) => { u64 };
+    (@ty $lt:lifetime u64_be) => { u64 };
+    (@ty $lt:lifetime u128_le) => { u128 };
+    (@ty $lt:lifetime u128_be) => { u128 };
+    (@ty $lt:lifetime i16_le) => { i16 };
+    (@ty $lt:lifetime i16_be) => { i16 };
+    (@ty $lt:lifetime i32_le) => { i32 };
+    (@ty $lt:lifetime i32_be) => { i32 };
+    (@ty $lt:lifetime i64_le) => { i64 };
+    (@ty $lt:lifetime i64_be) => { i64 };
+    (@ty $lt:lifetime i128_le) => { i128 };
+    (@ty $lt:lifetime i128_be) => { i128 };
+    (@ty $lt:lifetime f32_le) => { f32 };
+    (@ty $lt:lifetime f32_be) => { f32 };
+    (@ty $lt:lifetime f64_le) => { f64 };
+    (@ty $lt:lifetime f64_be) => { f64 };
+    (@len [u8; $len:expr]) => { $len };
+    (@len ..) => { 0 };
+    (@len $scalar:ident) => {
+        $crate::__private::mem::size_of::<$crate::__fields_kind!(@ty 'static $scalar)>()
+    };
+    (@is_rest ..) => { true };
+    (@is_rest $kind:tt) => { false };
+    (@read $cursor:ident, [u8; $len:expr]) => { $cursor.take::<{ $len }>() };
+    (@read $cursor:ident, ..) => { $cursor.rest() };
+    (@read $cursor:ident, u8) => { u8::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i8) => { i8::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, u16_le) => { u16::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, u16_be) => { u16::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, u32_le) => { u32::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, u32_be) => { u32::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, u64_le) => { u64::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, u64_be) => { u64::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, u128_le) => { u128::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, u128_be) => { u128::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i16_le) => { i16::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i16_be) => { i16::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i32_le) => { i32::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i32_be) => { i32::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i64_le) => { i64::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i64_be) => { i64::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i128_le) => { i128::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, i128_be) => { i128::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, f32_le) => { f32::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, f32_be) => { f32::from_be_bytes(*$cursor.take()) };
+    (@read $cursor:ident, f64_le) => { f64::from_le_bytes(*$cursor.take()) };
+    (@read $cursor:ident, f64_be) => { f64::from_be_bytes(*$cursor.take()) };
+}
+
+/// You can use `concat_arrays` to concatenate arrays, or array
+/// references, of `Copy` elements into a new owned array, as when
+/// building a nonce or a composite key.  The length of the result is
+/// usually inferred from how it is used, and that it is the total of
+/// the input lengths is checked at compile time.  Up to 16 arrays can
+/// be concatenated at once.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let salt = [0xa5u8; 4];
+/// let counter = 7u64.to_be_bytes();
+/// let nonce: [u8; 12] = concat_arrays!(&salt, counter);
+/// assert_eq!(nonce, [0xa5, 0xa5, 0xa5, 0xa5, 0, 0, 0, 0, 0, 0, 0, 7]);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let key: [u8; 16] = concat_arrays!([0u8; 8], [0u8; 4]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! concat_arrays {
+    ( $( $arr:expr ),+ $(,)? ) => {
+        $crate::__concat_arrays!(
+            []
+            [N0 a0] [N1 a1] [N2 a2] [N3 a3] [N4 a4] [N5 a5] [N6 a6] [N7 a7]
+            [N8 a8] [N9 a9] [N10 a10] [N11 a11] [N12 a12] [N13 a13] [N14 a14] [N15 a15];
+            $( $arr ),+
+        )
+    };
+}
+
+/// Support for `concat_arrays!`, which pairs each input with a name
+/// for its length and a name for the argument holding it, since
+/// `macro_rules!` cannot make up names of its own.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __concat_arrays {
+    (
+        [ $( $done:tt )* ] [$n:ident $a:ident] $( [$names:ident $args:ident] )*;
+        $arr:expr $( , $rest:expr )*
+    ) => {
+        $crate::__concat_arrays!(
+            [ $( $done )* [$n $a $arr] ] $( [$names $args] )*; $( $rest ),*
+        )
+    };
+    ( [ $( [$n:ident $a:ident $arr:expr] )+ ] $( [$names:ident $args:ident] )*; ) => {{
+        {
+            struct AssertTotal<$( const $n: usize, )+ const L: usize>;
+            impl<$( const $n: usize, )+ const L: usize> AssertTotal<$( $n, )+ L> {
+                const OK: () = assert!(
+                    0 $( + $n )+ == L,
+                    "the result must have exactly as many elements as the arrays"
+                );
+            }
+            #[inline]
+            fn concat<T: Copy, $( const $n: usize, )+ const L: usize>(
+                $( $a: &[T; $n], )+
+            ) -> [T; L] {
+                let () = AssertTotal::<$( $n, )+ L>::OK;
+                let mut out = $crate::__private::mem::MaybeUninit::<[T; L]>::uninit();
+                let p = out.as_mut_ptr() as *mut T;
+                let mut offset = 0;
+                $(
+                    // The lengths total `L`, so each array fits.
+                    unsafe { $crate::__private::ptr::copy_nonoverlapping($a.as_ptr(), p.add(offset), $n) };
+                    offset += $n;
+                )+
+                let _ = offset;
+                // Every element has been written.
+                unsafe { out.assume_init() }
+            }
+            concat($( &$arr, )+)
+        }
+    }};
+}
+
+/// You can use `swap_between` to swap a fixed-size window of one
+/// sliceable bit of data with an equally sized window of another.
+/// Since the two inputs are distinct mutable borrows, the windows can
+/// never overlap, and since the length is known at compile time the
+/// swap compiles down to an unrolled series of element swaps.
+///
+/// **Panics** if either window is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut front = [0,1,2,3,4,5];
+/// let mut back = [6,7,8,9];
+/// swap_between!(front, 1, back, 2, 2);
+/// assert_eq!(front, [0,8,9,3,4,5]);
+/// assert_eq!(back, [6,7,1,2]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! swap_between {
+    ($a:expr, $offset_a:expr, $b:expr, $offset_b:expr, $len:expr) => {{
+        {
+            #[inline]
+            fn swap_elements<T>(a: &mut [T; $len], b: &mut [T; $len]) {
+                a.swap_with_slice(b)
+            }
+            swap_elements(
+                $crate::array_mut_ref!($a, $offset_a, $len),
+                $crate::array_mut_ref!($b, $offset_b, $len),
+            )
+        }
+    }};
+}
+
+/// You can use `swap_arrays` to swap two equally sized windows of one
+/// mutable slice, as `swap_between!` does for windows of two different
+/// ones.
+///
+/// **Panics** if either window is out of bounds, or if the windows
+/// overlap.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut data = [0, 1, 2, 3, 4, 5, 6, 7];
+/// swap_arrays!(&mut data, 1, 5, 3);
+/// assert_eq!(data, [0, 5, 6, 7, 4, 1, 2, 3]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! swap_arrays {
+    ($arr:expr, $offset_a:expr, $offset_b:expr, $len:expr) => {{
+        let (a, b) = $crate::disjoint_mut_refs!($arr, ($offset_a, $len), ($offset_b, $len));
+        $crate::__private::mem::swap(a, b)
+    }};
+}
+
+/// You can use `disjoint_mut_refs` to take several mutable array
+/// references into one mutable slice at arbitrary offsets, given as
+/// `(offset, LEN)` pairs, which is awkward to do with `split_at_mut`.
+/// The lengths must be constants, but the offsets need not be.  When
+/// they are, the checks are folded away.
+///
+/// **Panics** if any region is out of bounds, or if any two regions
+/// overlap.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut buf = [0u8; 16];
+/// {
+///     let (src, dst) = disjoint_mut_refs!(&mut buf, (12, 4), (2, 4));
+///     *src = [1, 2, 3, 4];
+///     dst.copy_from_slice(src);
+/// }
+/// assert_eq!(buf, [0, 0, 1, 2, 3, 4, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! disjoint_mut_refs {
+    ( $arr:expr, $( ( $offset:expr, $len:expr ) ),+ $(,)? ) => {{
+        {
+            #[inline]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            unsafe fn as_arrays<'a, T>(
+                a: &'a mut [T],
+                regions: &[(usize, usize)],
+            ) -> ( $( &'a mut [T; $len], )+ ) {
+                let p = a.as_mut_ptr();
+                let mut regions = regions.iter();
+                ( $( &mut *(p.add(regions.next().unwrap().0) as *mut [T; $len]), )+ )
+            }
+            let regions: &[(usize, usize)] = &[ $( ($offset, $len) ),+ ];
+            let input: &mut [_] = $arr;
+            $crate::__private::check_disjoint(regions, input.len());
+            #[allow(unused_unsafe)]
+            unsafe {
+                as_arrays(input, regions)
+            }
+        }
+    }};
+}
+
+/// You can use `soa_columns_mut` to split a single mutable byte slice
+/// into several typed columns of `count` elements each, as is done
+/// when laying out struct-of-arrays data in one allocation.  The
+/// columns are laid out back to back in the order given, and each
+/// column type must implement `Plain`.
+///
+/// The column layout is checked at compile time: each column must
+/// start at an offset that is a multiple of its element alignment, so
+/// order columns from largest to smallest alignment.  The element
+/// count must be a constant.
+///
+/// **Panics** if the length of the slice is not exactly the total
+/// size of the columns, or if the slice is not aligned for the most
+/// strictly aligned column type.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// #[repr(align(8))]
+/// struct Storage([u8; 36]);
+///
+/// # fn main() {
+/// let mut storage = Storage([0; 36]);
+/// {
+///     let (positions, ids, flags) = soa_columns_mut!(&mut storage.0, 4; f32, u32, u8);
+///     positions[2] = 1.5;
+///     ids[3] = 7;
+///     flags[0] = 1;
+/// }
+/// assert_eq!(*array_ref!(storage.0, 8, 4), 1.5f32.to_ne_bytes());
+/// assert_eq!(*array_ref!(storage.0, 28, 4), 7u32.to_ne_bytes());
+/// assert_eq!(storage.0[32], 1);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! soa_columns_mut {
+    ( $bytes:expr, $count:expr; $( $ty:ty ),+ $(,)* ) => {{
+        {
+            #[inline]
+            #[allow(unused_assignments)]
+            #[allow(clippy::mixed_read_write_in_expression)]
+            fn as_columns(bytes: &mut [u8]) -> ( $( &mut [$ty; $count], )+ ) {
+                use $crate::__private::mem::{align_of, size_of};
+                fn plain<T: $crate::Plain>() {}
+                $( plain::<$ty>(); )+
+                // (total size, strictest alignment)
+                const LAYOUT: (usize, usize) = {
+                    let mut size = 0usize;
+                    let mut align = 1usize;
+                    $(
+                        assert!(size % align_of::<$ty>() == 0,
+                                "columns must be ordered so that each one is aligned");
+                        size += size_of::<[$ty; $count]>();
+                        if align_of::<$ty>() > align {
+                            align = align_of::<$ty>();
+                        }
+                    )+
+                    (size, align)
+                };
+                assert!(bytes.len() == LAYOUT.0, "slice length does not match the columns");
+                assert!(bytes.as_ptr() as usize % LAYOUT.1 == 0, "slice is not aligned for the columns");
+                let mut p = bytes.as_mut_ptr();
+                unsafe {
+                    ( $( {
+                        let column = &mut *(p as *mut [$ty; $count]);
+                        p = p.add(size_of::<[$ty; $count]>());
+                        column
+                    }, )+ )
+                }
+            }
+            as_columns($bytes)
+        }
+    }};
+}
+
+/// You can use `const_sub_array` to take an array reference to a
+/// window of an existing array reference, where the offset and length
+/// of the window are constants.  Unlike `array_ref!`, the bounds are
+/// checked at compile time: if `OFFSET + LEN` exceeds the length of
+/// the array, the code fails to compile, and the expansion contains
+/// no panic path at all.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn checksum(packet: &[u8; 8]) -> &[u8; 2] {
+///     const_sub_array!(packet, 6, 2)
+/// }
+/// # fn main() {
+/// let packet = [0,1,2,3,4,5,6,7];
+/// assert_eq!(checksum(&packet), &[6,7]);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// fn checksum(packet: &[u8; 8]) -> &[u8; 2] {
+///     const_sub_array!(packet, 7, 2)
+/// }
+/// # fn main() {
+/// # checksum(&[0; 8]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! const_sub_array {
+    ($aref:expr, $offset:expr, $len:expr) => {
+        <_ as $crate::__private::SubArray<{ $offset }, { $len }>>::sub_array($aref)
+    };
+}
+
+/// You can use `const_sub_array_mut` to take a mutable array reference
+/// to a window of an existing mutable array reference, with the
+/// bounds checked at compile time just as for `const_sub_array!`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// # fn main() {
+/// let mut header = [0u8; 8];
+/// *const_sub_array_mut!(&mut header, 4, 4) = 42u32.to_be_bytes();
+/// assert_eq!(header, [0,0,0,0, 0,0,0,42]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! const_sub_array_mut {
+    ($aref:expr, $offset:expr, $len:expr) => {
+        <_ as $crate::__private::SubArray<{ $offset }, { $len }>>::sub_array_mut($aref)
+    };
+}
+
+/// You can use `array_accessors` to give a struct that keeps its raw
+/// bytes in a `[u8; N]` field named getters and setters for fixed
+/// ranges of those bytes.  The buffer stays the source of truth, while
+/// the rest of the code gets to say `header.seq()` rather than
+/// `array_ref!(header.bytes, 4, 4)`.  Each range is checked against
+/// the length of the field at compile time, as for `const_sub_array!`.
+///
+/// Each entry gives the visibility and names of the getter and setter,
+/// then the offset and length of the range.  Attributes (such as doc
+/// comments) on an entry are applied to the getter.
+///
+/// An entry may also end with `=> OFFSET_NAME, SIZE_NAME` to define
+/// constants holding the offset and length of the range, next to the
+/// invocation and with the entry's visibility.  When the same format
+/// is read from C, these let one Rust definition be the single source
+/// of truth for the header offsets too.  Note that `cbindgen` only
+/// sees constants defined by a macro when it runs with `parse.expand`,
+/// which needs a nightly compiler.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// struct TcpHeader {
+///     bytes: [u8; 20],
+/// }
+///
+/// array_accessors! {
+///     impl TcpHeader.bytes {
+///         /// The source port.
+///         pub src_port, set_src_port: 0, 2;
+///         pub dst_port, set_dst_port: 2, 2;
+///         pub(crate) seq, set_seq: 4, 4;
+///     }
+/// }
+///
+/// array_accessors! {
+///     impl TcpHeader.bytes {
+///         pub flags, set_flags: 13, 1 => TCP_FLAGS_OFFSET, TCP_FLAGS_SIZE;
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!((TCP_FLAGS_OFFSET, TCP_FLAGS_SIZE), (13, 1));
+/// let mut header = TcpHeader { bytes: [0; 20] };
+/// header.set_seq(7u32.to_be_bytes());
+/// header.set_dst_port([0, 80]);
+/// assert_eq!(u32::from_be_bytes(*header.seq()), 7);
+/// assert_eq!(header.dst_port(), &[0, 80]);
+/// assert_eq!(header.src_port(), &[0, 0]);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// struct Tag {
+///     bytes: [u8; 4],
+/// }
+///
+/// array_accessors! {
+///     impl Tag.bytes {
+///         pub value, set_value: 2, 4;
+///     }
+/// }
+/// # fn main() {
+/// # Tag { bytes: [0; 4] }.value();
+/// # }
+/// ```
+#[macro_export]
+macro_rules! array_accessors {
+    ( impl $ty:ident . $field:ident {
+        $(
+            $(#[$attr:meta])* $vis:vis $get:ident , $set:ident : $offset:expr , $len:expr
+            $( => $offset_name:ident , $size_name:ident )? ;
+        )*
+    } ) => {
+        impl $ty {
+            $(
+                $(#[$attr])*
+                #[inline]
+                $vis fn $get(&self) -> &[u8; $len] {
+                    $crate::const_sub_array!(&self.$field, $offset, $len)
+                }
+
+                #[doc = concat!("Sets the bytes returned by `", stringify!($get), "`.")]
+                #[inline]
+                $vis fn $set(&mut self, value: [u8; $len]) {
+                    *$crate::const_sub_array_mut!(&mut self.$field, $offset, $len) = value;
+                }
+            )*
+        }
+
+        $( $(
+            #[doc = concat!("The offset of the bytes returned by `", stringify!($ty), "::", stringify!($get), "`.")]
+            $vis const $offset_name: usize = $offset;
+            #[doc = concat!("The number of bytes returned by `", stringify!($ty), "::", stringify!($get), "`.")]
+            $vis const $size_name: usize = $len;
+        )? )*
+    };
+}
+
+#[cfg(feature = "futures-io")]
+mod async_read;
+mod bits;
+mod bitwise;
+mod bounds;
+mod builder;
+mod bytes;
+mod cell;
+mod chained;
+mod chunk_iter;
+mod chunks;
+mod cow;
+mod cursor;
+mod decimal;
+mod delay_line;
+mod double_buffer;
+mod dump;
+mod endian;
+mod error;
+#[cfg(feature = "generic-array")]
+#[allow(deprecated)]
+mod generic;
+#[cfg(feature = "alloc")]
+mod heap;
+#[cfg(feature = "std")]
+mod io;
+mod layout;
+mod narrow;
+mod net;
+#[cfg(feature = "nightly")]
+mod nightly;
+mod owned;
+#[cfg(feature = "alloc")]
+mod owned_ref;
+#[cfg(feature = "rayon")]
+mod par;
+mod pin;
+mod plain;
+mod plan;
+#[cfg(feature = "bytemuck")]
+mod pod;
+mod records;
+mod ring;
+mod slice_ext;
+mod small;
+mod split;
+mod sub_array;
+mod uninit;
+#[cfg(feature = "uuid")]
+mod uuid_fields;
+mod window;
+mod zero;
+#[cfg(feature = "zerocopy")]
+mod zerocopy_view;
+
+/// With the `layout` feature, you can use `layout!` to describe a
+/// record as a list of consecutive fields once, and get the offsets,
+/// lengths and accessors that would otherwise be kept in sync with each
+/// other by hand.
+///
+/// `layout! { HEADER = { MAGIC: 4, VERSION: 2, KEY: 32 } }` lays the
+/// fields out one after another, and defines
+///
+/// - `HEADER_MAGIC_OFFSET` and `HEADER_MAGIC_LEN`, the position of each
+///   field in the record, and so on for the other fields,
+/// - `HEADER_SIZE`, the total length,
+/// - `header_magic!(buf)`, which takes an array reference to the field
+///   in `buf` as `array_ref!(buf, HEADER_MAGIC_OFFSET, HEADER_MAGIC_LEN)`
+///   would, and
+/// - `header_magic_mut!(buf)`, which does the same as `array_mut_ref!`.
+///
+/// So `HEADER_KEY_OFFSET` is 6, `HEADER_SIZE` is 38, and inserting a
+/// field moves everything after it.  The lengths may refer to constants
+/// in scope at the invocation.  The constants have the visibility given
+/// to the record, and take its attributes, such as doc comments, for
+/// `_SIZE` and those of each field for the rest.  The accessor macros
+/// can be used after the invocation in the same module and its
+/// children, like any `macro_rules!` macro, wherever the constants are
+/// in scope.
+///
+/// **Panics**, in the accessors, if the field is out of bounds.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// const KEY_LEN: usize = 32;
+///
+/// layout! {
+///     /// The length of the header of a key file.
+///     pub HEADER = {
+///         MAGIC: 4,
+///         VERSION: 2,
+///         EMPTY: 0,
+///         /// The public key.
+///         KEY: KEY_LEN,
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!((HEADER_KEY_OFFSET, HEADER_KEY_LEN, HEADER_SIZE), (6, 32, 38));
+/// assert_eq!((HEADER_EMPTY_OFFSET, HEADER_EMPTY_LEN), (6, 0));
+/// let mut file = vec![0u8; HEADER_SIZE];
+/// *header_magic_mut!(file) = *b"KEY1";
+/// header_key_mut!(file)[0] = 0xed;
+/// assert_eq!(header_magic!(file), b"KEY1");
+/// assert_eq!(header_key!(file), array_ref!(file, 6, 32));
+/// assert_eq!(file[6], 0xed);
+/// # }
+/// ```
+#[cfg(feature = "layout")]
+pub use arrayref_derive::layout;
+/// Derives a constructor for a struct whose fields are array references
+/// into one buffer, one after another, optionally followed by a slice
+/// of whatever is left over, so that such a view can be made with a
+/// single length check instead of a hand-written chain of `array_ref!`
+/// calls.  It is available with the `derive` feature.
+///
+/// For a struct of shared references, the derive adds
+/// `fn from_slice(slice: &'a [T]) -> Result<Self, LengthError>`, and
+/// for a struct of mutable references it adds
+/// `fn from_mut_slice(slice: &'a mut [T]) -> Result<Self, LengthError>`.
+/// Either one fails if `slice` is shorter than the arrays together;
+/// when there is no final slice field, anything after the arrays is
+/// ignored.
+///
+/// ```
+/// extern crate arrayref;
+///
+/// use arrayref::ArrayRefs;
+///
+/// #[derive(ArrayRefs, Debug)]
+/// struct Header<'a> {
+///     magic: &'a [u8; 4],
+///     key: &'a [u8; 32],
+///     rest: &'a [u8],
+/// }
+///
+/// #[derive(ArrayRefs)]
+/// struct Trailer<'a> {
+///     crc: &'a mut [u8; 4],
+/// }
+///
+/// # fn main() {
+/// let mut file = [0u8; 40];
+/// let header = Header::from_slice(&file).unwrap();
+/// assert_eq!((header.magic, header.key.len(), header.rest), (&[0; 4], 32, &[0; 4][..]));
+/// assert_eq!(Header::from_slice(&file[..35]).unwrap_err().missing(), 1);
+///
+/// *Trailer::from_mut_slice(&mut file[36..]).unwrap().crc = [1, 2, 3, 4];
+/// assert_eq!(file[36..], [1, 2, 3, 4]);
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+pub use arrayref_derive::ArrayRefs;
+/// With the `proc-macros` feature, `array_refs!` and `mut_array_refs!`
+/// are procedural macros.  They take the same input and expand to the
+/// same code, but mistakes in the input are reported where they are,
+/// in plain words, rather than as type errors inside the expansion: a
+/// length that is not a constant is pointed out, as is an input array
+/// whose length is not the sum of the segment lengths.
+///
+/// ```compile_fail
+/// #[macro_use]
+/// extern crate arrayref;
+///
 /// # fn main() {
-/// let mut data = [0,1,2,3,4,0,6,7,8,9];
-/// write_u16(array_mut_ref![data,0,2], 1);
-/// write_u16(array_mut_ref![data,2,2], 5);
-/// assert_eq!(*array_ref![data,0,4], [1,0,5,0]);
-/// *array_mut_ref![data,4,5] = [4,3,2,1,0];
-/// assert_eq!(data, [1,0,5,0,4,3,2,1,0,9]);
+/// let data = [0u8; 64];
+/// // error: sum of segment lengths (62) does not match the length of
+/// // the input `[u8; 64]`
+/// let (magic, key, crc) = array_refs!(&data, 4, 32, 26);
 /// # }
 /// ```
-#[macro_export]
-macro_rules! array_mut_ref {
-    ($arr:expr, $offset:expr, $len:expr) => {{
-        {
-            #[inline]
-            unsafe fn as_array<T>(slice: &mut [T]) -> &mut [T; $len] {
-                &mut *(slice.as_mut_ptr() as *mut [_; $len])
-            }
-            let offset = $offset;
-            let slice = &mut $arr[offset..offset + $len];
-            #[allow(unused_unsafe)]
-            unsafe {
-                as_array(slice)
-            }
-        }
-    }};
+#[cfg(feature = "proc-macros")]
+pub use arrayref_derive::{array_refs, mut_array_refs};
+#[cfg(feature = "futures-io")]
+pub use async_read::{AsyncReadArrayExt, ReadExactArray};
+pub use bits::{BitOrder, BitView, BitViewMut};
+pub use bitwise::{and_assign, and_assign_at, or_assign, or_assign_at, xor_assign, xor_assign_at};
+#[cfg(feature = "bounds-hook")]
+pub use bounds::set_bounds_failure_handler;
+pub use builder::{collect_array, try_array_from_fn, ArrayBuilder, ArrayFill};
+pub use bytes::{ByteReader, ByteWriter};
+pub use cell::cell_array;
+pub use chained::ChainedArrayRef;
+pub use chunk_iter::{
+    zip_chunks, zip_chunks_mut, ArrayChunks, ArrayChunksMut, ArrayRChunks, ArrayRChunksMut,
+    ArrayStrided, ArrayStridedMut, ArrayWindows,
+};
+pub use chunks::{
+    as_chunks, as_chunks_mut, binary_search_chunks, diff_chunks, partition_point_chunks, take_many,
+    take_many_mut, ChunkDiff,
+};
+/// A re-export of `core::array::from_fn`.
+pub use core::array::from_fn as array_from_fn;
+pub use cow::CowArrayRef;
+pub use cursor::{ArrayCursor, ArrayCursorMut, Checked, Checking, TrustedInput};
+pub use decimal::{
+    bcd_digits, read_bcd, read_packed_decimal, read_zoned_decimal, write_bcd, write_packed_decimal,
+    write_zoned_decimal,
+};
+pub use delay_line::DelayLine;
+pub use double_buffer::DoubleBuffer;
+pub use dump::SegmentDump;
+pub use endian::{
+    bswap_array, read_u128, read_u16, read_u24, read_u32, read_u40, read_u48, read_u56, read_u64,
+    write_u128, write_u16, write_u24, write_u32, write_u40, write_u48, write_u56, write_u64,
+    BigEndian, ByteOrder, LittleEndian, NetworkEndian, SwapBytes,
+};
+pub use error::{BoundsFailure, CollectError, DecimalError, LayoutError, LengthError};
+#[cfg(feature = "generic-array")]
+pub use generic::{
+    array_from_generic_mut, array_from_generic_ref, generic_array_mut, generic_array_ref,
+};
+#[cfg(feature = "alloc")]
+pub use heap::{
+    deque_array_at, into_arc_slice, into_boxed_slice, into_rc_slice, take_array, take_deque_array,
+    try_into_arc_array, try_into_boxed_array, try_into_rc_array,
+};
+#[cfg(feature = "std")]
+pub use io::{ReadArrayExt, WriteArrayExt};
+pub use layout::{Field, LayoutPlan, Record, RecordMut};
+pub use narrow::{array_at_u16, array_at_u16_mut, array_at_u8, array_at_u8_mut};
+pub use net::{
+    read_ipv4, read_ipv6, read_port, read_socket_v4, read_socket_v6, write_ipv4, write_ipv6,
+    write_port, write_socket_v4, write_socket_v6,
+};
+#[cfg(feature = "nightly")]
+pub use nightly::{SplitPrefix, SplitSuffix};
+pub use owned::{map_with_index, split_array, unzip_arrays, zip_arrays};
+#[cfg(feature = "alloc")]
+pub use owned_ref::{ArcArrayRef, OwnedArrayRef};
+#[cfg(feature = "rayon")]
+pub use par::{par_array_chunks, par_array_chunks_mut};
+pub use pin::{pin_array_mut_ref, pin_array_ref};
+pub use plain::Plain;
+pub use plan::{ArraySplit, Seg};
+#[cfg(feature = "bytemuck")]
+pub use pod::{byte_array_mut, byte_array_ref, cast_array_mut, cast_array_ref};
+pub use records::{RecordSlice, RecordSliceMut};
+pub use ring::ArrayRing;
+pub use slice_ext::SliceExt;
+pub use small::{read_small_array, write_small_array};
+pub use split::{split_lengths, split_lengths_mut, SplitLengths, SplitLengthsMut};
+pub use sub_array::ArrayExt;
+pub use uninit::{assume_init_array_mut, assume_init_array_ref, write_array, write_array_at};
+#[cfg(feature = "uuid")]
+pub use uuid_fields::{read_uuid, read_uuid_le, write_uuid, write_uuid_hyphenated, write_uuid_le};
+pub use window::ArrayWindow;
+pub use zero::{secure_zero, secure_zero_at};
+
+#[doc(hidden)]
+pub mod __private {
+    //! Re-exports used by the expansions of this crate's macros.
+    pub use bounds::{
+        check_disjoint, split_head, split_head_mut, split_tail, split_tail_mut, sub_slice,
+        sub_slice_end, sub_slice_end_mut, sub_slice_mut, try_sub_slice, try_sub_slice_mut,
+    };
+    pub use chunks::{flatten, flatten_mut, unflatten, unflatten_mut, Pieces};
+    pub use core::cell::Cell;
+    pub use core::marker::PhantomData;
+    pub use core::mem;
+    pub use core::ptr;
+    pub use core::result::Result::{self, Err, Ok};
+    pub use core::slice;
+    #[cfg(feature = "std")]
+    pub use std::io::Read;
+    pub use sub_array::SubArray;
+    #[cfg(feature = "zerocopy")]
+    pub use zerocopy_view::{struct_mut_ref, struct_ref};
+
+    /// Constructs a `LengthError` for the fallible macros.
+    pub const fn length_error(required: usize, available: usize) -> ::LengthError {
+        ::LengthError::new(required, available)
+    }
 }
 
 #[allow(clippy::all)]
@@ -478,6 +2838,311 @@ mod test {
         assert_eq!(&[10; 10], array_ref![data, 118, 10]);
     }
 
+    #[test]
+    fn array_refs_take_long_plain_lists() {
+        let mut data: [u8; 64] = [0; 64];
+        {
+            let refs = mut_array_refs!(
+                &mut data, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+                1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+                1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1
+            );
+            *refs.0 = [1];
+            *refs.63 = [64];
+        }
+        let refs = array_refs!(
+            &data, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+            1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+            1, 1, 1, 1, 1, 1, 1, 1
+        );
+        assert_eq!((refs.0, refs.1, refs.63), (&[1], &[0], &[64]));
+        let refs = array_refs!(&data[..], 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1; ..; 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1);
+        assert_eq!((refs.0, refs.32, refs.64), (&[1], &[][..], &[64]));
+    }
+
+    #[test]
+    fn array_refs_take_long_lists_of_paths() {
+        mod lens {
+            pub const ONE: usize = 1;
+        }
+        struct Pair<const A: usize, const B: usize>;
+        impl<const A: usize, const B: usize> Pair<A, B> {
+            const LEN: usize = A + B;
+        }
+        let mut data = [0u8; 130];
+        {
+            let refs = mut_array_refs!(
+                &mut data,
+                Pair::<1, 1>::LEN,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE,
+                lens::ONE
+            );
+            *refs.0 = [1, 2];
+            *refs.128 = [3];
+        }
+        let refs = array_refs!(
+            &data,
+            Pair::<1, 1>::LEN,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE,
+            lens::ONE
+        );
+        assert_eq!((refs.0, refs.1, refs.128), (&[1, 2], &[0], &[3]));
+        let refs = array_refs!(&data[..], Pair::<1, 1>::LEN; ..; lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE, lens::ONE);
+        assert_eq!((refs.0, refs.1, refs.129), (&[1, 2], &[][..], &[3]));
+    }
+
     #[forbid(clippy::ptr_offset_with_cast)]
     #[test]
     fn forbidden_clippy_lints_do_not_fire() {
@@ -501,4 +3166,598 @@ mod test {
         let (_,) = array_refs![&data,; ..;];
         let (_,) = mut_array_refs![&mut data,; ..;];
     }
+
+    #[test]
+    fn swap_between_swaps_windows() {
+        let mut a: Vec<u8> = (0..10).collect();
+        let mut b = [100u8; 6];
+        swap_between!(a, 7, b, 0, 3);
+        assert_eq!(a, [0, 1, 2, 3, 4, 5, 6, 100, 100, 100]);
+        assert_eq!(b, [7, 8, 9, 100, 100, 100]);
+        swap_between!(&mut a[..], 7, &mut b[..], 0, 3);
+        assert_eq!(a, (0..10).collect::<Vec<u8>>());
+        assert_eq!(b, [100; 6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_between_checks_bounds() {
+        let mut a = [0u8; 4];
+        let mut b = [0u8; 8];
+        swap_between!(a, 2, b, 0, 3);
+    }
+
+    #[test]
+    fn array_refs_by_follows_the_table() {
+        const LENS: [usize; 4] = [1, 0, 5, 2];
+        let mut data = [0u8, 1, 2, 3, 4, 5, 6, 7];
+        {
+            let (a, b, c, d) = array_refs_by!(&data, LENS; 0, 1, 2, 3);
+            assert_eq!((a, b, c, d), array_refs!(&data, 1, 0, 5, 2));
+        }
+        {
+            let (_, _, c, _) = mut_array_refs_by!(&mut data, LENS; 0, 1, 2, 3);
+            *c = [9; 5];
+        }
+        assert_eq!(data, [0, 9, 9, 9, 9, 9, 6, 7]);
+    }
+
+    #[repr(align(8))]
+    struct Aligned<const N: usize>([u8; N]);
+
+    #[test]
+    fn soa_columns_line_up() {
+        let mut storage = Aligned([0; 3 * 8 + 3 * 2 + 3]);
+        {
+            let (a, b, c) = soa_columns_mut!(&mut storage.0[..], 3; u64, i16, [u8; 1]);
+            *a = [1, 2, 3];
+            *b = [-1, -2, -3];
+            *c = [[4], [5], [6]];
+        }
+        assert_eq!(*array_ref!(storage.0, 16, 8), 3u64.to_ne_bytes());
+        assert_eq!(*array_ref!(storage.0, 26, 2), (-2i16).to_ne_bytes());
+        assert_eq!(storage.0[30..], [4, 5, 6]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn soa_columns_check_length() {
+        let mut storage = Aligned([0; 16]);
+        soa_columns_mut!(&mut storage.0[..15], 2; u32, u32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn soa_columns_check_alignment() {
+        let mut storage = Aligned([0; 17]);
+        soa_columns_mut!(&mut storage.0[1..], 2; u32, u32);
+    }
+
+    struct Registers {
+        raw: [u8; 6],
+    }
+
+    array_accessors! {
+        impl Registers.raw {
+            status, set_status: 0, 1;
+            count, set_count: 1, 4;
+            empty, set_empty: 6, 0 => EMPTY_OFFSET, EMPTY_SIZE;
+        }
+    }
+
+    #[test]
+    fn accessors_read_and_write_the_buffer() {
+        let mut regs = Registers { raw: [0; 6] };
+        regs.set_count([1, 2, 3, 4]);
+        regs.set_status([9]);
+        regs.set_empty([]);
+        assert_eq!(regs.raw, [9, 1, 2, 3, 4, 0]);
+        assert_eq!((regs.status(), regs.count()), (&[9], &[1, 2, 3, 4]));
+        assert_eq!(regs.empty(), &[]);
+        assert_eq!((EMPTY_OFFSET, EMPTY_SIZE), (6, 0));
+    }
+
+    #[test]
+    fn const_array_ref_works_in_consts() {
+        const BLOB: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+        const TAIL: &[u8] = &BLOB;
+        const HEAD: &[u8; 3] = const_array_ref!(BLOB, 0, 3);
+        const LAST: &[u8; 2] = const_array_ref!(TAIL, 6, 2);
+        const EMPTY: &[u8; 0] = const_array_ref!(BLOB, 8, 0);
+        assert_eq!((HEAD, LAST, EMPTY), (&[0, 1, 2], &[6, 7], &[]));
+        let data: Vec<u8> = BLOB.to_vec();
+        assert_eq!(const_array_ref!(data, 2, 4), array_ref!(data, 2, 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "const_array_ref! out of bounds")]
+    fn const_array_ref_panics_out_of_bounds() {
+        let data = [0u8; 4];
+        let _ = const_array_ref!(data, 3, 2);
+    }
+
+    #[test]
+    fn cell_array_ref_projects_cell_slices() {
+        use core::cell::Cell;
+        let mut data = [0u8, 1, 2, 3, 4, 5];
+        {
+            let cells = Cell::from_mut(&mut data[..]);
+            let a = cell_array_ref!(cells, 1, 2);
+            let b = cell_array_ref!(cells, 4, 2);
+            a[0].swap(&b[1]);
+            assert_eq!(cell_array_ref!(cells, 6, 0).len(), 0);
+            let plain: &[Cell<u8>] = cells.as_slice_of_cells();
+            array_ref!(plain, 0, 1)[0].set(9);
+        }
+        assert_eq!(data, [9, 5, 2, 3, 4, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cell_array_ref_panics_out_of_bounds() {
+        let mut data = [0u8; 4];
+        let cells = core::cell::Cell::from_mut(&mut data[..]);
+        let _ = cell_array_ref!(cells, 3, 2);
+    }
+
+    #[test]
+    fn try_array_ref_agrees_with_array_ref() {
+        fn f(mut data: Vec<u8>, offset: usize) -> bool {
+            let fits = offset <= data.len() && data.len() - offset >= 3;
+            let expected = if fits {
+                Some(*array_ref!(data, offset, 3))
+            } else {
+                None
+            };
+            if try_array_ref!(data, offset, 3).cloned() != expected {
+                return false;
+            }
+            if let Some(a) = try_array_mut_ref!(data, offset, 3) {
+                *a = [7; 3];
+            }
+            !fits || *array_ref!(data, offset, 3) == [7; 3]
+        }
+        quickcheck::quickcheck(f as fn(Vec<u8>, usize) -> bool);
+        assert!(try_array_ref!([0u8; 4], usize::MAX, 2).is_none());
+        assert_eq!(try_array_ref!([0u8; 4], 4, 0), Some(&[]));
+    }
+
+    #[test]
+    fn try_array_refs_checks_length() {
+        let mut data: Vec<u8> = (0..10).collect();
+        {
+            let (a, b, c) = try_array_refs!(&data[..], 2, 0, 3).unwrap();
+            assert_eq!((a, b, c), (&[0, 1], &[], &[2, 3, 4]));
+        }
+        let err = try_array_refs!(&data[..4], 2, 0, 3).unwrap_err();
+        assert_eq!((err.required(), err.available()), (5, 4));
+        {
+            let (a, b) = try_mut_array_refs!(&mut data, 4, 6).unwrap();
+            a[0] = 9;
+            b[5] = 9;
+        }
+        assert_eq!((data[0], data[9]), (9, 9));
+        assert!(try_mut_array_refs!(&mut data[1..], 4, 6).is_err());
+    }
+
+    #[test]
+    fn unchecked_refs_agree_with_checked() {
+        let mut data: Vec<u8> = (0..12).collect();
+        for offset in 0..=9 {
+            let expected = *array_ref!(data, offset, 3);
+            assert_eq!(unsafe { *array_ref_unchecked!(data, offset, 3) }, expected);
+            unsafe { array_mut_ref_unchecked!(data, offset, 3)[0] ^= 0x80 };
+            assert_eq!(data[offset], expected[0] ^ 0x80);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn unchecked_refs_are_checked_in_debug_builds() {
+        let data = [0u8; 4];
+        let _ = unsafe { array_ref_unchecked!(data, 3, 2) };
+    }
+
+    #[test]
+    fn end_refs_agree_with_start_refs() {
+        let mut data: Vec<u8> = (0..10).collect();
+        for offset in 0..=7 {
+            let start = data.len() - offset - 3;
+            assert_eq!(array_ref_end!(data, offset, 3), array_ref!(data, start, 3));
+            array_mut_ref_end!(data, offset, 3)[2] = 0xff;
+            assert_eq!(data[start + 2], 0xff);
+        }
+        assert_eq!(array_ref_end!(data, 10, 0), &[]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn end_refs_check_bounds() {
+        let data = [0u8; 10];
+        let _ = array_ref_end!(data, 8, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn end_refs_huge_offset_panics() {
+        let mut data = [0u8; 10];
+        let _ = array_mut_ref_end!(data, usize::MAX, 2);
+    }
+
+    #[test]
+    fn split_refs_cover_the_input() {
+        let mut data: Vec<u8> = (0..6).collect();
+        assert_eq!(split_array_ref!(data, 2), (&[0, 1], &[2, 3, 4, 5][..]));
+        assert_eq!(rsplit_array_ref!(data, 2), (&[0, 1, 2, 3][..], &[4, 5]));
+        assert_eq!(split_array_ref!(data, 6).1, &[]);
+        assert_eq!(rsplit_array_ref!(data, 0).0, &data[..]);
+        {
+            let (head, tail) = split_array_mut_ref!(data, 1);
+            head[0] = 9;
+            tail[0] = 9;
+        }
+        {
+            let (head, tail) = rsplit_array_mut_ref!(data, 1);
+            head[4] = 8;
+            tail[0] = 8;
+        }
+        assert_eq!(data, [9, 9, 2, 3, 8, 8]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_refs_check_bounds() {
+        let data = [0u8; 3];
+        let _ = rsplit_array_ref!(data, 4);
+    }
+
+    #[test]
+    fn read_int_matches_from_bytes() {
+        fn f(data: Vec<u8>, offset: usize) -> quickcheck::TestResult {
+            if offset > data.len() || data.len() - offset < 8 {
+                return quickcheck::TestResult::discard();
+            }
+            let bytes = *array_ref!(data, offset, 8);
+            quickcheck::TestResult::from_bool(
+                read_int!(data, offset, u64, le) == u64::from_le_bytes(bytes)
+                    && read_int!(data, offset, i32, be)
+                        == i32::from_be_bytes(*array_ref!(bytes, 0, 4))
+                    && read_int!(data, offset, f64, ne).to_bits()
+                        == f64::from_ne_bytes(bytes).to_bits(),
+            )
+        }
+        quickcheck::quickcheck(f as fn(Vec<u8>, usize) -> quickcheck::TestResult);
+    }
+
+    #[test]
+    fn write_int_round_trips_through_read_int() {
+        fn f(a: u64, b: i16, offset: u8) -> bool {
+            let mut data = vec![0u8; offset as usize + 10];
+            let offset = offset as usize;
+            write_int!(data, offset, a, be);
+            write_int!(data, offset + 8, b, le);
+            let c = f32::from_bits(a as u32);
+            let ok =
+                read_int!(data, offset, u64, be) == a && read_int!(data, offset + 8, i16, le) == b;
+            write_int!(data, offset + 1, c, ne);
+            ok && read_int!(data, offset + 1, f32, ne).to_bits() == c.to_bits()
+        }
+        quickcheck::quickcheck(f as fn(u64, i16, u8) -> bool);
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_int_checks_bounds() {
+        let mut data = [0u8; 7];
+        write_int!(data, 0, 1u64, be);
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_int_checks_bounds() {
+        let data = [0u8; 7];
+        let _ = read_int!(data, 0, u64, be);
+    }
+
+    #[test]
+    fn fields_matches_byte_reader() {
+        fn f(data: Vec<u8>) -> bool {
+            let parsed = fields!(data; a: u8, b: i16_be, c: [u8; 3], d: f32_le, rest: ..);
+            if data.len() < 10 {
+                return parsed.unwrap_err() == ::LengthError::new(10, data.len());
+            }
+            let parsed = parsed.unwrap();
+            let mut r = ::ByteReader::new(&data);
+            parsed.a == r.u8()
+                && parsed.b == r.i16_be()
+                && parsed.c == r.bytes::<3>()
+                && parsed.d.to_bits() == r.f32_le().to_bits()
+                && parsed.rest == r.rest()
+        }
+        quickcheck::quickcheck(f as fn(Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn fields_without_rest_ignores_trailing_bytes() {
+        let data = [1u8, 2, 3, 4, 5];
+        let parsed = fields!(data; x: u16_le, y: [u8; 2]).unwrap();
+        assert_eq!((parsed.x, parsed.y), (0x0201, &[3, 4]));
+    }
+
+    #[test]
+    fn array_refs_nested_groups_match_flat() {
+        let mut data: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        let (a, b, c, d, e) = array_refs!(&data, 1, 2, 3, 4, 2);
+        assert_eq!(
+            array_refs!(&data, (1, (2,)), 3, (4, 2)),
+            ((a, (b,)), c, (d, e))
+        );
+        const LEN: usize = 3;
+        let (x, y) = array_refs!(&data, (LEN * 2), (LEN + 3,));
+        assert_eq!((x, y), (array_ref!(data, 0, 6), (array_ref!(data, 6, 6),)));
+        {
+            let ((a, b), c) = mut_array_refs!(&mut data, (4, 4), 4);
+            a[0] = 9;
+            b[0] = 9;
+            c[0] = 9;
+        }
+        assert_eq!((data[0], data[4], data[8]), (9, 9, 9));
+    }
+
+    #[test]
+    fn array_copy_outlives_the_buffer() {
+        let copy = {
+            let data = vec![0u8, 1, 2, 3, 4];
+            array_copy!(data, 2, 3)
+        };
+        assert_eq!(copy, [2, 3, 4]);
+    }
+
+    #[test]
+    fn matrix_ref_is_row_major() {
+        let data: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+        let m = matrix_ref!(data, 1, 2, 3);
+        assert_eq!(m, &[[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(matrix_ref!(&data[..], 0, 4, 2)[3], [6, 7]);
+        assert_eq!(matrix_ref!(data, 8, 0, 3).len(), 0);
+    }
+
+    #[test]
+    fn matrix_mut_ref_writes_rows() {
+        let mut data = [0u8; 8];
+        {
+            let m = matrix_mut_ref!(data, 2, 3, 2);
+            m[0] = [1, 2];
+            m[2][1] = 3;
+        }
+        assert_eq!(data, [0, 0, 1, 2, 0, 0, 0, 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn matrix_ref_out_of_bounds_panics() {
+        let data = [0u8; 8];
+        let _ = matrix_ref!(data, 3, 2, 3);
+    }
+
+    #[test]
+    fn flatten_and_unflatten_round_trip() {
+        let mut blocks = [[1u8, 2, 3], [4, 5, 6]];
+        let flat: &[u8; 6] = flatten_ref!(&blocks);
+        assert_eq!(flat, &[1, 2, 3, 4, 5, 6]);
+        let rows: &[[u8; 2]; 3] = unflatten_ref!(flat);
+        assert_eq!(rows, &[[1, 2], [3, 4], [5, 6]]);
+        {
+            let flat: &mut [u8; 6] = flatten_mut_ref!(&mut blocks);
+            let rows: &mut [[u8; 2]; 3] = unflatten_mut_ref!(flat);
+            rows[1] = [0, 0];
+        }
+        assert_eq!(blocks, [[1, 2, 0], [0, 5, 6]]);
+        let empty: &[u8; 0] = flatten_ref!(&[[0u8; 0]; 5]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn split_even_pieces_cover_the_array() {
+        let mut data: [u8; 6] = [0, 1, 2, 3, 4, 5];
+        let [a, b, c]: &[[u8; 2]; 3] = split_even!(&data, 3);
+        assert_eq!((a, b, c), (&[0, 1], &[2, 3], &[4, 5]));
+        let halves: &[[u8; 3]; 2] = split_even!(&data, 1 + 1);
+        assert_eq!(halves[1], [3, 4, 5]);
+        {
+            let [x, y]: &mut [[u8; 3]; 2] = split_even_mut!(&mut data, 2);
+            x[0] = 9;
+            y[0] = 9;
+        }
+        assert_eq!(data, [9, 1, 2, 9, 4, 5]);
+    }
+
+    #[test]
+    fn concat_arrays_of_arrays_and_references() {
+        let a = [1u8, 2];
+        let b: &[u8; 3] = &[3, 4, 5];
+        let joined: [u8; 6] = concat_arrays!(a, b, [6]);
+        assert_eq!(joined, [1, 2, 3, 4, 5, 6]);
+        let single: [u8; 2] = concat_arrays!(&a);
+        assert_eq!(single, a);
+        let empty: [u8; 0] = concat_arrays!([0u8; 0], []);
+        assert_eq!(empty, []);
+        let many: [u8; 16] = concat_arrays!(
+            [0],
+            [1],
+            [2],
+            [3],
+            [4],
+            [5],
+            [6],
+            [7],
+            [8],
+            [9],
+            [10],
+            [11],
+            [12],
+            [13],
+            [14],
+            [15],
+        );
+        assert_eq!(many[15], 15);
+    }
+
+    #[test]
+    fn array_refs_skip_placeholders() {
+        let mut data: [u8; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        let (a, b, c, d, e) = array_refs!(&data, 1, 2, 3, 4, 2);
+        assert_eq!(array_refs!(&data, 1, _ 2, 3, _ 4, 2), (a, c, e));
+        assert_eq!(array_refs!(&data, _ 1, 2, 3, 4, _ 2), (b, c, d));
+        assert_eq!(array_refs!(&data, (1, _ 2), (_ 3, 4), 2), ((a,), (d,), e));
+        assert_eq!(array_refs!(&data, (_ 6,), 4, _ 2), ((), d));
+        {
+            let (x, y) = mut_array_refs!(&mut data, _ 4, 4, _ 2, 2);
+            x[0] = 9;
+            y[0] = 9;
+        }
+        assert_eq!((data[4], data[10]), (9, 9));
+    }
+
+    #[test]
+    fn array_refs_remainder_in_the_middle() {
+        let mut data: [u8; 9] = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(
+            array_refs!(&data[..], 2, .., 4),
+            array_refs!(&data[..], 2; .. ; 4)
+        );
+        let (body, tail) = array_refs!(&data[..], .., 3);
+        assert_eq!((body, tail), (&data[..6], &[6, 7, 8]));
+        let (head, body) = array_refs!(&data[..], 3, ..);
+        assert_eq!((head, body), (&[0, 1, 2], &data[3..]));
+        let (head, body, tail) = array_refs!(&data[..5], 2, .., 3);
+        assert_eq!((head, body, tail), (&[0, 1], &[][..], &[2, 3, 4]));
+        {
+            let (head, body, tail) = mut_array_refs!(&mut data[..], 1, .., 1);
+            head[0] = 9;
+            body[0] = 9;
+            tail[0] = 9;
+        }
+        assert_eq!((data[0], data[1], data[8]), (9, 9, 9));
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_refs_remainder_too_short_panics() {
+        let data = [0u8; 4];
+        let _ = array_refs!(&data[..], 2, .., 3);
+    }
+
+    #[test]
+    fn slice_refs_return_the_remainder() {
+        let mut data = vec![0u8, 1, 2, 3, 4, 5, 6];
+        let (a, b, rest) = slice_refs!(&data, 2, 3);
+        assert_eq!((a, b, rest), (&[0, 1], &[2, 3, 4], &[5, 6][..]));
+        let (a, rest) = slice_refs!(&data[..2], 2);
+        assert_eq!((a, rest), (&[0, 1], &[][..]));
+        {
+            let (a, rest) = mut_slice_refs!(&mut data, 4);
+            a[0] = 9;
+            rest[0] = 9;
+        }
+        assert_eq!((data[0], data[4]), (9, 9));
+    }
+
+    #[test]
+    fn ends_refs_split_head_body_and_tail() {
+        let mut data = [0u8, 1, 2, 3, 4, 5, 6];
+        let (head, body, tail) = ends_refs!(&data[..], 2, 3);
+        assert_eq!((head, body, tail), (&[0, 1], &[2, 3][..], &[4, 5, 6]));
+        let (head, body, tail) = ends_refs!(&data[..5], 2, 3);
+        assert_eq!((head, body, tail), (&[0, 1], &[][..], &[2, 3, 4]));
+        {
+            let (head, body, tail) = mut_ends_refs!(&mut data[..], 1, 1);
+            head[0] = 9;
+            body.iter_mut().for_each(|x| *x = 8);
+            tail[0] = 9;
+        }
+        assert_eq!(data, [9, 8, 8, 8, 8, 8, 9]);
+    }
+
+    #[test]
+    fn disjoint_mut_refs_at_any_offsets() {
+        let mut data = [0u8; 10];
+        let second = 1;
+        {
+            let (a, b, c) = disjoint_mut_refs!(&mut data, (6, 4), (second, 2), (3, 3));
+            *a = [1; 4];
+            *b = [2; 2];
+            *c = [3; 3];
+        }
+        assert_eq!(data, [0, 2, 2, 3, 3, 3, 1, 1, 1, 1]);
+        let (empty, all) = disjoint_mut_refs!(&mut data, (4, 0), (0, 10));
+        assert_eq!((empty.len(), all.len()), (0, 10));
+    }
+
+    #[test]
+    fn swap_arrays_within_one_slice() {
+        let mut data = [0u8, 1, 2, 3, 4, 5];
+        swap_arrays!(&mut data, 4, 0, 2);
+        assert_eq!(data, [4, 5, 2, 3, 0, 1]);
+        swap_arrays!(&mut data[..], 2, 3, 1);
+        assert_eq!(data, [4, 5, 3, 2, 0, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "overlaps")]
+    fn swap_arrays_overlap_panics() {
+        let mut data = [0u8; 6];
+        swap_arrays!(&mut data, 1, 2, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "overlaps")]
+    fn disjoint_mut_refs_overlap_panics() {
+        let mut data = [0u8; 10];
+        let _ = disjoint_mut_refs!(&mut data, (0, 4), (3, 2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn disjoint_mut_refs_out_of_bounds_panics() {
+        let mut data = [0u8; 10];
+        let _ = disjoint_mut_refs!(&mut data, (0, 4), (8, 4));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ends_refs_too_short_panics() {
+        let data = [0u8; 4];
+        let _ = ends_refs!(&data[..], 2, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_refs_too_short_panics() {
+        let data = [0u8; 4];
+        let _ = slice_refs!(&data[..], 2, 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_ref_huge_offset_panics() {
+        let data = [0u8; 4];
+        let _ = array_ref!(data, usize::MAX, 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn array_mut_ref_huge_offset_panics() {
+        let mut data = [0u8; 4];
+        let _ = array_mut_ref!(data, usize::MAX - 1, 2);
+    }
+
+    #[test]
+    fn lengths_saturate_at_usize_max() {
+        let data = [0u8; 16];
+        let err = ::split_lengths(&data, &[usize::MAX - 1, 2]).unwrap_err();
+        assert_eq!(err.required(), usize::MAX);
+        assert!(::ArrayWindow::<u8, 4>::new(&mut [0u8; 8], usize::MAX - 2).is_err());
+    }
 } // mod test
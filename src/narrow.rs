@@ -0,0 +1,157 @@
+//! Array references at `u8` and `u16` offsets, which need at most one
+//! runtime bounds check.
+
+use bounds::{sub_slice, sub_slice_mut};
+
+struct AssertAnyU8Fits<const M: usize, const N: usize>;
+
+impl<const M: usize, const N: usize> AssertAnyU8Fits<M, N> {
+    const OK: () = assert!(
+        N <= M && M - N >= u8::MAX as usize,
+        "every u8 offset must leave room for the sub-array"
+    );
+}
+
+/// You can use `array_at_u8` to take an `N`-element array reference
+/// at a `u8` offset into an array that is long enough for every
+/// possible offset, which is checked at compile time.  There is then
+/// no bounds check or overflow check left at runtime, which makes this
+/// the cheapest way to follow a one-byte offset field on small
+/// microcontrollers.
+///
+/// ```
+/// use arrayref::array_at_u8;
+///
+/// let mut page = [0u8; 260];
+/// page[0] = 200;
+/// page[200..204].copy_from_slice(b"root");
+/// let name: &[u8; 4] = array_at_u8(&page, page[0]);
+/// assert_eq!(name, b"root");
+/// ```
+///
+/// ```compile_fail
+/// use arrayref::array_at_u8;
+///
+/// let page = [0u8; 256];
+/// let _: &[u8; 4] = array_at_u8(&page, 0);
+/// ```
+#[inline]
+pub fn array_at_u8<T, const M: usize, const N: usize>(array: &[T; M], offset: u8) -> &[T; N] {
+    let () = AssertAnyU8Fits::<M, N>::OK;
+    unsafe { &*(array.as_ptr().add(offset as usize) as *const [T; N]) }
+}
+
+/// The mutable version of `array_at_u8`.
+#[inline]
+pub fn array_at_u8_mut<T, const M: usize, const N: usize>(
+    array: &mut [T; M],
+    offset: u8,
+) -> &mut [T; N] {
+    let () = AssertAnyU8Fits::<M, N>::OK;
+    unsafe { &mut *(array.as_mut_ptr().add(offset as usize) as *mut [T; N]) }
+}
+
+struct AssertFits<const M: usize, const N: usize>;
+
+impl<const M: usize, const N: usize> AssertFits<M, N> {
+    const OK: () = assert!(N <= M, "the sub-array must fit in the array");
+}
+
+/// You can use `array_at_u16` to take an `N`-element array reference
+/// at a `u16` offset into an `M`-element array.  Since `N <= M` is
+/// checked at compile time, the only runtime check left is a single
+/// comparison of the offset against the constant `M - N`, and widening
+/// a `u16` offset cannot overflow even where `usize` is 16 bits, as on
+/// AVR and MSP430.
+///
+/// **Panics** if the sub-array would be out of bounds, as
+/// `array_ref!` does.
+///
+/// ```
+/// use arrayref::array_at_u16;
+///
+/// let mut flash = [0u8; 1024];
+/// flash[600..602].copy_from_slice(&[0xbe, 0xef]);
+/// let word: &[u8; 2] = array_at_u16(&flash, 600);
+/// assert_eq!(word, &[0xbe, 0xef]);
+/// ```
+///
+/// ```compile_fail
+/// use arrayref::array_at_u16;
+///
+/// let flash = [0u8; 2];
+/// let _: &[u8; 4] = array_at_u16(&flash, 0);
+/// ```
+#[inline]
+pub fn array_at_u16<T, const M: usize, const N: usize>(array: &[T; M], offset: u16) -> &[T; N] {
+    let () = AssertFits::<M, N>::OK;
+    let offset = offset as usize;
+    if offset > M - N {
+        // reports the failure, as `array_ref!` does
+        sub_slice(array, offset, N);
+    }
+    unsafe { &*(array.as_ptr().add(offset) as *const [T; N]) }
+}
+
+/// The mutable version of `array_at_u16`.
+#[inline]
+pub fn array_at_u16_mut<T, const M: usize, const N: usize>(
+    array: &mut [T; M],
+    offset: u16,
+) -> &mut [T; N] {
+    let () = AssertFits::<M, N>::OK;
+    let offset = offset as usize;
+    if offset > M - N {
+        sub_slice_mut(array, offset, N);
+    }
+    unsafe { &mut *(array.as_mut_ptr().add(offset) as *mut [T; N]) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matches_array_ref_at_every_offset() {
+        let mut data = [0u16; 258];
+        for (i, x) in data.iter_mut().enumerate() {
+            *x = i as u16;
+        }
+        for offset in 0..=u8::MAX {
+            let a: &[u16; 3] = array_at_u8(&data, offset);
+            assert_eq!(a, array_ref!(data, offset as usize, 3));
+        }
+        *array_at_u8_mut::<_, 258, 3>(&mut data, 255) = [0; 3];
+        assert_eq!(data[254..], [254, 0, 0, 0]);
+    }
+
+    #[test]
+    fn u16_offsets_match_array_ref() {
+        let mut data = [0u16; 300];
+        for (i, x) in data.iter_mut().enumerate() {
+            *x = i as u16;
+        }
+        for offset in 0..=297u16 {
+            let a: &[u16; 3] = array_at_u16(&data, offset);
+            assert_eq!(a, array_ref!(data, offset as usize, 3));
+        }
+        *array_at_u16_mut::<_, 300, 2>(&mut data, 298) = [0; 2];
+        assert_eq!(data[297..], [297, 0, 0]);
+        let all: &[u16; 300] = array_at_u16(&data, 0);
+        assert_eq!(all.len(), 300);
+    }
+
+    #[test]
+    #[should_panic]
+    fn u16_offsets_are_checked() {
+        let data = [0u8; 300];
+        let _: &[u8; 4] = array_at_u16(&data, 297);
+    }
+
+    #[test]
+    #[should_panic]
+    fn u16_offset_past_the_end_is_checked() {
+        let data = [0u8; 8];
+        let _: &[u8; 0] = array_at_u16(&data, u16::MAX);
+    }
+}
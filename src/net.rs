@@ -0,0 +1,131 @@
+//! Conversions between fixed-size windows and `core::net` addresses.
+//!
+//! Addresses are always stored in network byte order, as every
+//! protocol does; ports take a `ByteOrder` because some formats (such
+//! as pcap headers or Windows structures) store them little-endian.
+
+use core::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+
+use endian::{BigEndian, ByteOrder};
+
+/// Reads an IPv4 address.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// use arrayref::read_ipv4;
+/// use std::net::Ipv4Addr;
+///
+/// # fn main() {
+/// let ip_header = [
+///     0x45, 0x00, 0x00, 0x54, 0x00, 0x00, 0x40, 0x00, 0x40, 0x01, 0x00, 0x00,
+///     192, 168, 1, 10, 8, 8, 8, 8,
+/// ];
+/// let (_, source, destination) = array_refs!(&ip_header, 12, 4, 4);
+/// assert_eq!(read_ipv4(source), Ipv4Addr::new(192, 168, 1, 10));
+/// assert_eq!(read_ipv4(destination), Ipv4Addr::new(8, 8, 8, 8));
+/// # }
+/// ```
+#[inline]
+pub fn read_ipv4(bytes: &[u8; 4]) -> Ipv4Addr {
+    Ipv4Addr::from(*bytes)
+}
+
+/// Writes an IPv4 address.
+#[inline]
+pub fn write_ipv4(bytes: &mut [u8; 4], addr: Ipv4Addr) {
+    *bytes = addr.octets();
+}
+
+/// Reads an IPv6 address.
+#[inline]
+pub fn read_ipv6(bytes: &[u8; 16]) -> Ipv6Addr {
+    Ipv6Addr::from(*bytes)
+}
+
+/// Writes an IPv6 address.
+#[inline]
+pub fn write_ipv6(bytes: &mut [u8; 16], addr: Ipv6Addr) {
+    *bytes = addr.octets();
+}
+
+/// Reads a port number in byte order `E`.
+#[inline]
+pub fn read_port<E: ByteOrder>(bytes: &[u8; 2]) -> u16 {
+    E::read_u16(bytes)
+}
+
+/// Writes a port number in byte order `E`.
+#[inline]
+pub fn write_port<E: ByteOrder>(bytes: &mut [u8; 2], port: u16) {
+    E::write_u16(bytes, port)
+}
+
+/// Reads an IPv4 address followed by a big-endian port, as in SOCKS
+/// and many peer lists.
+///
+/// ```
+/// use arrayref::read_socket_v4;
+///
+/// let peer = [127, 0, 0, 1, 0x1f, 0x90];
+/// assert_eq!(read_socket_v4(&peer).to_string(), "127.0.0.1:8080");
+/// ```
+#[inline]
+pub fn read_socket_v4(bytes: &[u8; 6]) -> SocketAddrV4 {
+    let (addr, port) = array_refs!(bytes, 4, 2);
+    SocketAddrV4::new(read_ipv4(addr), read_port::<BigEndian>(port))
+}
+
+/// Writes an IPv4 address followed by a big-endian port.
+#[inline]
+pub fn write_socket_v4(bytes: &mut [u8; 6], socket: SocketAddrV4) {
+    let (addr, port) = mut_array_refs!(bytes, 4, 2);
+    write_ipv4(addr, *socket.ip());
+    write_port::<BigEndian>(port, socket.port());
+}
+
+/// Reads an IPv6 address followed by a big-endian port.  The flow
+/// info and scope id are set to zero.
+#[inline]
+pub fn read_socket_v6(bytes: &[u8; 18]) -> SocketAddrV6 {
+    let (addr, port) = array_refs!(bytes, 16, 2);
+    SocketAddrV6::new(read_ipv6(addr), read_port::<BigEndian>(port), 0, 0)
+}
+
+/// Writes an IPv6 address followed by a big-endian port.  The flow
+/// info and scope id are not stored.
+#[inline]
+pub fn write_socket_v6(bytes: &mut [u8; 18], socket: SocketAddrV6) {
+    let (addr, port) = mut_array_refs!(bytes, 16, 2);
+    write_ipv6(addr, *socket.ip());
+    write_port::<BigEndian>(port, socket.port());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use endian::LittleEndian;
+
+    #[test]
+    fn sockets_round_trip() {
+        let v4 = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 443);
+        let mut buf4 = [0; 6];
+        write_socket_v4(&mut buf4, v4);
+        assert_eq!(buf4, [10, 0, 0, 1, 1, 0xbb]);
+        assert_eq!(read_socket_v4(&buf4), v4);
+
+        let v6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 53, 0, 0);
+        let mut buf6 = [0; 18];
+        write_socket_v6(&mut buf6, v6);
+        assert_eq!(buf6[15..], [1, 0, 53]);
+        assert_eq!(read_socket_v6(&buf6), v6);
+    }
+
+    #[test]
+    fn ports_respect_byte_order() {
+        let mut port = [0; 2];
+        write_port::<LittleEndian>(&mut port, 8080);
+        assert_eq!(port, [0x90, 0x1f]);
+        assert_eq!(read_port::<BigEndian>(&port), 0x901f);
+    }
+}
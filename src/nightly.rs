@@ -0,0 +1,120 @@
+//! Type-level splitting of arrays, using the unstable
+//! `generic_const_exprs` feature.
+
+/// `SplitPrefix<N>` is implemented for every array `[T; M]` with
+/// `N <= M`, splitting it into an array reference to its first `N`
+/// elements and one to the remaining `M - N`.  Unlike `array_refs!`,
+/// this can be used from generic code that does not know `M`.
+///
+/// Only available with the `nightly` feature.
+///
+/// ```
+/// #![feature(generic_const_exprs)]
+/// # #![allow(incomplete_features)]
+/// use arrayref::SplitPrefix;
+///
+/// fn tag_and_body<const M: usize>(msg: &[u8; M]) -> (u8, &[u8; M - 1])
+/// where
+///     [(); M - 1]:,
+/// {
+///     let (tag, body) = SplitPrefix::<1>::split_prefix(msg);
+///     (tag[0], body)
+/// }
+///
+/// assert_eq!(tag_and_body(&[7, 1, 2]), (7, &[1, 2]));
+/// ```
+pub trait SplitPrefix<const N: usize> {
+    /// The element type of the array.
+    type Elem;
+    /// The type of the rest of the array after the prefix.
+    type Rest: ?Sized;
+    /// Splits off the first `N` elements.
+    fn split_prefix(&self) -> (&[Self::Elem; N], &Self::Rest);
+    /// Splits off the first `N` elements, mutably.
+    fn split_prefix_mut(&mut self) -> (&mut [Self::Elem; N], &mut Self::Rest);
+}
+
+/// `SplitSuffix<N>` is implemented for every array `[T; M]` with
+/// `N <= M`, splitting it into an array reference to its first
+/// `M - N` elements and one to its last `N`.
+///
+/// Only available with the `nightly` feature.
+pub trait SplitSuffix<const N: usize> {
+    /// The element type of the array.
+    type Elem;
+    /// The type of the start of the array before the suffix.
+    type Rest: ?Sized;
+    /// Splits off the last `N` elements.
+    fn split_suffix(&self) -> (&Self::Rest, &[Self::Elem; N]);
+    /// Splits off the last `N` elements, mutably.
+    fn split_suffix_mut(&mut self) -> (&mut Self::Rest, &mut [Self::Elem; N]);
+}
+
+impl<T, const M: usize, const N: usize> SplitPrefix<N> for [T; M]
+where
+    [(); M - N]:,
+{
+    type Elem = T;
+    type Rest = [T; M - N];
+
+    fn split_prefix(&self) -> (&[T; N], &[T; M - N]) {
+        let p = self.as_ptr();
+        unsafe { (&*(p as *const [T; N]), &*(p.add(N) as *const [T; M - N])) }
+    }
+
+    fn split_prefix_mut(&mut self) -> (&mut [T; N], &mut [T; M - N]) {
+        let p = self.as_mut_ptr();
+        unsafe {
+            (
+                &mut *(p as *mut [T; N]),
+                &mut *(p.add(N) as *mut [T; M - N]),
+            )
+        }
+    }
+}
+
+impl<T, const M: usize, const N: usize> SplitSuffix<N> for [T; M]
+where
+    [(); M - N]:,
+{
+    type Elem = T;
+    type Rest = [T; M - N];
+
+    fn split_suffix(&self) -> (&[T; M - N], &[T; N]) {
+        let p = self.as_ptr();
+        unsafe {
+            (
+                &*(p as *const [T; M - N]),
+                &*(p.add(M - N) as *const [T; N]),
+            )
+        }
+    }
+
+    fn split_suffix_mut(&mut self) -> (&mut [T; M - N], &mut [T; N]) {
+        let p = self.as_mut_ptr();
+        unsafe {
+            (
+                &mut *(p as *mut [T; M - N]),
+                &mut *(p.add(M - N) as *mut [T; N]),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn splits_match_array_refs() {
+        let mut data = [1u8, 2, 3, 4, 5];
+        let (a, b) = SplitPrefix::<2>::split_prefix(&data);
+        assert_eq!((a, b), array_refs!(&data, 2, 3));
+        let (a, b) = SplitSuffix::<1>::split_suffix(&data);
+        assert_eq!((a, b), array_refs!(&data, 4, 1));
+        let (a, b) = SplitPrefix::<5>::split_prefix_mut(&mut data);
+        a[0] = 9;
+        assert_eq!(b, &[]);
+        assert_eq!(data, [9, 2, 3, 4, 5]);
+    }
+}
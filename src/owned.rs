@@ -0,0 +1,152 @@
+//! Combinators on owned fixed-size arrays.
+
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+use builder::ArrayBuilder;
+
+/// You can use `zip_arrays` to pair up the elements of two arrays of
+/// the same length.  The elements are moved, so they need not be
+/// `Copy` or `Default`.
+///
+/// ```
+/// use arrayref::zip_arrays;
+///
+/// let names = [String::from("x"), String::from("y")];
+/// assert_eq!(zip_arrays(names, [1, 2]), [(String::from("x"), 1), (String::from("y"), 2)]);
+/// ```
+pub fn zip_arrays<T, U, const N: usize>(a: [T; N], b: [U; N]) -> [(T, U); N] {
+    let mut a = IntoIterator::into_iter(a);
+    let mut b = IntoIterator::into_iter(b);
+    core::array::from_fn(|_| (a.next().unwrap(), b.next().unwrap()))
+}
+
+/// You can use `unzip_arrays` to split an array of pairs into an
+/// array of first elements and an array of second elements.
+///
+/// ```
+/// use arrayref::unzip_arrays;
+///
+/// assert_eq!(unzip_arrays([(1, 'a'), (2, 'b')]), ([1, 2], ['a', 'b']));
+/// ```
+pub fn unzip_arrays<T, U, const N: usize>(pairs: [(T, U); N]) -> ([T; N], [U; N]) {
+    let mut firsts = ArrayBuilder::new();
+    let mut seconds = ArrayBuilder::new();
+    for (t, u) in IntoIterator::into_iter(pairs) {
+        firsts.push(t).unwrap();
+        seconds.push(u).unwrap();
+    }
+    (firsts.finish().unwrap(), seconds.finish().unwrap())
+}
+
+/// You can use `map_with_index` to transform each element of an array
+/// by a function that is also given the element's index.  The
+/// elements are moved into `f` in order.
+///
+/// ```
+/// use arrayref::map_with_index;
+///
+/// let weighted = map_with_index([1.0, 1.0, 1.0], |i, x| x * i as f64);
+/// assert_eq!(weighted, [0.0, 1.0, 2.0]);
+/// ```
+pub fn map_with_index<T, U, const N: usize>(
+    array: [T; N],
+    mut f: impl FnMut(usize, T) -> U,
+) -> [U; N] {
+    let mut items = IntoIterator::into_iter(array).enumerate();
+    core::array::from_fn(|_| {
+        let (i, item) = items.next().unwrap();
+        f(i, item)
+    })
+}
+
+struct AssertSplit<const A: usize, const B: usize, const N: usize>;
+
+impl<const A: usize, const B: usize, const N: usize> AssertSplit<A, B, N> {
+    const OK: () = assert!(
+        match A.checked_add(B) {
+            Some(total) => total == N,
+            None => false,
+        },
+        "the two halves must have exactly N elements between them"
+    );
+}
+
+/// You can use `split_array` to split an owned array `[T; N]` into its
+/// first `A` elements and its last `B`, moving the elements rather
+/// than cloning them.  The lengths are usually inferred from how the
+/// halves are used, and that `A + B` is `N` is checked at compile time.
+///
+/// ```
+/// use arrayref::split_array;
+///
+/// struct Keys {
+///     encrypt: [u8; 32],
+///     mac: [u8; 32],
+/// }
+///
+/// let derived = [7u8; 64];
+/// let (encrypt, mac) = split_array(derived);
+/// let keys = Keys { encrypt, mac };
+/// assert_eq!(keys.encrypt.len() + keys.mac.len(), 64);
+/// ```
+///
+/// ```compile_fail
+/// use arrayref::split_array;
+///
+/// let (a, b): ([u8; 32], [u8; 16]) = split_array([0u8; 64]);
+/// ```
+pub fn split_array<T, const A: usize, const B: usize, const N: usize>(
+    array: [T; N],
+) -> ([T; A], [T; B]) {
+    let () = AssertSplit::<A, B, N>::OK;
+    let array = ManuallyDrop::new(array);
+    let p = array.as_ptr();
+    // The elements are moved out exactly once, into one half or the
+    // other, and `array` is not dropped.
+    unsafe {
+        (
+            ptr::read(p as *const [T; A]),
+            ptr::read(p.add(A) as *const [T; B]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::string::{String, ToString};
+
+    #[test]
+    fn zip_then_unzip_round_trips() {
+        let a = [String::from("a"), String::from("b"), String::from("c")];
+        let b = [vec![1], vec![], vec![2, 3]];
+        let (a2, b2) = unzip_arrays(zip_arrays(a.clone(), b.clone()));
+        assert_eq!(a, a2);
+        assert_eq!(b, b2);
+    }
+
+    #[test]
+    fn split_array_moves_every_element() {
+        let words = [String::from("a"), String::from("b"), String::from("c")];
+        let (head, tail): ([String; 1], [String; 2]) = split_array(words);
+        assert_eq!(
+            (head, tail),
+            (["a".to_string()], ["b".to_string(), "c".to_string()])
+        );
+        let (empty, all): ([u8; 0], [u8; 2]) = split_array([1, 2]);
+        assert_eq!((empty, all), ([], [1, 2]));
+    }
+
+    #[test]
+    fn map_with_index_moves_in_order() {
+        let words = [String::from("x"), String::from("y")];
+        let mut seen = vec![];
+        let out = map_with_index(words, |i, w| {
+            seen.push(i);
+            w + &i.to_string()
+        });
+        assert_eq!(out, ["x0", "y1"]);
+        assert_eq!(seen, [0, 1]);
+    }
+}
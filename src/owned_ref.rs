@@ -0,0 +1,170 @@
+//! Fixed-size windows into owned or shared buffers, available with
+//! the `alloc` feature.
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::ops::{Deref, DerefMut};
+
+use error::LengthError;
+
+/// An `OwnedArrayRef` owns a heap buffer and dereferences to a fixed
+/// window of `N` elements within it.  A function can return one to
+/// hand back a parsed header view together with the bytes it points
+/// into, without tying the result to the lifetime of a borrow.
+///
+/// The window is checked once, when the `OwnedArrayRef` is created;
+/// it stores the buffer and the offset rather than a reference, so it
+/// is not self-referential.
+///
+/// ```
+/// use arrayref::OwnedArrayRef;
+///
+/// fn read_record() -> OwnedArrayRef<u8, 4> {
+///     let bytes = vec![0xca, 0xfe, 0, 0, 0, 42, 1, 2, 3];
+///     OwnedArrayRef::new(bytes, 2).unwrap()
+/// }
+///
+/// let length = read_record();
+/// assert_eq!(u32::from_be_bytes(*length), 42);
+/// assert_eq!(length.buffer().len(), 9);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedArrayRef<T, const N: usize> {
+    buffer: Box<[T]>,
+    offset: usize,
+}
+
+impl<T, const N: usize> OwnedArrayRef<T, N> {
+    /// Takes ownership of `buffer` and exposes
+    /// `buffer[offset..offset + N]`, or returns a `LengthError` (and
+    /// drops the buffer) if the window does not fit.
+    pub fn new<B: Into<Box<[T]>>>(buffer: B, offset: usize) -> Result<Self, LengthError> {
+        let buffer = buffer.into();
+        let required = offset.saturating_add(N);
+        if required > buffer.len() {
+            return Err(LengthError::new(required, buffer.len()));
+        }
+        Ok(OwnedArrayRef { buffer, offset })
+    }
+
+    /// The offset of the window within the buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The whole buffer, including the window.
+    pub fn buffer(&self) -> &[T] {
+        &self.buffer
+    }
+
+    /// Gives up the window, returning the buffer.
+    pub fn into_buffer(self) -> Box<[T]> {
+        self.buffer
+    }
+}
+
+// `new` checked that the window fits, and the buffer is never
+// replaced or resized afterwards, so the window needs no further check.
+
+impl<T, const N: usize> Deref for OwnedArrayRef<T, N> {
+    type Target = [T; N];
+    fn deref(&self) -> &[T; N] {
+        unsafe { &*(self.buffer.as_ptr().add(self.offset) as *const [T; N]) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for OwnedArrayRef<T, N> {
+    fn deref_mut(&mut self) -> &mut [T; N] {
+        unsafe { &mut *(self.buffer.as_mut_ptr().add(self.offset) as *mut [T; N]) }
+    }
+}
+
+/// An `ArcArrayRef` is a fixed window of `N` elements into a shared
+/// `Arc<[T]>` buffer.  Cloning one just bumps the reference count, so
+/// parsed fields can be handed around and outlive the parsing code
+/// without copying, much as `bytes::Bytes` does for slices.
+///
+/// ```
+/// use arrayref::ArcArrayRef;
+/// use std::sync::Arc;
+///
+/// let packet: Arc<[u8]> = Arc::from(&[0u8, 1, 2, 3, 4, 5, 6, 7][..]);
+/// let header = ArcArrayRef::<u8, 2>::new(packet, 0).unwrap();
+/// let body = header.window::<6>(2).unwrap();
+/// let kept = body.clone();
+/// drop(header);
+/// assert_eq!(*kept, [2, 3, 4, 5, 6, 7]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ArcArrayRef<T, const N: usize> {
+    buffer: Arc<[T]>,
+    offset: usize,
+}
+
+impl<T, const N: usize> ArcArrayRef<T, N> {
+    /// Shares `buffer` and exposes `buffer[offset..offset + N]`, or
+    /// returns a `LengthError` if the window does not fit.
+    pub fn new<B: Into<Arc<[T]>>>(buffer: B, offset: usize) -> Result<Self, LengthError> {
+        let buffer = buffer.into();
+        let required = offset.saturating_add(N);
+        if required > buffer.len() {
+            return Err(LengthError::new(required, buffer.len()));
+        }
+        Ok(ArcArrayRef { buffer, offset })
+    }
+
+    /// Another window of `M` elements into the same buffer, at
+    /// `offset` from the start of the buffer (not of this window).
+    pub fn window<const M: usize>(&self, offset: usize) -> Result<ArcArrayRef<T, M>, LengthError> {
+        ArcArrayRef::new(self.buffer.clone(), offset)
+    }
+
+    /// The offset of the window within the buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The shared buffer.
+    pub fn buffer(&self) -> &Arc<[T]> {
+        &self.buffer
+    }
+
+    /// Gives up the window, returning the shared buffer.
+    pub fn into_buffer(self) -> Arc<[T]> {
+        self.buffer
+    }
+}
+
+impl<T, const N: usize> Deref for ArcArrayRef<T, N> {
+    type Target = [T; N];
+    fn deref(&self) -> &[T; N] {
+        // as for `OwnedArrayRef`, `new` checked the window.
+        unsafe { &*(self.buffer.as_ptr().add(self.offset) as *const [T; N]) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn window_is_validated_once() {
+        let mut w = OwnedArrayRef::<i32, 2>::new(vec![1, 2, 3], 1).unwrap();
+        assert_eq!(*w, [2, 3]);
+        w[1] = 9;
+        assert_eq!(w.offset(), 1);
+        assert_eq!(&*w.into_buffer(), &[1, 2, 9]);
+        let err = OwnedArrayRef::<i32, 2>::new(vec![1, 2, 3], 2).unwrap_err();
+        assert_eq!(err.missing(), 1);
+    }
+
+    #[test]
+    fn arc_windows_share_the_buffer() {
+        let a = ArcArrayRef::<u8, 3>::new(vec![1, 2, 3, 4], 1).unwrap();
+        let b = a.window::<1>(0).unwrap();
+        assert_eq!((*a, *b), ([2, 3, 4], [1]));
+        assert!(Arc::ptr_eq(a.buffer(), b.buffer()));
+        assert_eq!(Arc::strong_count(b.buffer()), 2);
+        assert_eq!(a.window::<2>(3).unwrap_err().missing(), 1);
+    }
+}
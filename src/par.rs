@@ -0,0 +1,60 @@
+//! Parallel iterators over fixed-size chunks, available with the
+//! `rayon` feature.
+
+use rayon::prelude::*;
+use rayon::slice::{Iter, IterMut};
+
+use chunks::{as_chunks, as_chunks_mut};
+
+/// A parallel iterator over the non-overlapping `N`-element chunks of
+/// `slice`, as array references.  It is an `IndexedParallelIterator`,
+/// so it can be zipped, enumerated and so on like any other.  Trailing
+/// elements that do not make up a whole chunk are not yielded; use
+/// `as_chunks` to get at them.
+///
+/// **Panics** if `N` is zero.
+///
+/// ```
+/// extern crate arrayref;
+/// extern crate rayon;
+///
+/// use arrayref::par_array_chunks;
+/// use rayon::prelude::*;
+///
+/// # fn main() {
+/// let data = vec![1u8; 4096 + 3];
+/// let sums: Vec<u32> = par_array_chunks::<_, 512>(&data)
+///     .map(|block| block.iter().map(|&b| b as u32).sum())
+///     .collect();
+/// assert_eq!(sums, [512; 8]);
+/// # }
+/// ```
+pub fn par_array_chunks<T: Sync, const N: usize>(slice: &[T]) -> Iter<'_, [T; N]> {
+    as_chunks(slice).0.par_iter()
+}
+
+/// A parallel iterator over the non-overlapping `N`-element chunks of
+/// `slice`, as mutable array references.  Trailing elements that do
+/// not make up a whole chunk are not yielded; use `as_chunks_mut` to
+/// get at them.
+///
+/// **Panics** if `N` is zero.
+///
+/// ```
+/// extern crate arrayref;
+/// extern crate rayon;
+///
+/// use arrayref::par_array_chunks_mut;
+/// use rayon::prelude::*;
+///
+/// # fn main() {
+/// let mut data = vec![0u32; 1024];
+/// par_array_chunks_mut::<_, 256>(&mut data)
+///     .enumerate()
+///     .for_each(|(i, block)| block.fill(i as u32));
+/// assert_eq!(data[3 * 256], 3);
+/// # }
+/// ```
+pub fn par_array_chunks_mut<T: Send, const N: usize>(slice: &mut [T]) -> IterMut<'_, [T; N]> {
+    as_chunks_mut(slice).0.par_iter_mut()
+}
@@ -0,0 +1,100 @@
+//! Array references into pinned slices.
+//!
+//! Pinning is structural for the elements of a slice: a `Pin<&mut [T]>`
+//! promises that no element moves until it is dropped, and the only way
+//! to move one out is through an `&mut [T]`, which `Pin` does not hand
+//! out unless `T: Unpin`.  A sub-array is the same elements at the same
+//! addresses, so pinning them again as a `Pin<&mut [T; N]>` keeps that
+//! promise as long as the unpinned `&mut [T]` used to find them never
+//! escapes, which it does not here.
+
+use bounds::{sub_slice, sub_slice_mut};
+use core::pin::Pin;
+
+/// You can use `pin_array_ref` to take a pinned array reference to
+/// `N` elements of a pinned slice, starting at `offset`.
+///
+/// **Panics** if the array would be out of bounds, as `array_ref!`
+/// does.
+///
+/// ```
+/// use arrayref::pin_array_ref;
+/// use std::pin::Pin;
+///
+/// let data = [1u8, 2, 3, 4];
+/// let pinned = Pin::new(&data[..]);
+/// let tail: Pin<&[u8; 2]> = pin_array_ref(pinned, 2);
+/// assert_eq!(*tail, [3, 4]);
+/// ```
+#[inline]
+pub fn pin_array_ref<T, const N: usize>(slice: Pin<&[T]>, offset: usize) -> Pin<&[T; N]> {
+    unsafe {
+        slice.map_unchecked(|slice| &*(sub_slice(slice, offset, N).as_ptr() as *const [T; N]))
+    }
+}
+
+/// You can use `pin_array_mut_ref` to take a pinned mutable array
+/// reference to `N` elements of a pinned slice, starting at `offset`,
+/// for handing a fixed-size part of a pinned buffer to an API that
+/// wants it pinned.
+///
+/// **Panics** if the array would be out of bounds, as `array_mut_ref!`
+/// does.
+///
+/// ```
+/// use arrayref::pin_array_mut_ref;
+/// use std::pin::Pin;
+///
+/// let mut buffer = [0u8; 6];
+/// let mut pinned = Pin::new(&mut buffer[..]);
+/// let header: Pin<&mut [u8; 2]> = pin_array_mut_ref(pinned.as_mut(), 0);
+/// *header.get_mut() = [0xca, 0xfe];
+/// pin_array_mut_ref::<_, 1>(pinned, 5).get_mut()[0] = 1;
+/// assert_eq!(buffer, [0xca, 0xfe, 0, 0, 0, 1]);
+/// ```
+#[inline]
+pub fn pin_array_mut_ref<T, const N: usize>(
+    slice: Pin<&mut [T]>,
+    offset: usize,
+) -> Pin<&mut [T; N]> {
+    unsafe {
+        slice.map_unchecked_mut(|slice| {
+            &mut *(sub_slice_mut(slice, offset, N).as_mut_ptr() as *mut [T; N])
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use core::marker::PhantomPinned;
+
+    struct Pinned(u8, PhantomPinned);
+
+    #[test]
+    fn projects_pinned_slices() {
+        let mut data = [
+            Pinned(0, PhantomPinned),
+            Pinned(1, PhantomPinned),
+            Pinned(2, PhantomPinned),
+        ];
+        let mut slice = unsafe { Pin::new_unchecked(&mut data[..]) };
+        {
+            let a: Pin<&mut [Pinned; 2]> = pin_array_mut_ref(slice.as_mut(), 1);
+            let a = unsafe { a.get_unchecked_mut() };
+            a[0].0 = 7;
+            assert_eq!(a[1].0, 2);
+        }
+        let a: Pin<&[Pinned; 3]> = pin_array_ref(slice.as_ref(), 0);
+        assert_eq!([a[0].0, a[1].0, a[2].0], [0, 7, 2]);
+        let empty: Pin<&[Pinned; 0]> = pin_array_ref(slice.as_ref(), 3);
+        assert_eq!(empty.len(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn pin_array_ref_panics_out_of_bounds() {
+        let data = [0u8; 4];
+        let _: Pin<&[u8; 2]> = pin_array_ref(Pin::new(&data[..]), 3);
+    }
+}
@@ -0,0 +1,30 @@
+//! The `Plain` marker trait for types that may be viewed as raw bytes.
+
+/// A `Plain` type is one for which every bit pattern of the right
+/// size is a valid value, and which has no padding bytes, so that a
+/// suitably aligned region of bytes can be reinterpreted as a value of
+/// this type (and vice versa).  This holds for the primitive integer
+/// and floating point types, and for arrays of them.
+///
+/// # Safety
+///
+/// Implementing this trait for a type with invalid bit patterns (such
+/// as `bool`, `char` or a reference) or with padding bytes allows
+/// undefined behavior.
+pub unsafe trait Plain: Copy + 'static {}
+
+unsafe impl Plain for u8 {}
+unsafe impl Plain for u16 {}
+unsafe impl Plain for u32 {}
+unsafe impl Plain for u64 {}
+unsafe impl Plain for u128 {}
+unsafe impl Plain for usize {}
+unsafe impl Plain for i8 {}
+unsafe impl Plain for i16 {}
+unsafe impl Plain for i32 {}
+unsafe impl Plain for i64 {}
+unsafe impl Plain for i128 {}
+unsafe impl Plain for isize {}
+unsafe impl Plain for f32 {}
+unsafe impl Plain for f64 {}
+unsafe impl<T: Plain, const N: usize> Plain for [T; N] {}
@@ -0,0 +1,118 @@
+//! Segment plans as types, for generic code that splits buffers.
+
+use core::convert::TryFrom;
+use core::mem;
+
+use error::LengthError;
+
+/// A `Seg<N>` marks a segment of `N` elements in a tuple implementing
+/// `ArraySplit`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Seg<const N: usize>;
+
+/// `ArraySplit<T>` is implemented for tuples of up to eight `Seg`
+/// markers, describing how to split a slice of `T` into consecutive
+/// array references.  It lets a library accept "any segment plan" as
+/// a type parameter where otherwise it could only offer a macro like
+/// `array_refs!`.
+///
+/// Unlike `array_refs!`, which works on arrays and insists on using
+/// all of them, `split` works on slices: it checks that there are at
+/// least `LEN` elements, and ignores any beyond them.
+///
+/// ```
+/// use arrayref::{ArraySplit, Seg};
+///
+/// fn fields<P: ArraySplit<u8>>(record: &[u8]) -> P::Refs<'_> {
+///     P::split(record).expect("record too short")
+/// }
+///
+/// let record = [1, 2, 3, 4, 5, 6];
+/// let (tag, body) = fields::<(Seg<1>, Seg<4>)>(&record);
+/// assert_eq!((tag, body), (&[1], &[2, 3, 4, 5]));
+/// assert_eq!(<(Seg<1>, Seg<4>) as ArraySplit<u8>>::LEN, 5);
+/// ```
+pub trait ArraySplit<T> {
+    /// The total length of the segments.
+    const LEN: usize;
+    /// A tuple of array references, one per segment.
+    type Refs<'a>
+    where
+        T: 'a;
+    /// A tuple of mutable array references, one per segment.
+    type Muts<'a>
+    where
+        T: 'a;
+    /// Splits the first `LEN` elements of `slice` into the segments,
+    /// or returns a `LengthError` if it is too short.
+    fn split(slice: &[T]) -> Result<Self::Refs<'_>, LengthError>;
+    /// Splits the first `LEN` elements of `slice` into mutable
+    /// segments, or returns a `LengthError` if it is too short.
+    fn split_mut(slice: &mut [T]) -> Result<Self::Muts<'_>, LengthError>;
+}
+
+macro_rules! impl_array_split {
+    ($( $len:ident ),*) => {
+        impl<T, $( const $len: usize ),*> ArraySplit<T> for ( $( Seg<$len>, )* ) {
+            const LEN: usize = 0 $( + $len )*;
+            type Refs<'a> = ( $( &'a [T; $len], )* ) where T: 'a;
+            type Muts<'a> = ( $( &'a mut [T; $len], )* ) where T: 'a;
+
+            #[allow(unused_assignments)]
+            fn split(slice: &[T]) -> Result<Self::Refs<'_>, LengthError> {
+                let len = <Self as ArraySplit<T>>::LEN;
+                if slice.len() < len {
+                    return Err(LengthError::new(len, slice.len()));
+                }
+                let mut rest = slice;
+                Ok(( $( {
+                    let (segment, tail) = rest.split_at($len);
+                    rest = tail;
+                    <&[T; $len]>::try_from(segment).unwrap()
+                }, )* ))
+            }
+
+            #[allow(unused_assignments)]
+            fn split_mut(slice: &mut [T]) -> Result<Self::Muts<'_>, LengthError> {
+                let len = <Self as ArraySplit<T>>::LEN;
+                if slice.len() < len {
+                    return Err(LengthError::new(len, slice.len()));
+                }
+                let mut rest = slice;
+                Ok(( $( {
+                    let (segment, tail) = mem::take(&mut rest).split_at_mut($len);
+                    rest = tail;
+                    <&mut [T; $len]>::try_from(segment).unwrap()
+                }, )* ))
+            }
+        }
+    };
+}
+
+impl_array_split!(A);
+impl_array_split!(A, B);
+impl_array_split!(A, B, C);
+impl_array_split!(A, B, C, D);
+impl_array_split!(A, B, C, D, E);
+impl_array_split!(A, B, C, D, E, F);
+impl_array_split!(A, B, C, D, E, F, G);
+impl_array_split!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matches_array_refs() {
+        let mut data = [0u8, 1, 2, 3, 4, 5, 6, 7, 8];
+        type Plan = (Seg<2>, Seg<0>, Seg<3>, Seg<4>);
+        assert_eq!(Plan::split(&data), Ok(array_refs!(&data, 2, 0, 3, 4)));
+        {
+            let (a, _, _, d) = Plan::split_mut(&mut data).unwrap();
+            a[0] = 9;
+            d[3] = 9;
+        }
+        assert_eq!(data, [9, 1, 2, 3, 4, 5, 6, 7, 9]);
+        assert_eq!(Plan::split(&data[1..]).unwrap_err().missing(), 1);
+    }
+}
@@ -0,0 +1,127 @@
+//! Reinterpreting byte arrays as arrays of `bytemuck::Pod` values,
+//! available with the `bytemuck` feature.
+
+use bytemuck::Pod;
+use core::marker::PhantomData;
+use core::mem::size_of;
+
+struct AssertSameSize<T, const N: usize, const M: usize>(PhantomData<T>);
+
+impl<T, const N: usize, const M: usize> AssertSameSize<T, N, M> {
+    const OK: () = assert!(
+        size_of::<T>().checked_mul(N).is_some() && size_of::<T>() * N == M,
+        "the arrays must be the same size in bytes"
+    );
+}
+
+/// You can use `cast_array_ref` to view a byte array as an array of
+/// wider `Pod` values, such as `&[u8; 64]` as `&[u32; 16]`.  The sizes
+/// are checked at compile time, and the alignment at run time: this
+/// returns `None` if `bytes` is not aligned for `T`.  The values are
+/// in native byte order.
+///
+/// ```
+/// extern crate arrayref;
+/// extern crate bytemuck;
+///
+/// use arrayref::cast_array_ref;
+///
+/// #[repr(align(8))]
+/// struct Block([u8; 16]);
+///
+/// # fn main() {
+/// let block = Block([1; 16]);
+/// let words: &[u32; 4] = cast_array_ref(&block.0).unwrap();
+/// assert_eq!(words, &[0x01010101; 4]);
+/// let halves: Option<&[u64; 1]> = cast_array_ref(arrayref::array_ref!(block.0, 4, 8));
+/// assert!(halves.is_none());
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// extern crate arrayref;
+/// extern crate bytemuck;
+///
+/// # fn main() {
+/// let bytes = [0u8; 6];
+/// let _: Option<&[u32; 2]> = arrayref::cast_array_ref(&bytes);
+/// # }
+/// ```
+#[inline]
+pub fn cast_array_ref<T: Pod, const M: usize, const N: usize>(bytes: &[u8; M]) -> Option<&[T; N]> {
+    let () = AssertSameSize::<T, N, M>::OK;
+    bytemuck::try_cast_ref(bytes).ok()
+}
+
+/// The mutable version of `cast_array_ref`.
+#[inline]
+pub fn cast_array_mut<T: Pod, const M: usize, const N: usize>(
+    bytes: &mut [u8; M],
+) -> Option<&mut [T; N]> {
+    let () = AssertSameSize::<T, N, M>::OK;
+    bytemuck::try_cast_mut(bytes).ok()
+}
+
+/// You can use `byte_array_ref` to view an array of `Pod` values as
+/// its bytes, in native byte order, such as `&[u64; 8]` as
+/// `&[u8; 64]`.  Bytes need no alignment, so only the sizes are
+/// checked, at compile time.
+///
+/// ```
+/// extern crate arrayref;
+/// extern crate bytemuck;
+///
+/// use arrayref::byte_array_ref;
+///
+/// # fn main() {
+/// let state = [u32::from_ne_bytes([1, 2, 3, 4]); 2];
+/// let bytes: &[u8; 8] = byte_array_ref(&state);
+/// assert_eq!(bytes, &[1, 2, 3, 4, 1, 2, 3, 4]);
+/// # }
+/// ```
+#[inline]
+pub fn byte_array_ref<T: Pod, const N: usize, const M: usize>(array: &[T; N]) -> &[u8; M] {
+    let () = AssertSameSize::<T, N, M>::OK;
+    bytemuck::cast_ref(array)
+}
+
+/// The mutable version of `byte_array_ref`.
+#[inline]
+pub fn byte_array_mut<T: Pod, const N: usize, const M: usize>(array: &mut [T; N]) -> &mut [u8; M] {
+    let () = AssertSameSize::<T, N, M>::OK;
+    bytemuck::cast_mut(array)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[repr(align(16))]
+    struct Aligned([u8; 32]);
+
+    #[test]
+    fn casts_follow_alignment() {
+        let mut buf = Aligned([0; 32]);
+        {
+            let words: &mut [u64; 4] = cast_array_mut(&mut buf.0).unwrap();
+            words[1] = u64::from_ne_bytes([1, 2, 3, 4, 5, 6, 7, 8]);
+        }
+        assert_eq!(buf.0[8..16], [1, 2, 3, 4, 5, 6, 7, 8]);
+        let shifted: Option<&[u32; 1]> = cast_array_ref(array_ref!(buf.0, 2, 4));
+        assert!(shifted.is_none());
+        let shifted: Option<&[u16; 2]> = cast_array_ref(array_ref!(buf.0, 2, 4));
+        assert!(shifted.is_some());
+        let empty: Option<&[u64; 0]> = cast_array_ref(array_ref!(buf.0, 1, 0));
+        assert!(empty.is_none());
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let mut lanes = [0u32; 4];
+        byte_array_mut::<_, 4, 16>(&mut lanes)[4..8].copy_from_slice(&7u32.to_ne_bytes());
+        assert_eq!(lanes, [0, 7, 0, 0]);
+        let bytes: &[u8; 16] = byte_array_ref(&lanes);
+        let round: &[u32; 4] = cast_array_ref(bytes).unwrap();
+        assert_eq!(round, &lanes);
+    }
+}
@@ -0,0 +1,218 @@
+//! Views of a buffer as an array of fixed-size records.
+
+use core::ops::{Index, IndexMut};
+use core::slice;
+
+use chunks::{as_chunks, as_chunks_mut};
+
+/// A `RecordSlice` is a view of a slice as a sequence of records of
+/// `N` elements each.  Indexing it with a record number gives an
+/// array reference to that record, and its `len` is measured in
+/// records rather than elements.
+///
+/// ```
+/// use arrayref::RecordSlice;
+///
+/// let buf = [1,2,3, 4,5,6, 7,8,9];
+/// let records = RecordSlice::<_, 3>::new(&buf).unwrap();
+/// assert_eq!(records.len(), 3);
+/// assert_eq!(records[1], [4,5,6]);
+/// assert_eq!(records.iter().map(|r| r[0]).sum::<i32>(), 12);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RecordSlice<'a, T: 'a, const N: usize> {
+    records: &'a [[T; N]],
+}
+
+impl<'a, T, const N: usize> RecordSlice<'a, T, N> {
+    /// Creates a view of `slice` as records of `N` elements, or
+    /// returns `None` if the length of `slice` is not a multiple of
+    /// `N`.
+    ///
+    /// **Panics** if `N` is zero.
+    pub fn new(slice: &'a [T]) -> Option<Self> {
+        match as_chunks(slice) {
+            (records, []) => Some(RecordSlice { records }),
+            _ => None,
+        }
+    }
+
+    /// The number of records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether there are no records at all.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The record with number `index`, or `None` if it is out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<&'a [T; N]> {
+        self.records.get(index)
+    }
+
+    /// An iterator over the records in order.
+    pub fn iter(&self) -> slice::Iter<'a, [T; N]> {
+        self.records.iter()
+    }
+
+    /// The records as a slice of arrays.
+    pub fn as_records(&self) -> &'a [[T; N]] {
+        self.records
+    }
+}
+
+impl<'a, T, const N: usize> Clone for RecordSlice<'a, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, const N: usize> Copy for RecordSlice<'a, T, N> {}
+
+impl<'a, T, const N: usize> Index<usize> for RecordSlice<'a, T, N> {
+    type Output = [T; N];
+    fn index(&self, index: usize) -> &[T; N] {
+        &self.records[index]
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for RecordSlice<'a, T, N> {
+    type Item = &'a [T; N];
+    type IntoIter = slice::Iter<'a, [T; N]>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.iter()
+    }
+}
+
+/// A `RecordSliceMut` is the mutable version of a `RecordSlice`,
+/// giving mutable array references to each record.
+///
+/// ```
+/// use arrayref::RecordSliceMut;
+///
+/// let mut buf = [0u8; 8];
+/// let mut records = RecordSliceMut::<_, 2>::new(&mut buf).unwrap();
+/// records[3] = [7, 8];
+/// for (i, rec) in records.iter_mut().enumerate().take(3) {
+///     rec[0] = i as u8;
+/// }
+/// assert_eq!(buf, [0,0, 1,0, 2,0, 7,8]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct RecordSliceMut<'a, T: 'a, const N: usize> {
+    records: &'a mut [[T; N]],
+}
+
+impl<'a, T, const N: usize> RecordSliceMut<'a, T, N> {
+    /// Creates a mutable view of `slice` as records of `N` elements,
+    /// or returns `None` if the length of `slice` is not a multiple of
+    /// `N`.
+    ///
+    /// **Panics** if `N` is zero.
+    pub fn new(slice: &'a mut [T]) -> Option<Self> {
+        match as_chunks_mut(slice) {
+            (records, []) => Some(RecordSliceMut { records }),
+            _ => None,
+        }
+    }
+
+    /// The number of records.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether there are no records at all.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The record with number `index`, or `None` if it is out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<&[T; N]> {
+        self.records.get(index)
+    }
+
+    /// The record with number `index` for modification, or `None` if
+    /// it is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut [T; N]> {
+        self.records.get_mut(index)
+    }
+
+    /// An iterator over the records in order.
+    pub fn iter(&self) -> slice::Iter<'_, [T; N]> {
+        self.records.iter()
+    }
+
+    /// An iterator over mutable references to the records in order.
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, [T; N]> {
+        self.records.iter_mut()
+    }
+
+    /// The records as a slice of arrays.
+    pub fn as_records(&self) -> &[[T; N]] {
+        self.records
+    }
+
+    /// The records as a mutable slice of arrays.
+    pub fn as_records_mut(&mut self) -> &mut [[T; N]] {
+        self.records
+    }
+}
+
+impl<'a, T, const N: usize> Index<usize> for RecordSliceMut<'a, T, N> {
+    type Output = [T; N];
+    fn index(&self, index: usize) -> &[T; N] {
+        &self.records[index]
+    }
+}
+
+impl<'a, T, const N: usize> IndexMut<usize> for RecordSliceMut<'a, T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut [T; N] {
+        &mut self.records[index]
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for RecordSliceMut<'a, T, N> {
+    type Item = &'a mut [T; N];
+    type IntoIter = slice::IterMut<'a, [T; N]>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn new_checks_divisibility() {
+        let buf = [0u8; 10];
+        assert!(RecordSlice::<_, 5>::new(&buf).is_some());
+        assert!(RecordSlice::<_, 4>::new(&buf).is_none());
+        assert!(RecordSlice::<_, 4>::new(&buf[..0]).unwrap().is_empty());
+        let mut buf = buf;
+        assert!(RecordSliceMut::<_, 3>::new(&mut buf).is_none());
+    }
+
+    #[test]
+    fn records_line_up_with_array_ref() {
+        let buf: [u16; 12] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
+        let records = RecordSlice::<_, 4>::new(&buf).unwrap();
+        for i in 0..records.len() {
+            assert_eq!(&records[i], array_ref!(buf, 4 * i, 4));
+        }
+        assert_eq!(records.get(3), None);
+        assert_eq!(records.into_iter().count(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_checks_bounds() {
+        let mut buf = [0u8; 6];
+        let records = RecordSliceMut::<_, 2>::new(&mut buf).unwrap();
+        let _ = records[3];
+    }
+}
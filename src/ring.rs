@@ -0,0 +1,237 @@
+//! A fixed-capacity ring buffer with array-reference views.
+
+use core::convert::TryFrom;
+use core::mem::MaybeUninit;
+use core::{fmt, ptr, slice};
+
+use cow::CowArrayRef;
+
+/// An `ArrayRing` is a first-in first-out queue holding up to `N`
+/// elements in inline storage, with no allocation.  Besides `push`
+/// and `pop`, its contents can be viewed in place as (at most) two
+/// contiguous regions, and the oldest `K` elements can be read as a
+/// `[T; K]`, borrowed when they are contiguous and copied when they
+/// wrap around the end of the storage.
+///
+/// ```
+/// use arrayref::ArrayRing;
+///
+/// let mut ring = ArrayRing::<u8, 4>::new();
+/// for x in 0..4 {
+///     ring.push(x).unwrap();
+/// }
+/// assert_eq!(ring.push(4), Err(4));
+/// assert_eq!(ring.pop(), Some(0));
+/// ring.push(4).unwrap();
+/// assert_eq!(ring.as_array_refs(), (&[1, 2, 3][..], &[4][..]));
+/// assert_eq!(*ring.read_array::<2>().unwrap(), [1, 2]);
+/// assert_eq!(*ring.read_array::<4>().unwrap(), [1, 2, 3, 4]);
+/// ```
+pub struct ArrayRing<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayRing<T, N> {
+    /// Creates an empty ring.
+    pub fn new() -> Self {
+        ArrayRing {
+            // an array of `MaybeUninit` needs no initialization
+            buf: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// The number of elements in the ring.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the ring is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether the ring holds `N` elements, so that `push` would fail.
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Adds an element at the back of the ring, or hands it back if
+    /// the ring is already full.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(item);
+        }
+        let tail = (self.head + self.len) % N;
+        self.buf[tail] = MaybeUninit::new(item);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the element at the front of the ring, if any.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let item = unsafe { self.buf[self.head].as_ptr().read() };
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(item)
+    }
+
+    /// The contents of the ring, oldest first, as the contiguous run
+    /// starting at the front followed by the run (possibly empty) that
+    /// wrapped around to the start of the storage.
+    pub fn as_array_refs(&self) -> (&[T], &[T]) {
+        let first = if self.head + self.len <= N {
+            self.len
+        } else {
+            N - self.head
+        };
+        // both runs lie within the initialized part of the storage
+        let p = self.buf.as_ptr() as *const T;
+        unsafe {
+            (
+                slice::from_raw_parts(p.add(self.head), first),
+                slice::from_raw_parts(p, self.len - first),
+            )
+        }
+    }
+
+    /// The contents of the ring for modification, in the same two
+    /// runs as `as_array_refs`.
+    pub fn as_mut_array_refs(&mut self) -> (&mut [T], &mut [T]) {
+        let first = if self.head + self.len <= N {
+            self.len
+        } else {
+            N - self.head
+        };
+        let p = self.buf.as_mut_ptr() as *mut T;
+        unsafe {
+            (
+                slice::from_raw_parts_mut(p.add(self.head), first),
+                slice::from_raw_parts_mut(p, self.len - first),
+            )
+        }
+    }
+
+    /// The `K` oldest elements, without removing them, or `None` if
+    /// the ring holds fewer than `K` elements.  They are borrowed in
+    /// place when contiguous, and copied when they wrap around.
+    pub fn read_array<const K: usize>(&self) -> Option<CowArrayRef<'_, T, K>>
+    where
+        T: Copy,
+    {
+        if K > self.len {
+            return None;
+        }
+        let (first, second) = self.as_array_refs();
+        Some(if K <= first.len() {
+            CowArrayRef::Borrowed(<&[T; K]>::try_from(&first[..K]).unwrap())
+        } else {
+            CowArrayRef::Owned(core::array::from_fn(|i| {
+                if i < first.len() {
+                    first[i]
+                } else {
+                    second[i - first.len()]
+                }
+            }))
+        })
+    }
+
+    /// Removes all elements from the ring.
+    pub fn clear(&mut self) {
+        let (first, second) = self.as_mut_array_refs();
+        let (first, second) = (first as *mut [T], second as *mut [T]);
+        self.head = 0;
+        self.len = 0;
+        unsafe {
+            ptr::drop_in_place(first);
+            ptr::drop_in_place(second);
+        }
+    }
+}
+
+impl<T, const N: usize> Default for ArrayRing<T, N> {
+    fn default() -> Self {
+        ArrayRing::new()
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayRing<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayRing<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (first, second) = self.as_array_refs();
+        f.debug_list().entries(first).entries(second).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn behaves_like_a_queue() {
+        let mut ring = ArrayRing::<u32, 3>::new();
+        let mut model = std::collections::VecDeque::new();
+        for i in 0..50 {
+            if i % 3 == 2 {
+                assert_eq!(ring.pop(), model.pop_front());
+            } else if ring.push(i).is_ok() {
+                model.push_back(i);
+            }
+            let (a, b) = ring.as_array_refs();
+            let (x, y) = model.as_slices();
+            assert_eq!([a, b].concat(), [x, y].concat());
+            if let Some(read) = ring.read_array::<2>() {
+                assert_eq!(
+                    read[..],
+                    model.iter().take(2).cloned().collect::<std::vec::Vec<_>>()[..]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn read_array_borrows_only_when_contiguous() {
+        let mut ring = ArrayRing::<u8, 3>::new();
+        ring.push(1).unwrap();
+        ring.push(2).unwrap();
+        assert!(matches!(
+            ring.read_array::<2>(),
+            Some(CowArrayRef::Borrowed(&[1, 2]))
+        ));
+        ring.pop();
+        ring.push(3).unwrap();
+        ring.push(4).unwrap();
+        assert!(matches!(
+            ring.read_array::<3>(),
+            Some(CowArrayRef::Owned([2, 3, 4]))
+        ));
+        assert!(ring.read_array::<4>().is_none());
+    }
+
+    #[test]
+    fn drops_remaining_elements() {
+        let counter = Rc::new(());
+        {
+            let mut ring = ArrayRing::<Rc<()>, 4>::new();
+            for _ in 0..4 {
+                ring.push(counter.clone()).unwrap();
+            }
+            ring.pop();
+            ring.push(counter.clone()).unwrap();
+            assert_eq!(Rc::strong_count(&counter), 5);
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+}
@@ -0,0 +1,257 @@
+//! Method-call forms of `array_ref!` and friends.
+
+use bounds::{sub_slice, sub_slice_mut, try_sub_slice, try_sub_slice_mut};
+use chunk_iter::{
+    ArrayChunks, ArrayChunksMut, ArrayRChunks, ArrayRChunksMut, ArrayStrided, ArrayStridedMut,
+    ArrayWindows,
+};
+
+/// `SliceExt` makes the array references of `array_ref!`,
+/// `array_mut_ref!` and their fallible forms available as methods on
+/// slices.  Since the length is a const parameter rather than a macro
+/// argument, these can be used in generic code and in method chains.
+///
+/// ```
+/// use arrayref::SliceExt;
+///
+/// fn checksum<const N: usize>(record: &[u8]) -> u8 {
+///     record.array_at::<N>(1).iter().fold(0, |a, &b| a ^ b)
+/// }
+///
+/// let mut record = [0u8, 1, 2, 4, 8];
+/// assert_eq!(checksum::<3>(&record), 7);
+/// *record.array_at_mut::<2>(3) = [0, 0];
+/// assert_eq!(record.try_array_at::<2>(3), Some(&[0, 0]));
+/// assert_eq!(record.try_array_at::<2>(4), None);
+/// ```
+pub trait SliceExt<T> {
+    /// An array reference to the `N` elements starting at `offset`,
+    /// as `array_ref!(self, offset, N)`.
+    ///
+    /// **Panics** if the array is out of bounds.
+    fn array_at<const N: usize>(&self, offset: usize) -> &[T; N];
+
+    /// A mutable array reference to the `N` elements starting at
+    /// `offset`, as `array_mut_ref!(self, offset, N)`.
+    ///
+    /// **Panics** if the array is out of bounds.
+    fn array_at_mut<const N: usize>(&mut self, offset: usize) -> &mut [T; N];
+
+    /// An array reference to the `N` elements starting at `offset`, or
+    /// `None` if it would be out of bounds, as `try_array_ref!`.
+    fn try_array_at<const N: usize>(&self, offset: usize) -> Option<&[T; N]>;
+
+    /// A mutable array reference to the `N` elements starting at
+    /// `offset`, or `None` if it would be out of bounds, as
+    /// `try_array_mut_ref!`.
+    fn try_array_at_mut<const N: usize>(&mut self, offset: usize) -> Option<&mut [T; N]>;
+
+    /// An iterator over the non-overlapping `N`-element chunks of the
+    /// slice, as array references.  Any elements left over at the end
+    /// are available from `ArrayChunks::remainder`.
+    ///
+    /// **Panics** if `N` is zero.
+    ///
+    /// ```
+    /// use arrayref::SliceExt;
+    ///
+    /// let message = [0x5au8; 40];
+    /// let mut blocks = message.array_chunks_ref::<16>();
+    /// let digest = blocks
+    ///     .by_ref()
+    ///     .fold(0u128, |h, block| h.rotate_left(5) ^ u128::from_le_bytes(*block));
+    /// assert_ne!(digest, 0);
+    /// assert_eq!(blocks.remainder(), &message[32..]);
+    /// ```
+    fn array_chunks_ref<const N: usize>(&self) -> ArrayChunks<'_, T, N>;
+
+    /// An iterator over the non-overlapping `N`-element chunks of the
+    /// slice, as mutable array references.  Any elements left over at
+    /// the end are available from `ArrayChunksMut::into_remainder`.
+    ///
+    /// **Panics** if `N` is zero.
+    ///
+    /// ```
+    /// use arrayref::{xor_assign, SliceExt};
+    ///
+    /// // Counter-mode encryption with a (very) toy block function.
+    /// let keystream = |counter: u32| [counter as u8; 4];
+    /// let mut message = *b"attack at dawn";
+    /// let mut blocks = message.array_chunks_mut_ref::<4>();
+    /// for (counter, block) in (0..).zip(&mut blocks) {
+    ///     xor_assign(block, &keystream(counter));
+    /// }
+    /// for b in blocks.into_remainder() {
+    ///     *b ^= 0xff;
+    /// }
+    /// assert_eq!(&message[..6], b"attabj");
+    /// ```
+    fn array_chunks_mut_ref<const N: usize>(&mut self) -> ArrayChunksMut<'_, T, N>;
+
+    /// An iterator over the overlapping `N`-element windows of the
+    /// slice, as array references, like `slice::windows` but with the
+    /// length of each window known at compile time.
+    ///
+    /// **Panics** if `N` is zero.
+    ///
+    /// ```
+    /// use arrayref::SliceExt;
+    ///
+    /// let samples = [1, 4, 2, 8, 5, 7];
+    /// let smoothed: Vec<i32> = samples
+    ///     .array_windows_ref::<3>()
+    ///     .map(|[a, b, c]| (a + b + c) / 3)
+    ///     .collect();
+    /// assert_eq!(smoothed, [2, 4, 5, 6]);
+    /// ```
+    fn array_windows_ref<const N: usize>(&self) -> ArrayWindows<'_, T, N>;
+
+    /// An iterator over the non-overlapping `N`-element chunks of the
+    /// slice, starting at the end, as array references.  Any elements
+    /// left over at the start are available from
+    /// `ArrayRChunks::remainder`.  This suits formats that are parsed
+    /// back to front from a trailer.
+    ///
+    /// **Panics** if `N` is zero.
+    ///
+    /// ```
+    /// use arrayref::SliceExt;
+    ///
+    /// // A log whose 4-byte records are appended after a 1-byte header.
+    /// let log = [9, 0, 0, 0, 1, 0, 0, 0, 2];
+    /// let newest_first = log.array_rchunks_ref::<4>();
+    /// assert_eq!(newest_first.remainder(), &[9]);
+    /// let seqs: Vec<u32> = newest_first.map(|r| u32::from_be_bytes(*r)).collect();
+    /// assert_eq!(seqs, [2, 1]);
+    /// ```
+    fn array_rchunks_ref<const N: usize>(&self) -> ArrayRChunks<'_, T, N>;
+
+    /// An iterator over the non-overlapping `N`-element chunks of the
+    /// slice, starting at the end, as mutable array references.  Any
+    /// elements left over at the start are available from
+    /// `ArrayRChunksMut::into_remainder`.
+    ///
+    /// **Panics** if `N` is zero.
+    fn array_rchunks_mut_ref<const N: usize>(&mut self) -> ArrayRChunksMut<'_, T, N>;
+
+    /// An iterator over the `N`-element arrays at offsets `0, stride,
+    /// 2 * stride, ...` of the slice, as array references, skipping
+    /// the elements between them.  The last array must fit whole; any
+    /// elements after it are ignored.
+    ///
+    /// **Panics** if `stride` is zero or less than `N`.
+    ///
+    /// ```
+    /// use arrayref::SliceExt;
+    ///
+    /// // Stereo 16-bit frames, each followed by 2 bytes of padding.
+    /// let frames = [1, 0, 2, 0, 0xee, 0xee, 3, 0, 4, 0, 0xee, 0xee];
+    /// let left: Vec<u16> = frames
+    ///     .array_strided_ref::<2>(6)
+    ///     .map(|s| u16::from_le_bytes(*s))
+    ///     .collect();
+    /// assert_eq!(left, [1, 3]);
+    /// ```
+    fn array_strided_ref<const N: usize>(&self, stride: usize) -> ArrayStrided<'_, T, N>;
+
+    /// An iterator over the `N`-element arrays at offsets `0, stride,
+    /// 2 * stride, ...` of the slice, as mutable array references.
+    ///
+    /// **Panics** if `stride` is zero or less than `N`.
+    fn array_strided_mut_ref<const N: usize>(&mut self, stride: usize)
+        -> ArrayStridedMut<'_, T, N>;
+}
+
+impl<T> SliceExt<T> for [T] {
+    #[inline]
+    fn array_at<const N: usize>(&self, offset: usize) -> &[T; N] {
+        let slice = sub_slice(self, offset, N);
+        // `slice` has exactly `N` elements.
+        unsafe { &*(slice.as_ptr() as *const [T; N]) }
+    }
+
+    #[inline]
+    fn array_at_mut<const N: usize>(&mut self, offset: usize) -> &mut [T; N] {
+        let slice = sub_slice_mut(self, offset, N);
+        unsafe { &mut *(slice.as_mut_ptr() as *mut [T; N]) }
+    }
+
+    #[inline]
+    fn try_array_at<const N: usize>(&self, offset: usize) -> Option<&[T; N]> {
+        try_sub_slice(self, offset, N).map(|slice| unsafe { &*(slice.as_ptr() as *const [T; N]) })
+    }
+
+    #[inline]
+    fn try_array_at_mut<const N: usize>(&mut self, offset: usize) -> Option<&mut [T; N]> {
+        try_sub_slice_mut(self, offset, N)
+            .map(|slice| unsafe { &mut *(slice.as_mut_ptr() as *mut [T; N]) })
+    }
+
+    #[inline]
+    fn array_chunks_ref<const N: usize>(&self) -> ArrayChunks<'_, T, N> {
+        ArrayChunks::new(self)
+    }
+
+    #[inline]
+    fn array_chunks_mut_ref<const N: usize>(&mut self) -> ArrayChunksMut<'_, T, N> {
+        ArrayChunksMut::new(self)
+    }
+
+    #[inline]
+    fn array_windows_ref<const N: usize>(&self) -> ArrayWindows<'_, T, N> {
+        ArrayWindows::new(self)
+    }
+
+    #[inline]
+    fn array_rchunks_ref<const N: usize>(&self) -> ArrayRChunks<'_, T, N> {
+        ArrayRChunks::new(self)
+    }
+
+    #[inline]
+    fn array_rchunks_mut_ref<const N: usize>(&mut self) -> ArrayRChunksMut<'_, T, N> {
+        ArrayRChunksMut::new(self)
+    }
+
+    #[inline]
+    fn array_strided_ref<const N: usize>(&self, stride: usize) -> ArrayStrided<'_, T, N> {
+        ArrayStrided::new(self, stride)
+    }
+
+    #[inline]
+    fn array_strided_mut_ref<const N: usize>(
+        &mut self,
+        stride: usize,
+    ) -> ArrayStridedMut<'_, T, N> {
+        ArrayStridedMut::new(self, stride)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate quickcheck;
+
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn agrees_with_macros() {
+        fn f(mut data: Vec<u8>, offset: usize) -> bool {
+            let expected = try_array_ref!(data, offset, 4).cloned();
+            if data.try_array_at::<4>(offset).cloned() != expected {
+                return false;
+            }
+            match data.try_array_at_mut::<4>(offset) {
+                Some(a) => *a = [1; 4],
+                None => return expected.is_none(),
+            }
+            data.array_at::<4>(offset) == &[1; 4] && data.array_at_mut::<4>(offset) == &[1; 4]
+        }
+        quickcheck::quickcheck(f as fn(Vec<u8>, usize) -> bool);
+    }
+
+    #[test]
+    #[should_panic]
+    fn checks_bounds() {
+        [0u8; 4].array_at::<2>(3);
+    }
+}
@@ -0,0 +1,91 @@
+//! Copying small byte arrays in and out of buffers.
+
+use bounds::{sub_slice, sub_slice_mut};
+use core::ptr;
+
+struct AssertSmall<const N: usize>;
+
+impl<const N: usize> AssertSmall<N> {
+    const OK: () = assert!(N <= 16, "small arrays are at most 16 bytes");
+}
+
+/// Copies `N <= 16` bytes starting at `offset` out of `bytes`.  This
+/// does a single bounds check followed by a single unaligned load,
+/// which is what you want for reading a small header field, and
+/// unlike `*array_ref!(...)` does not depend on the optimizer to
+/// collapse the copy.  `N` is checked at compile time.
+///
+/// `read_int!` is built on this.
+///
+/// **Panics** if the array is out of bounds, as `array_ref!` does.
+///
+/// ```
+/// use arrayref::read_small_array;
+///
+/// let frame = [0xff, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66];
+/// let mac: [u8; 6] = read_small_array(&frame, 2);
+/// assert_eq!(mac, [0x11, 0x22, 0x33, 0x44, 0x55, 0x66]);
+/// ```
+#[inline(always)]
+pub fn read_small_array<const N: usize>(bytes: &[u8], offset: usize) -> [u8; N] {
+    let () = AssertSmall::<N>::OK;
+    let window = sub_slice(bytes, offset, N);
+    unsafe { ptr::read_unaligned(window.as_ptr() as *const [u8; N]) }
+}
+
+/// Copies `N <= 16` bytes into `bytes` starting at `offset`, with a
+/// single bounds check and a single unaligned store.  `N` is checked
+/// at compile time.  `write_int!` is built on this.
+///
+/// **Panics** if the array is out of bounds, as `array_mut_ref!`
+/// does.
+///
+/// ```
+/// use arrayref::write_small_array;
+///
+/// let mut frame = [0u8; 8];
+/// write_small_array(&mut frame, 4, 0xdeadbeefu32.to_be_bytes());
+/// assert_eq!(frame, [0, 0, 0, 0, 0xde, 0xad, 0xbe, 0xef]);
+/// ```
+#[inline(always)]
+pub fn write_small_array<const N: usize>(bytes: &mut [u8], offset: usize, value: [u8; N]) {
+    let () = AssertSmall::<N>::OK;
+    let window = sub_slice_mut(bytes, offset, N);
+    unsafe { ptr::write_unaligned(window.as_mut_ptr() as *mut [u8; N], value) }
+}
+
+#[cfg(test)]
+mod test {
+    extern crate quickcheck;
+
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn agrees_with_array_ref() {
+        fn f(mut data: Vec<u8>, offset: usize) -> quickcheck::TestResult {
+            if data.len() < 7 || data.len() - 7 < offset {
+                return quickcheck::TestResult::discard();
+            }
+            let read: [u8; 7] = read_small_array(&data, offset);
+            let expected = *array_ref!(data, offset, 7);
+            write_small_array(&mut data, offset, [1; 7]);
+            quickcheck::TestResult::from_bool(
+                read == expected && *array_ref!(data, offset, 7) == [1; 7],
+            )
+        }
+        quickcheck::quickcheck(f as fn(Vec<u8>, usize) -> quickcheck::TestResult);
+    }
+
+    #[test]
+    #[should_panic]
+    fn checks_bounds() {
+        let _: [u8; 4] = read_small_array(&[0; 6], 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn offset_overflow_is_caught() {
+        write_small_array(&mut [0; 6], usize::MAX, [0; 2]);
+    }
+}
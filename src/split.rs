@@ -0,0 +1,203 @@
+//! Splitting slices into segments whose lengths are only known at
+//! runtime.
+
+use core::{mem, slice};
+
+use error::LengthError;
+
+/// Adds up `lens`, saturating at `usize::MAX` on overflow (which can
+/// never fit in a slice anyway).
+fn total_length(lens: &[usize]) -> usize {
+    lens.iter()
+        .fold(0usize, |total, &len| total.saturating_add(len))
+}
+
+/// You can use `split_lengths` to split a slice into consecutive
+/// segments with the given lengths, for formats whose field widths
+/// come from a header rather than being known at compile time.  It is
+/// the runtime companion of `array_refs!`.
+///
+/// The lengths are validated once up front: if they add up to more
+/// than the length of the slice, a `LengthError` is returned.  If they
+/// add up to less, the leftover elements are available from the
+/// iterator's `remainder` method.
+///
+/// ```
+/// use arrayref::split_lengths;
+///
+/// let widths = [2, 3, 1];
+/// let record = b"idnamex...";
+/// let mut fields = split_lengths(record, &widths).unwrap();
+/// assert_eq!(fields.next(), Some(&b"id"[..]));
+/// assert_eq!(fields.next(), Some(&b"nam"[..]));
+/// assert_eq!(fields.next(), Some(&b"e"[..]));
+/// assert_eq!(fields.next(), None);
+/// assert_eq!(fields.remainder(), b"x...");
+///
+/// assert_eq!(split_lengths(record, &[8, 4]).unwrap_err().missing(), 2);
+/// ```
+pub fn split_lengths<'a, 'b, T>(
+    slice: &'a [T],
+    lens: &'b [usize],
+) -> Result<SplitLengths<'a, 'b, T>, LengthError> {
+    let total = total_length(lens);
+    if total > slice.len() {
+        return Err(LengthError::new(total, slice.len()));
+    }
+    let (segments, remainder) = slice.split_at(total);
+    Ok(SplitLengths {
+        segments,
+        remainder,
+        lens: lens.iter(),
+    })
+}
+
+/// The iterator returned by `split_lengths`.
+#[derive(Debug, Clone)]
+pub struct SplitLengths<'a, 'b, T: 'a> {
+    segments: &'a [T],
+    remainder: &'a [T],
+    lens: slice::Iter<'b, usize>,
+}
+
+impl<'a, 'b, T> SplitLengths<'a, 'b, T> {
+    /// The elements following the last segment.
+    pub fn remainder(&self) -> &'a [T] {
+        self.remainder
+    }
+}
+
+impl<'a, 'b, T> Iterator for SplitLengths<'a, 'b, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<&'a [T]> {
+        let &len = self.lens.next()?;
+        let (segment, rest) = self.segments.split_at(len);
+        self.segments = rest;
+        Some(segment)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lens.size_hint()
+    }
+}
+
+impl<'a, 'b, T> ExactSizeIterator for SplitLengths<'a, 'b, T> {}
+
+/// You can use `split_lengths_mut` to split a mutable slice into
+/// disjoint mutable segments with the given lengths, which a chain of
+/// `split_at_mut` calls can only do for a fixed number of segments.
+/// The lengths are validated as in `split_lengths`.
+///
+/// ```
+/// use arrayref::split_lengths_mut;
+///
+/// let mut record = *b"abcdefg";
+/// for field in split_lengths_mut(&mut record, &[2, 3]).unwrap() {
+///     field.reverse();
+/// }
+/// assert_eq!(&record, b"baedcfg");
+/// ```
+pub fn split_lengths_mut<'a, 'b, T>(
+    slice: &'a mut [T],
+    lens: &'b [usize],
+) -> Result<SplitLengthsMut<'a, 'b, T>, LengthError> {
+    let total = total_length(lens);
+    if total > slice.len() {
+        return Err(LengthError::new(total, slice.len()));
+    }
+    let (segments, remainder) = slice.split_at_mut(total);
+    Ok(SplitLengthsMut {
+        segments,
+        remainder,
+        lens: lens.iter(),
+    })
+}
+
+/// The iterator returned by `split_lengths_mut`.
+#[derive(Debug)]
+pub struct SplitLengthsMut<'a, 'b, T: 'a> {
+    segments: &'a mut [T],
+    remainder: &'a mut [T],
+    lens: slice::Iter<'b, usize>,
+}
+
+impl<'a, 'b, T> SplitLengthsMut<'a, 'b, T> {
+    /// Consumes the iterator, returning the elements following the
+    /// last segment.
+    pub fn into_remainder(self) -> &'a mut [T] {
+        self.remainder
+    }
+}
+
+impl<'a, 'b, T> Iterator for SplitLengthsMut<'a, 'b, T> {
+    type Item = &'a mut [T];
+
+    fn next(&mut self) -> Option<&'a mut [T]> {
+        let &len = self.lens.next()?;
+        let (segment, rest) = mem::take(&mut self.segments).split_at_mut(len);
+        self.segments = rest;
+        Some(segment)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lens.size_hint()
+    }
+}
+
+impl<'a, 'b, T> ExactSizeIterator for SplitLengthsMut<'a, 'b, T> {}
+
+#[cfg(test)]
+mod test {
+    extern crate quickcheck;
+
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn segments_cover_the_slice() {
+        fn f(data: Vec<u8>, lens: Vec<u8>) -> bool {
+            let lens: Vec<usize> = lens.iter().map(|&l| l as usize % 8).collect();
+            let total: usize = lens.iter().sum();
+            match split_lengths(&data, &lens) {
+                Ok(mut segments) => {
+                    let mut joined: Vec<u8> = Vec::new();
+                    for (segment, &len) in (&mut segments).zip(&lens) {
+                        assert_eq!(segment.len(), len);
+                        joined.extend(segment);
+                    }
+                    joined.extend(segments.remainder());
+                    joined == data
+                }
+                Err(e) => total > data.len() && e.missing() == total - data.len(),
+            }
+        }
+        quickcheck::quickcheck(f as fn(Vec<u8>, Vec<u8>) -> bool);
+    }
+
+    #[test]
+    fn mutable_segments_are_disjoint() {
+        let mut data = [0u8; 10];
+        {
+            let mut segments = split_lengths_mut(&mut data, &[1, 0, 3, 4]).unwrap();
+            let all: Vec<&mut [u8]> = (&mut segments).collect();
+            for (i, segment) in all.into_iter().enumerate() {
+                for x in segment.iter_mut() {
+                    *x = i as u8 + 1;
+                }
+            }
+            segments.into_remainder()[1] = 9;
+        }
+        assert_eq!(data, [1, 3, 3, 3, 4, 4, 4, 4, 0, 9]);
+        assert_eq!(
+            split_lengths_mut(&mut data, &[11]).unwrap_err().missing(),
+            1
+        );
+    }
+
+    #[test]
+    fn overflowing_lengths_are_an_error() {
+        let data = [0u8; 4];
+        assert!(split_lengths(&data, &[usize::MAX, 2]).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+//! Compile-time checked windows of array references.
+
+/// Support for `const_sub_array!` and `const_sub_array_mut!`.  This
+/// is a trait so that the macros can name the window's offset and
+/// length while leaving the element type and array length inferred.
+#[doc(hidden)]
+pub trait SubArray<const OFFSET: usize, const LEN: usize> {
+    type Elem;
+    fn sub_array(&self) -> &[Self::Elem; LEN];
+    fn sub_array_mut(&mut self) -> &mut [Self::Elem; LEN];
+}
+
+struct AssertInBounds<const M: usize, const OFFSET: usize, const LEN: usize>;
+
+impl<const M: usize, const OFFSET: usize, const LEN: usize> AssertInBounds<M, OFFSET, LEN> {
+    const OK: () = assert!(
+        OFFSET <= M && LEN <= M - OFFSET,
+        "sub-array does not fit within the array"
+    );
+}
+
+impl<T, const M: usize, const OFFSET: usize, const LEN: usize> SubArray<OFFSET, LEN> for [T; M] {
+    type Elem = T;
+
+    #[inline]
+    fn sub_array(&self) -> &[T; LEN] {
+        let () = AssertInBounds::<M, OFFSET, LEN>::OK;
+        unsafe { &*(self.as_ptr().add(OFFSET) as *const [T; LEN]) }
+    }
+
+    #[inline]
+    fn sub_array_mut(&mut self) -> &mut [T; LEN] {
+        let () = AssertInBounds::<M, OFFSET, LEN>::OK;
+        unsafe { &mut *(self.as_mut_ptr().add(OFFSET) as *mut [T; LEN]) }
+    }
+}
+
+/// `ArrayExt` is the method-call form of `const_sub_array!` and
+/// `const_sub_array_mut!`: with the offset and length as const
+/// parameters, a window can be taken in generic code or at the end of
+/// a method chain, still with the bounds checked at compile time and
+/// no panic path.
+///
+/// ```
+/// use arrayref::ArrayExt;
+///
+/// fn tag<const N: usize>(record: &[u8; N]) -> u16 {
+///     u16::from_be_bytes(*record.sub_array::<0, 2>())
+/// }
+///
+/// let mut record = [0x01, 0x02, 0, 0, 0, 0];
+/// assert_eq!(tag(&record), 0x0102);
+/// *record.sub_array_mut::<2, 4>() = [9; 4];
+/// assert_eq!(record, [1, 2, 9, 9, 9, 9]);
+/// ```
+///
+/// ```compile_fail
+/// use arrayref::ArrayExt;
+///
+/// let record = [0u8; 6];
+/// let _ = record.sub_array::<4, 4>();
+/// ```
+pub trait ArrayExt<T> {
+    /// An array reference to the `LEN` elements starting at `OFFSET`.
+    fn sub_array<const OFFSET: usize, const LEN: usize>(&self) -> &[T; LEN];
+
+    /// A mutable array reference to the `LEN` elements starting at
+    /// `OFFSET`.
+    fn sub_array_mut<const OFFSET: usize, const LEN: usize>(&mut self) -> &mut [T; LEN];
+}
+
+impl<T, const M: usize> ArrayExt<T> for [T; M] {
+    #[inline]
+    fn sub_array<const OFFSET: usize, const LEN: usize>(&self) -> &[T; LEN] {
+        SubArray::<OFFSET, LEN>::sub_array(self)
+    }
+
+    #[inline]
+    fn sub_array_mut<const OFFSET: usize, const LEN: usize>(&mut self) -> &mut [T; LEN] {
+        SubArray::<OFFSET, LEN>::sub_array_mut(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ArrayExt;
+
+    #[test]
+    fn windows_line_up_with_array_ref() {
+        let mut data = [0u8, 1, 2, 3, 4, 5, 6, 7];
+        assert_eq!(const_sub_array!(&data, 2, 3), array_ref!(data, 2, 3));
+        assert_eq!(const_sub_array!(&data, 8, 0), &[]);
+        assert_eq!(const_sub_array!(&data, 0, 8), &data);
+        *const_sub_array_mut!(&mut data, 6, 2) = [9, 9];
+        assert_eq!(data, [0, 1, 2, 3, 4, 5, 9, 9]);
+        assert_eq!(data.sub_array::<5, 3>(), &[5, 9, 9]);
+        assert_eq!(data.sub_array_mut::<0, 0>(), &mut []);
+    }
+}
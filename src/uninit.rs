@@ -0,0 +1,112 @@
+//! Initializing fixed-size regions of `MaybeUninit` buffers.
+//!
+//! `array_ref!` and `array_mut_ref!` work on slices of `MaybeUninit<T>`
+//! like any other, so `array_mut_ref!(buf, offset, N)` already gives a
+//! `&mut [MaybeUninit<T>; N]`.  These functions fill such a region and
+//! hand it back as initialized values.
+
+use bounds::sub_slice_mut;
+use core::mem::MaybeUninit;
+
+/// You can use `write_array` to initialize an array of `MaybeUninit`
+/// from `value`, getting back a reference to the now initialized
+/// elements.  Anything already in the array is overwritten without
+/// being dropped, as with `MaybeUninit::write`.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+///
+/// use arrayref::write_array;
+/// use std::mem::MaybeUninit;
+///
+/// # fn main() {
+/// let mut buf = [MaybeUninit::<u8>::uninit(); 8];
+/// let header = write_array(array_mut_ref!(buf, 0, 2), [0xca, 0xfe]);
+/// header[1] = 0xfa;
+/// assert_eq!(header, &[0xca, 0xfa]);
+/// # }
+/// ```
+#[inline]
+pub fn write_array<T, const N: usize>(
+    slot: &mut [MaybeUninit<T>; N],
+    value: [T; N],
+) -> &mut [T; N] {
+    let slot = slot as *mut [MaybeUninit<T>; N] as *mut [T; N];
+    unsafe {
+        slot.write(value);
+        &mut *slot
+    }
+}
+
+/// You can use `write_array_at` to initialize the `N` elements of an
+/// uninitialized buffer starting at `offset`, getting back a reference
+/// to them.  This is `write_array(array_mut_ref!(buf, offset, N), value)`
+/// without spelling out `N`.
+///
+/// **Panics** if the array would be out of bounds, as
+/// `array_mut_ref!` does.
+///
+/// ```
+/// use arrayref::write_array_at;
+/// use std::mem::MaybeUninit;
+///
+/// let mut buf = [MaybeUninit::<u16>::uninit(); 4];
+/// let tail = write_array_at(&mut buf, 2, [7, 8]);
+/// assert_eq!(tail, &[7, 8]);
+/// ```
+#[inline]
+pub fn write_array_at<T, const N: usize>(
+    buf: &mut [MaybeUninit<T>],
+    offset: usize,
+    value: [T; N],
+) -> &mut [T; N] {
+    let slot = sub_slice_mut(buf, offset, N).as_mut_ptr() as *mut [MaybeUninit<T>; N];
+    write_array(unsafe { &mut *slot }, value)
+}
+
+/// Views an array of `MaybeUninit` as the values it holds.
+///
+/// # Safety
+///
+/// Every element of `array` must be initialized.
+#[inline]
+pub unsafe fn assume_init_array_ref<T, const N: usize>(array: &[MaybeUninit<T>; N]) -> &[T; N] {
+    &*(array as *const [MaybeUninit<T>; N] as *const [T; N])
+}
+
+/// The mutable version of `assume_init_array_ref`.
+///
+/// # Safety
+///
+/// Every element of `array` must be initialized.
+#[inline]
+pub unsafe fn assume_init_array_mut<T, const N: usize>(
+    array: &mut [MaybeUninit<T>; N],
+) -> &mut [T; N] {
+    &mut *(array as *mut [MaybeUninit<T>; N] as *mut [T; N])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn writes_regions_of_uninit_buffers() {
+        let mut buf = [MaybeUninit::<u32>::uninit(); 5];
+        assert_eq!(write_array_at(&mut buf, 0, [1, 2]), &[1, 2]);
+        *write_array_at(&mut buf, 2, [3, 4, 5]) = [6, 7, 8];
+        assert_eq!(write_array_at::<_, 0>(&mut buf, 5, []), &[]);
+        let all = unsafe { assume_init_array_mut(&mut buf) };
+        all[0] = 9;
+        let all = unsafe { assume_init_array_ref(&buf) };
+        assert_eq!(all, &[9, 2, 6, 7, 8]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn write_array_at_panics_out_of_bounds() {
+        let mut buf = [MaybeUninit::<u8>::uninit(); 4];
+        write_array_at(&mut buf, 3, [0, 0]);
+    }
+}
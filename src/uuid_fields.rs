@@ -0,0 +1,88 @@
+//! Conversions between 16-byte windows and `uuid::Uuid`, available
+//! with the `uuid` feature.
+
+use uuid::Uuid;
+
+/// Reads a UUID stored in its standard big-endian byte order.
+///
+/// ```
+/// #[macro_use]
+/// extern crate arrayref;
+/// extern crate uuid;
+///
+/// # fn main() {
+/// let mut page = [0u8; 64];
+/// page[16] = 0x67;
+/// let id = arrayref::read_uuid(array_ref!(page, 16, 16));
+/// assert_eq!(id.as_bytes()[0], 0x67);
+/// # }
+/// ```
+#[inline]
+pub fn read_uuid(bytes: &[u8; 16]) -> Uuid {
+    Uuid::from_bytes(*bytes)
+}
+
+/// Writes `id` in its standard big-endian byte order.
+#[inline]
+pub fn write_uuid(bytes: &mut [u8; 16], id: &Uuid) {
+    *bytes = *id.as_bytes();
+}
+
+/// Reads a UUID stored in the mixed-endian layout used by Microsoft
+/// GUIDs, where the first three fields are little-endian.
+#[inline]
+pub fn read_uuid_le(bytes: &[u8; 16]) -> Uuid {
+    Uuid::from_bytes_le(*bytes)
+}
+
+/// Writes `id` in the mixed-endian layout used by Microsoft GUIDs.
+#[inline]
+pub fn write_uuid_le(bytes: &mut [u8; 16], id: &Uuid) {
+    *bytes = id.to_bytes_le();
+}
+
+/// Formats `id` as a lowercase hyphenated string, such as
+/// `67e55044-10b1-426f-9247-bb680e5fe0c8`, into a fixed 36-byte field.
+///
+/// ```
+/// extern crate arrayref;
+/// extern crate uuid;
+///
+/// # fn main() {
+/// let id = uuid::Uuid::from_bytes([
+///     0x67, 0xe5, 0x50, 0x44, 0x10, 0xb1, 0x42, 0x6f,
+///     0x92, 0x47, 0xbb, 0x68, 0x0e, 0x5f, 0xe0, 0xc8,
+/// ]);
+/// let mut text = [0u8; 36];
+/// arrayref::write_uuid_hyphenated(&mut text, &id);
+/// assert_eq!(&text, b"67e55044-10b1-426f-9247-bb680e5fe0c8");
+/// # }
+/// ```
+pub fn write_uuid_hyphenated(text: &mut [u8; 36], id: &Uuid) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    let mut out = text.iter_mut();
+    for (i, &b) in id.as_bytes().iter().enumerate() {
+        if i == 4 || i == 6 || i == 8 || i == 10 {
+            *out.next().unwrap() = b'-';
+        }
+        *out.next().unwrap() = HEX[(b >> 4) as usize];
+        *out.next().unwrap() = HEX[(b & 0xf) as usize];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn byte_orders_round_trip() {
+        let bytes: [u8; 16] = core::array::from_fn(|i| i as u8);
+        let id = read_uuid(&bytes);
+        let mut out = [0; 16];
+        write_uuid(&mut out, &id);
+        assert_eq!(out, bytes);
+        write_uuid_le(&mut out, &id);
+        assert_eq!(out[..4], [3, 2, 1, 0]);
+        assert_eq!(read_uuid_le(&out), id);
+    }
+}
@@ -0,0 +1,119 @@
+//! Fixed-size windows that remember where they are in their parent.
+
+use core::ops::{Deref, DerefMut};
+
+use error::LengthError;
+
+/// An `ArrayWindow` is a mutable array reference to `N` elements of a
+/// larger buffer that remembers its offset and keeps hold of the whole
+/// buffer.  It dereferences to the window itself, while `before`,
+/// `after` and `parent` give read access to the rest of the buffer.
+/// This is what you need to fill in a length or checksum field that
+/// depends on the contents of the rest of the buffer.
+///
+/// ```
+/// use arrayref::ArrayWindow;
+///
+/// // A message with a two-byte checksum field at offset 2.
+/// let mut msg = [0x01, 0x07, 0x00, 0x00, 0xaa, 0xbb, 0xcc];
+/// let mut checksum = ArrayWindow::<_, 2>::new(&mut msg, 2).unwrap();
+/// let sum = checksum
+///     .before()
+///     .iter()
+///     .chain(checksum.after())
+///     .fold(0u16, |s, &b| s.wrapping_add(b as u16));
+/// checksum.commit(sum.to_be_bytes());
+/// assert_eq!(checksum.offset(), 2);
+/// assert_eq!(msg, [0x01, 0x07, 0x02, 0x39, 0xaa, 0xbb, 0xcc]);
+/// ```
+#[derive(Debug)]
+pub struct ArrayWindow<'a, T: 'a, const N: usize> {
+    parent: &'a mut [T],
+    offset: usize,
+}
+
+impl<'a, T, const N: usize> ArrayWindow<'a, T, N> {
+    /// Opens the window over `parent[offset..offset + N]`, or returns a
+    /// `LengthError` if it does not fit.
+    pub fn new(parent: &'a mut [T], offset: usize) -> Result<Self, LengthError> {
+        let required = offset.saturating_add(N);
+        if required > parent.len() {
+            return Err(LengthError::new(required, parent.len()));
+        }
+        Ok(ArrayWindow { parent, offset })
+    }
+
+    /// The offset of the window within its parent.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The length of the parent buffer.
+    pub fn parent_len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// The whole parent buffer, including the window.
+    pub fn parent(&self) -> &[T] {
+        self.parent
+    }
+
+    /// The part of the parent before the window.
+    pub fn before(&self) -> &[T] {
+        &self.parent[..self.offset]
+    }
+
+    /// The part of the parent after the window.
+    pub fn after(&self) -> &[T] {
+        &self.parent[self.offset + N..]
+    }
+
+    /// Overwrites the window with `value`.
+    pub fn commit(&mut self, value: [T; N]) {
+        **self = value;
+    }
+
+    /// Closes the window, returning the parent buffer.
+    pub fn into_parent(self) -> &'a mut [T] {
+        self.parent
+    }
+}
+
+// `new` checked that the window fits in the parent, which cannot change
+// length while the window holds it, so the window needs no further check.
+
+impl<'a, T, const N: usize> Deref for ArrayWindow<'a, T, N> {
+    type Target = [T; N];
+    fn deref(&self) -> &[T; N] {
+        unsafe { &*(self.parent.as_ptr().add(self.offset) as *const [T; N]) }
+    }
+}
+
+impl<'a, T, const N: usize> DerefMut for ArrayWindow<'a, T, N> {
+    fn deref_mut(&mut self) -> &mut [T; N] {
+        unsafe { &mut *(self.parent.as_mut_ptr().add(self.offset) as *mut [T; N]) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn window_stays_in_place() {
+        let mut buf = [0u8; 8];
+        {
+            let mut w = ArrayWindow::<u8, 3>::new(&mut buf, 5).unwrap();
+            assert_eq!(w.parent_len(), 8);
+            assert_eq!(w.before().len(), 5);
+            assert!(w.after().is_empty());
+            w[0] = 1;
+            w.commit([7, 8, 9]);
+            w.into_parent()[0] = 4;
+        }
+        assert_eq!(buf, [4, 0, 0, 0, 0, 7, 8, 9]);
+        let err = ArrayWindow::<u8, 3>::new(&mut buf, 6).unwrap_err();
+        assert_eq!((err.required(), err.available()), (9, 8));
+        assert!(ArrayWindow::<u8, 3>::new(&mut buf, usize::MAX).is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! Clearing fixed-size windows in a way the optimizer must keep.
+
+use core::convert::TryFrom;
+use core::ptr;
+use core::sync::atomic::{compiler_fence, Ordering};
+
+use bounds::sub_slice_mut;
+
+/// Overwrites every byte of `bytes` with zero, using volatile writes
+/// followed by a compiler fence, so that the zeroing is not removed
+/// even if `bytes` is never read again.  This is for clearing key
+/// material and the like before a buffer is dropped or reused.
+///
+/// ```
+/// use arrayref::secure_zero;
+///
+/// let mut key = [0x2bu8; 16];
+/// // ... use the key ...
+/// secure_zero(&mut key);
+/// assert_eq!(key, [0; 16]);
+/// ```
+#[inline(never)]
+pub fn secure_zero<const N: usize>(bytes: &mut [u8; N]) {
+    for b in bytes.iter_mut() {
+        // `b` is a valid, aligned, exclusive reference.
+        unsafe { ptr::write_volatile(b, 0) };
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Zeroes the `N` bytes of `buf` starting at `offset`, as
+/// `secure_zero(array_mut_ref!(buf, offset, N))` would.
+///
+/// **Panics** if the window is out of bounds.
+///
+/// ```
+/// use arrayref::secure_zero_at;
+///
+/// let mut frame = [0xffu8; 24];
+/// secure_zero_at::<16>(&mut frame, 4);
+/// assert_eq!(frame[3..21], [0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff]);
+/// ```
+#[inline]
+pub fn secure_zero_at<const N: usize>(buf: &mut [u8], offset: usize) {
+    secure_zero(<&mut [u8; N]>::try_from(sub_slice_mut(buf, offset, N)).unwrap());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn only_the_window_is_cleared() {
+        let mut buf = [1u8; 6];
+        secure_zero_at::<3>(&mut buf, 2);
+        assert_eq!(buf, [1, 1, 0, 0, 0, 1]);
+        secure_zero_at::<0>(&mut buf, 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn window_is_bounds_checked() {
+        secure_zero_at::<4>(&mut [1; 6], 3);
+    }
+}
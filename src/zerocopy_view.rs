@@ -0,0 +1,74 @@
+//! Typed views of byte regions through `zerocopy`, available with the
+//! `zerocopy` feature.
+
+use bounds::{sub_slice, sub_slice_mut};
+use core::mem::size_of;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+/// Views the `size_of::<T>()` bytes at `offset` as a `T`, for
+/// `struct_ref!`.
+#[doc(hidden)]
+#[inline]
+pub fn struct_ref<T: FromBytes + KnownLayout + Immutable>(bytes: &[u8], offset: usize) -> &T {
+    match T::ref_from_bytes(sub_slice(bytes, offset, size_of::<T>())) {
+        Ok(view) => view,
+        Err(_) => panic!("struct_ref! region is not aligned for the type"),
+    }
+}
+
+/// The mutable version of `struct_ref`, for `struct_mut_ref!`.
+#[doc(hidden)]
+#[inline]
+pub fn struct_mut_ref<T: FromBytes + IntoBytes + KnownLayout>(
+    bytes: &mut [u8],
+    offset: usize,
+) -> &mut T {
+    match T::mut_from_bytes(sub_slice_mut(bytes, offset, size_of::<T>())) {
+        Ok(view) => view,
+        Err(_) => panic!("struct_mut_ref! region is not aligned for the type"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+    #[derive(FromBytes, IntoBytes, KnownLayout, Immutable)]
+    #[repr(C)]
+    struct Header {
+        kind: [u8; 2],
+        len: [u8; 2],
+    }
+
+    #[repr(align(4))]
+    struct Buffer([u8; 12]);
+
+    #[test]
+    fn views_regions_as_structs() {
+        let mut buf = Buffer([0; 12]);
+        {
+            let header: &mut Header = struct_mut_ref!(buf.0, 4, Header);
+            header.kind = [1, 2];
+            header.len = 9u16.to_be_bytes();
+        }
+        assert_eq!(buf.0[4..8], [1, 2, 0, 9]);
+        let header: &Header = struct_ref!(buf.0, 4, Header);
+        assert_eq!(u16::from_be_bytes(header.len), 9);
+        let word: &u32 = struct_ref!(&buf.0[..], 8, u32);
+        assert_eq!(*word, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "not aligned")]
+    fn struct_ref_panics_when_misaligned() {
+        let buf = Buffer([0; 12]);
+        let _: &u32 = struct_ref!(buf.0, 2, u32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn struct_ref_panics_out_of_bounds() {
+        let buf = Buffer([0; 12]);
+        let _: &Header = struct_ref!(buf.0, 10, Header);
+    }
+}